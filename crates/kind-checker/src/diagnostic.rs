@@ -1,7 +1,11 @@
 //! Errors created by the type checker.
 
+use kind_report::codes;
 use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity, Subtitle, Word};
+use std::rc::Rc;
+
 use kind_span::Range;
+use kind_tree::desugared::resugar::Eliminators;
 use kind_tree::desugared::Expr;
 
 use crate::report::Context;
@@ -19,7 +23,15 @@ pub(crate) enum TypeDiagnostic {
     UncoveredPattern(Context, Range, Vec<Box<Expr>>)
 }
 
-fn context_to_subtitles(ctx: &Context, subtitles: &mut Vec<Subtitle>) {
+/// A [TypeDiagnostic] with the eliminators of the book, so its
+/// expressions can be printed in the surface syntax.
+#[derive(Debug)]
+pub(crate) struct TypeError {
+    pub diagnostic: TypeDiagnostic,
+    pub eliminators: Rc<Eliminators>,
+}
+
+fn context_to_subtitles(ctx: &Context, eliminators: &Eliminators, subtitles: &mut Vec<Subtitle>) {
     subtitles.push(Subtitle::LineBreak);
 
     if !ctx.0.is_empty() {
@@ -42,7 +54,7 @@ fn context_to_subtitles(ctx: &Context, subtitles: &mut Vec<Subtitle>) {
             vec![
                 Word::Dimmed(" ".to_string()),
                 Word::White(format!("{:<width$} :", name, width = biggest)),
-                Word::Painted(Color::Snd, typ.to_string()),
+                Word::Painted(Color::Snd, typ.resugar(eliminators).to_string()),
             ],
         ));
         for val in vals {
@@ -51,16 +63,16 @@ fn context_to_subtitles(ctx: &Context, subtitles: &mut Vec<Subtitle>) {
                 vec![
                     Word::Dimmed(" ".to_string()),
                     Word::Dimmed(format!("{:<width$} =", name, width = biggest)),
-                    Word::Dimmed(val.to_string()),
+                    Word::Dimmed(val.resugar(eliminators).to_string()),
                 ],
             ))
         }
     }
 }
 
-impl Diagnostic for TypeDiagnostic {
+impl Diagnostic for TypeError {
     fn get_syntax_ctx(&self) -> Option<kind_span::SyntaxCtxIndex> {
-        match self {
+        match &self.diagnostic {
             TypeDiagnostic::UnboundVariable(_, range) => Some(range.ctx),
             TypeDiagnostic::CantInferHole(_, range) => Some(range.ctx),
             TypeDiagnostic::CantInferLambda(_, range) => Some(range.ctx),
@@ -74,31 +86,33 @@ impl Diagnostic for TypeDiagnostic {
     }
 
     fn to_diagnostic_frame(&self) -> DiagnosticFrame {
-        match self {
+        let eliminators = &*self.eliminators;
+        match &self.diagnostic {
             TypeDiagnostic::TypeMismatch(ctx, range, detected, expected) => {
                 let mut subtitles = vec![
                     Subtitle::Phrase(
                         Color::Fst,
                         vec![
                             Word::White("Got      :".to_string()),
-                            Word::Painted(Color::Fst, expected.to_string()),
+                            Word::Painted(Color::Fst, expected.resugar(eliminators).to_string()),
                         ],
                     ),
                     Subtitle::Phrase(
                         Color::Snd,
                         vec![
                             Word::White("Expected :".to_string()),
-                            Word::Painted(Color::Snd, detected.to_string()),
+                            Word::Painted(Color::Snd, detected.resugar(eliminators).to_string()),
                         ],
                     ),
                 ];
-                context_to_subtitles(ctx, &mut subtitles);
+                context_to_subtitles(ctx, eliminators, &mut subtitles);
                 DiagnosticFrame {
-                    code: 101,
+                    code: codes::TYPE_MISMATCH,
                     severity: Severity::Error,
                     title: "Type mismatch".to_string(),
                     subtitles,
                     hints: vec![],
+                    suggestions: vec![],
                     positions: vec![Marker {
                         position: *range,
                         color: Color::Fst,
@@ -113,18 +127,19 @@ impl Diagnostic for TypeDiagnostic {
                     Color::Snd,
                     vec![
                         Word::White("Expected:".to_string()),
-                        Word::Painted(Color::Snd, expected.to_string()),
+                        Word::Painted(Color::Snd, expected.resugar(eliminators).to_string()),
                     ],
                 )];
 
-                context_to_subtitles(ctx, &mut subtitles);
+                context_to_subtitles(ctx, eliminators, &mut subtitles);
 
                 DiagnosticFrame {
-                    code: 101,
+                    code: codes::INSPECTION,
                     severity: Severity::Info,
                     title: "Inspection.".to_string(),
                     subtitles,
                     hints: vec![],
+                    suggestions: vec![],
                     positions: vec![Marker {
                         position: *range,
                         color: Color::Snd,
@@ -135,7 +150,7 @@ impl Diagnostic for TypeDiagnostic {
                 }
             }
             TypeDiagnostic::ImpossibleCase(_, range, detected, expected) => DiagnosticFrame {
-                code: 101,
+                code: codes::IMPOSSIBLE_CASE,
                 severity: Severity::Error,
                 title: "Impossible case.".to_string(),
                 subtitles: vec![
@@ -143,18 +158,19 @@ impl Diagnostic for TypeDiagnostic {
                         Color::Fst,
                         vec![
                             Word::White("Expected :".to_string()),
-                            Word::Painted(Color::Fst, detected.to_string()),
+                            Word::Painted(Color::Fst, detected.resugar(eliminators).to_string()),
                         ],
                     ),
                     Subtitle::Phrase(
                         Color::Snd,
                         vec![
                             Word::White("Got      :".to_string()),
-                            Word::Painted(Color::Snd, expected.to_string()),
+                            Word::Painted(Color::Snd, expected.resugar(eliminators).to_string()),
                         ],
                     ),
                 ],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -164,11 +180,12 @@ impl Diagnostic for TypeDiagnostic {
                 }],
             },
             TypeDiagnostic::CantInferHole(_, range) => DiagnosticFrame {
-                code: 101,
+                code: codes::CANT_INFER_HOLE,
                 severity: Severity::Error,
                 title: "Can't infer hole.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -178,11 +195,12 @@ impl Diagnostic for TypeDiagnostic {
                 }],
             },
             TypeDiagnostic::InvalidCall(_, range) => DiagnosticFrame {
-                code: 101,
+                code: codes::INVALID_CALL,
                 severity: Severity::Error,
                 title: "Cannot call this".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -192,17 +210,18 @@ impl Diagnostic for TypeDiagnostic {
                 }],
             },
             TypeDiagnostic::UncoveredPattern(_, range, terms) => DiagnosticFrame {
-                code: 101,
+                code: codes::UNCOVERED_PATTERN,
                 severity: Severity::Warning,
                 title: "This function does not cover all the possibilities!".to_string(),
                 subtitles: vec![Subtitle::Phrase(
                     Color::For,
                     vec![
                         Word::White("Missing case :".to_string()),
-                        Word::Painted(Color::For, terms.iter().map(|x| format!("{}", x)).collect::<Vec<_>>().join(" ")),
+                        Word::Painted(Color::For, terms.iter().map(|x| x.resugar(eliminators).to_string()).collect::<Vec<_>>().join(" ")),
                     ],
                 ),],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::For,
@@ -212,11 +231,12 @@ impl Diagnostic for TypeDiagnostic {
                 }],
             },
             TypeDiagnostic::CantInferLambda(_, range) => DiagnosticFrame {
-                code: 101,
+                code: codes::CANT_INFER_LAMBDA,
                 severity: Severity::Error,
                 title: "Can't infer lambda.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -226,11 +246,12 @@ impl Diagnostic for TypeDiagnostic {
                 }],
             },
             TypeDiagnostic::TooManyArguments(_, range) => DiagnosticFrame {
-                code: 101,
+                code: codes::TOO_MANY_ARGUMENTS,
                 severity: Severity::Error,
                 title: "Too many arguments".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -240,11 +261,12 @@ impl Diagnostic for TypeDiagnostic {
                 }],
             },
             TypeDiagnostic::UnboundVariable(_, range) => DiagnosticFrame {
-                code: 101,
+                code: codes::UNBOUND_VARIABLE_IN_CHECKER,
                 severity: Severity::Error,
                 title: "Unbound variable.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -258,7 +280,7 @@ impl Diagnostic for TypeDiagnostic {
 
     fn get_severity(&self) -> Severity {
         use TypeDiagnostic::*;
-        match self {
+        match &self.diagnostic {
             UnboundVariable(_, _)
             | CantInferHole(_, _)
             | CantInferLambda(_, _)
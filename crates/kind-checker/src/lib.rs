@@ -5,29 +5,348 @@
 
 pub mod compiler;
 mod diagnostic;
+pub mod partial;
 pub mod report;
 
+use std::fmt::Display;
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc::Sender;
+use std::sync::OnceLock;
 
+use fxhash::{FxHashMap, FxHashSet};
+use hvm::runtime::{fun, Function, ReduceCtx};
 use hvm::{language, get_cost};
-use hvm::{runtime, syntax::Term};
+use hvm::runtime;
+use hvm::syntax::{File, Term};
 use kind_report::data::Diagnostic;
-use kind_tree::desugared::Book;
+use diagnostic::TypeError;
+use kind_span::Range;
+use kind_tree::desugared::resugar::Eliminators;
+use kind_tree::desugared::{Book, Expr};
 use report::parse_report;
 
 pub const CHECKER: &str = include_str!("checker.hvm");
 
-pub fn eval(file: &str, term: &str, dbug: bool, tids: Option<usize>) -> Result<(Box<Term>, u64), String> {
-    let file = language::syntax::read_file(&format!("{}\nHVM_MAIN_CALL = {}", file, term))?;
+/// Rules that are added to the checker that records the type of each
+/// expression. They take the place of `Kind.Checker.check` and
+/// `Kind.Checker.infer`, whose rules are renamed to `.go`, so every
+/// recursive call goes through them.
+const TYPES: &str = include_str!("types.hvm");
+
+/// Location of the term that the evaluations normalize.
+const HOST: u64 = 0;
+
+/// Number of nodes at the end of the area of each thread that are
+/// kept free when the heap is limited. A function is only applied
+/// while the allocator is before them, so the rewrites that happen
+/// after the last one never make it run out of space (the HVM
+/// allocator spins forever when it does).
+const HEAP_GUARD: usize = 1 << 16;
+
+/// Limits that stop an evaluation before it finishes. Both of them
+/// are disabled by default, so the evaluation runs until it reaches
+/// the normal form.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvalLimits {
+    /// Maximum number of rewrites that the evaluation can perform.
+    /// Every rewrite is counted, but the limit is only checked before
+    /// a function is applied (see [limit_program]).
+    pub max_rewrites: Option<u64>,
+    /// Size of the heap in 64-bit nodes. If it's not given then
+    /// it uses [runtime::default_heap_size]. The HVM allocator only
+    /// reuses the nodes that were freed after it reaches the end of
+    /// the heap, so a limited heap bounds every node that the
+    /// evaluation allocates.
+    ///
+    /// The heap that is allocated has [HEAP_GUARD] more nodes for
+    /// each thread than this size, which the evaluation never uses
+    /// for its values: they are kept free for the rewrites that happen
+    /// after it was stopped.
+    pub heap_size: Option<usize>,
+}
+
+impl EvalLimits {
+    fn is_unlimited(&self) -> bool {
+        self.max_rewrites.is_none() && self.heap_size.is_none()
+    }
+
+    /// The size of the heap with the [HEAP_GUARD] of each thread.
+    fn real_heap_size(&self, tids: usize) -> usize {
+        match self.heap_size {
+            Some(size) => size + HEAP_GUARD * tids,
+            None => runtime::default_heap_size(),
+        }
+    }
+}
+
+/// Errors that can happen while running a program in the HVM.
+#[derive(Debug)]
+pub enum EvalError {
+    /// The program cannot be read by the HVM.
+    Syntax(String),
+    /// The evaluation was stopped after crossing the rewrite limit. It
+    /// contains the limit and the number of rewrites that were performed.
+    /// The partial value of the evaluation is dropped.
+    RewriteLimit(u64, u64),
+    /// The evaluation was stopped because the heap ran out of space.
+    /// It contains the heap size and the number of rewrites that
+    /// were performed. The partial value of the evaluation is dropped.
+    HeapLimit(usize, u64),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::Syntax(err) => write!(f, "{}", err),
+            EvalError::RewriteLimit(limit, rewrites) => {
+                write!(
+                    f,
+                    "the evaluation crossed the limit of {} rewrites after {} rewrites and its value was dropped",
+                    limit, rewrites
+                )
+            }
+            EvalError::HeapLimit(size, rewrites) => {
+                write!(
+                    f,
+                    "the evaluation ran out of the heap of {} nodes after {} rewrites and its value was dropped",
+                    size, rewrites
+                )
+            }
+        }
+    }
+}
+
+/// The limits of an evaluation as they are read by the functions of a
+/// limited program.
+#[derive(Clone, Copy)]
+struct Guard {
+    /// Distance between the id of a function and the id where its
+    /// original definition is kept.
+    offset: u64,
+    max_rewrites: u64,
+    check_heap: bool,
+}
+
+/// A program with the guard of its limits next to it. The functions
+/// of HVM are plain function pointers that run in the threads spawned
+/// by [runtime::reduce], so they cannot capture the limits. They only
+/// receive the program, and the functions installed by [limit_program]
+/// find the guard through it, because the program is the first field.
+#[repr(C)]
+struct LimitedProgram {
+    prog: runtime::Program,
+    /// It's only present when the functions check the limits.
+    guard: Option<Guard>,
+}
+
+impl LimitedProgram {
+    /// Prepares the program to run with the `limits`. The functions
+    /// are only replaced by the ones that check the limits when one of
+    /// them is configured, so an unlimited evaluation runs the program
+    /// as it is.
+    fn new(mut prog: runtime::Program, limits: EvalLimits) -> LimitedProgram {
+        let guard = if limits.is_unlimited() {
+            None
+        } else {
+            Some(Guard::new(limit_program(&mut prog), limits))
+        };
+        LimitedProgram { prog, guard }
+    }
+
+    /// The guard of the program that is being reduced by `ctx`.
+    ///
+    /// It can only be called by the functions installed by
+    /// [limit_program], which only run inside of a [LimitedProgram].
+    fn guard_of(ctx: &ReduceCtx) -> Guard {
+        // SAFETY: the program is the first field of a `repr(C)`
+        // struct, so a pointer to it is a pointer to the struct.
+        let limited = unsafe { &*(ctx.prog as *const runtime::Program as *const LimitedProgram) };
+        limited
+            .guard
+            .expect("Internal Error: a limited function runs without a guard")
+    }
+}
+
+impl Guard {
+    fn new(offset: usize, limits: EvalLimits) -> Guard {
+        Guard {
+            offset: offset as u64,
+            max_rewrites: limits.max_rewrites.unwrap_or(u64::MAX),
+            check_heap: limits.heap_size.is_some(),
+        }
+    }
+
+    /// Checks if one of the limits was crossed, so no other
+    /// function can be applied.
+    fn crossed(&self, heap: &runtime::Heap, tid: usize) -> bool {
+        let lvar = &heap.lvar[tid];
+        get_cost(heap) > self.max_rewrites
+            || (self.check_heap
+                && lvar.next.load(Ordering::Relaxed) as usize + HEAP_GUARD >= lvar.amax.load(Ordering::Relaxed) as usize)
+    }
+
+    /// Checks the limits before a function is visited or applied.
+    /// When one of them was crossed, the term that is being evaluated
+    /// is erased, so the reducer does not walk through what was built
+    /// until now to normalize it. The partial value is lost, and the
+    /// errors of the limits say so.
+    fn stopped(&self, ctx: &ReduceCtx) -> bool {
+        let crossed = self.crossed(ctx.heap, ctx.tid);
+        if crossed {
+            runtime::link(ctx.heap, HOST, runtime::Era());
+        }
+        crossed
+    }
+
+    fn original<'a>(&self, ctx: &ReduceCtx<'a>) -> Option<&'a Function> {
+        ctx.prog.funs.get(&(runtime::get_ext(ctx.term) + self.offset))
+    }
+}
+
+fn limited_visit(ctx: ReduceCtx) -> bool {
+    let guard = LimitedProgram::guard_of(&ctx);
+    if guard.stopped(&ctx) {
+        return false;
+    }
+    match guard.original(&ctx) {
+        Some(Function::Interpreted { visit, .. }) => fun::visit(ctx, &visit.strict_idx),
+        _ => false,
+    }
+}
+
+/// Applies a function unless a limit was crossed. A function that is
+/// not applied is left as it is, so the reducer runs out of work and
+/// the evaluation stops by itself.
+fn limited_apply(ctx: ReduceCtx) -> bool {
+    let guard = LimitedProgram::guard_of(&ctx);
+    if guard.stopped(&ctx) {
+        return false;
+    }
+    match guard.original(&ctx) {
+        Some(Function::Interpreted { visit, apply, .. }) => {
+            let fid = runtime::get_ext(ctx.term);
+            fun::apply(ctx, fid, visit, apply)
+        }
+        _ => false,
+    }
+}
+
+/// Makes every function of the program check the limits before it
+/// is applied, and returns the distance to the ids where the original
+/// definitions were moved, that no term uses.
+///
+/// Only the function rewrites are stopped: the other rewrites are
+/// counted, but the lambda applications, duplications and numeric
+/// operations happen inside the reducer and cannot be intercepted.
+/// An evaluation that loops only through lambda applications, without
+/// applying any function, is not stopped by the limits.
+fn limit_program(prog: &mut runtime::Program) -> usize {
+    let offset = prog.funs.data.len().max(prog.aris.data.len()).max(prog.nams.data.len());
+
+    for fid in 0..prog.funs.data.len() {
+        let smap = match &prog.funs.data[fid] {
+            Some(Function::Interpreted { smap, .. }) => smap.clone(),
+            _ => continue,
+        };
+        let original = prog.funs.data[fid].take().unwrap();
+        prog.funs.insert((fid + offset) as u64, original);
+        prog.funs.insert(
+            fid as u64,
+            Function::Compiled {
+                smap,
+                visit: limited_visit,
+                apply: limited_apply,
+            },
+        );
+    }
+
+    offset
+}
+
+/// Normalizes the term at `host` and checks if the evaluation was
+/// stopped by one of the `limits` of the program.
+fn normalize_with_limits(
+    heap: &runtime::Heap,
+    limited: &LimitedProgram,
+    tids: &[usize],
+    host: u64,
+    dbug: bool,
+    limits: EvalLimits,
+) -> Result<(), EvalError> {
+    let prog = &limited.prog;
+    let guard = match limited.guard {
+        Some(guard) => guard,
+        None => {
+            runtime::normalize(heap, prog, tids, host, dbug);
+            return Ok(());
+        }
+    };
+
+    // The same loop as [runtime::normalize], that reduces the term
+    // again until there's nothing else to rewrite, but it stops after
+    // the first pass that crosses a limit.
+    let mut cost = get_cost(heap);
+
+    loop {
+        runtime::reduce(heap, prog, tids, host, true, dbug);
+
+        let new_cost = get_cost(heap);
+
+        if let Some(max) = limits.max_rewrites {
+            if new_cost > max {
+                return Err(EvalError::RewriteLimit(max, new_cost));
+            }
+        }
+
+        if let Some(size) = limits.heap_size {
+            if (0..tids.len()).any(|tid| guard.crossed(heap, tid)) {
+                return Err(EvalError::HeapLimit(size, new_cost));
+            }
+        }
+
+        if new_cost == cost {
+            return Ok(());
+        }
+
+        cost = new_cost;
+    }
+}
+
+/// Prepares an HVM program to run with the `limits`.
+fn load_program(file: &File, limits: EvalLimits) -> (LimitedProgram, language::rulebook::RuleBook) {
+    let book = language::rulebook::gen_rulebook(file);
 
-    let book = language::rulebook::gen_rulebook(&file);
     let mut prog = runtime::Program::new();
     prog.add_book(&book);
-    let size = runtime::default_heap_size();
+
+    (LimitedProgram::new(prog, limits), book)
+}
+
+pub fn eval(file: &str, term: &str, dbug: bool, tids: Option<usize>) -> Result<(Box<Term>, u64), String> {
+    eval_with_limits(file, term, dbug, tids, EvalLimits::default()).map_err(|err| err.to_string())
+}
+
+/// Same as [eval] but it stops the evaluation if it crosses one of
+/// the `limits`.
+pub fn eval_with_limits(
+    file: &str,
+    term: &str,
+    dbug: bool,
+    tids: Option<usize>,
+    limits: EvalLimits,
+) -> Result<(Box<Term>, u64), EvalError> {
+    let file = language::syntax::read_file(&format!("{}\nHVM_MAIN_CALL = {}", file, term)).map_err(EvalError::Syntax)?;
+    eval_file(&file, dbug, tids, limits)
+}
+
+/// Evaluates the `HVM_MAIN_CALL` rule of a file that was already read.
+fn eval_file(file: &File, dbug: bool, tids: Option<usize>, limits: EvalLimits) -> Result<(Box<Term>, u64), EvalError> {
+    let (prog, book) = load_program(file, limits);
 
     let tids = tids.unwrap_or(1);
 
-    let heap = runtime::new_heap(size, tids);
+    let heap = runtime::new_heap(limits.real_heap_size(tids), tids);
     let tids = runtime::new_tids(tids);
 
     runtime::link(
@@ -36,10 +355,10 @@ pub fn eval(file: &str, term: &str, dbug: bool, tids: Option<usize>) -> Result<(
         runtime::Fun(*book.name_to_id.get("HVM_MAIN_CALL").unwrap(), 0),
     );
 
-    let host = 0;
-    runtime::normalize(&heap, &prog, &tids, host, dbug);
-    let code = language::readback::as_term(&heap, &prog, host);
-    runtime::collect(&heap, &prog.aris, tids[0], runtime::load_ptr(&heap, host));
+    let host = HOST;
+    normalize_with_limits(&heap, &prog, &tids, host, dbug, limits)?;
+    let code = language::readback::as_term(&heap, &prog.prog, host);
+    runtime::collect(&heap, &prog.prog.aris, tids[0], runtime::load_ptr(&heap, host));
     runtime::free(&heap, 0, 0, 1);
     Ok((code, get_cost(&heap)))
 
@@ -53,58 +372,188 @@ pub fn gen_checker(book: &Book, check_coverage: bool, functions_to_check: Vec<St
     checker
 }
 
-/// Type checks a dessugared book. It spawns an HVM instance in order
-/// to run a compiled version of the book
-pub fn type_check(
+/// The checker that is read by HVM, or the [recording_checker] if
+/// `record_types` is set. Reading it takes most of the time of the
+/// check of a small book, so each one is only read once.
+fn read_checker(record_types: bool) -> &'static File {
+    static PLAIN: OnceLock<File> = OnceLock::new();
+    static RECORDING: OnceLock<File> = OnceLock::new();
+
+    let read = |code: &str| language::syntax::read_file(code).expect("Internal Error: Cannot read the checker");
+
+    if record_types {
+        RECORDING.get_or_init(|| read(&recording_checker()))
+    } else {
+        PLAIN.get_or_init(|| read(CHECKER))
+    }
+}
+
+/// The same file as [gen_checker], or with the [recording_checker], but
+/// already read by HVM and with the rule `HVM_MAIN_CALL` that evaluates
+/// `term`. Only the compiled book is read, and the rules of the checker
+/// are copied.
+fn read_checker_with(
+    book: &Book,
+    check_coverage: bool,
+    record_types: bool,
+    functions_to_check: Vec<String>,
+    term: &str,
+) -> Result<File, EvalError> {
+    let code = compiler::codegen_book(book, check_coverage, functions_to_check);
+    let book = language::syntax::read_file(&format!("{}\nHVM_MAIN_CALL = {}", code, term)).map_err(EvalError::Syntax)?;
+
+    let checker = read_checker(record_types);
+
+    Ok(File {
+        rules: checker.rules.iter().cloned().chain(book.rules).collect(),
+        smaps: checker.smaps.iter().cloned().chain(book.smaps).collect(),
+    })
+}
+
+/// The checker with the [TYPES] rules, so it reports the type of
+/// every expression that it visits. The plain checker does not pay
+/// for them.
+fn recording_checker() -> String {
+    let mut checker = String::new();
+    for line in CHECKER.lines() {
+        let renamed = ["Kind.Checker.check", "Kind.Checker.infer"].iter().find_map(|name| {
+            let rule = line.strip_prefix(&format!("({} ", name))?;
+            Some(format!("({}.go {}", name, rule))
+        });
+        checker.push_str(&renamed.unwrap_or_else(|| line.to_string()));
+        checker.push('\n');
+    }
+    checker.push_str(TYPES);
+    checker
+}
+
+/// Runs the checker and sends the errors that it found to `tx`. It
+/// returns the number of rewrites (if there was no error) and the types
+/// that the checker recorded.
+fn run_checker(
     book: &Book,
     tx: Sender<Box<dyn Diagnostic>>,
     functions_to_check: Vec<String>,
     check_coverage: bool,
-    tids: Option<usize>
-) -> Option<u64> {
-    let file = gen_checker(book, check_coverage, functions_to_check);
+    record_types: bool,
+    tids: Option<usize>,
+) -> (Option<u64>, Vec<(Range, Box<Expr>)>) {
+    let file = read_checker_with(book, check_coverage, record_types, functions_to_check, "Main");
 
-    match eval(&file, "Main", false, tids) {
+    match file.and_then(|file| eval_file(&file, false, tids, EvalLimits::default())) {
         Ok((term, rewrites)) => {
-            let errs = parse_report(&term).unwrap_or_else(|_| {
+            let report = parse_report(&term).unwrap_or_else(|_| {
                 panic!(
                     "Internal Error: Cannot parse the report message from the type checker: {}",
                     term
                 )
             });
 
-            let succeeded = errs.is_empty();
+            let succeeded = report.errors.is_empty();
+            let eliminators = Rc::new(Eliminators::from_book(book));
 
-            for err in errs {
-                tx.send(Box::new(err)).unwrap()
+            for diagnostic in report.errors {
+                tx.send(Box::new(TypeError {
+                    diagnostic,
+                    eliminators: eliminators.clone(),
+                }))
+                .unwrap()
             }
 
-            if succeeded {
-                Some(rewrites)
-            } else {
-                None
-            }
+            (succeeded.then_some(rewrites), report.types)
         }
         Err(res) => panic!("{}", res),
     }
 }
 
+/// Type checks a dessugared book. It spawns an HVM instance in order
+/// to run a compiled version of the book
+pub fn type_check(
+    book: &Book,
+    tx: Sender<Box<dyn Diagnostic>>,
+    functions_to_check: Vec<String>,
+    check_coverage: bool,
+    tids: Option<usize>
+) -> Option<u64> {
+    run_checker(book, tx, functions_to_check, check_coverage, false, tids).0
+}
+
+/// The type that the checker inferred for the expression
+/// at `range`, printed in the surface syntax.
+#[derive(Debug, Clone)]
+pub struct TypeInfo {
+    pub range: Range,
+    pub typ: String,
+}
+
+/// Type checks a dessugared book recording the type of each
+/// expression that has a position in the source code. The errors
+/// are sent to `tx` like in [type_check]. The result is sorted by
+/// position and there's only one type for each range.
+pub fn infer_types(
+    book: &Book,
+    tx: Sender<Box<dyn Diagnostic>>,
+    functions_to_check: Vec<String>,
+    tids: Option<usize>,
+) -> Vec<TypeInfo> {
+    let (_, types) = run_checker(book, tx, functions_to_check, false, true, tids);
+
+    let eliminators = Eliminators::from_book(book);
+    let mut constructors = constructor_types(book);
+    let mut seen = FxHashSet::default();
+    let mut infos = Vec::new();
+
+    // The last type recorded for a range comes first in the report and
+    // it's the most complete one, e.g. the type of a whole application
+    // instead of the type of its head that shares the same range.
+    for (range, typ) in types {
+        if range.start != range.end && seen.insert(range) {
+            let typ = constructors.remove(&range).unwrap_or(typ);
+            infos.push(TypeInfo {
+                range,
+                typ: typ.resugar(&eliminators).to_string(),
+            });
+        }
+    }
+
+    infos.sort_by_key(|info| (info.range.ctx, info.range.start, info.range.end));
+    infos
+}
+
+/// The type of each constructor at the range of its name. The checker
+/// records the type of the signature there, which is always `Type`.
+fn constructor_types(book: &Book) -> FxHashMap<Range, Box<Expr>> {
+    book.families
+        .values()
+        .flat_map(|family| &family.constructors)
+        .filter_map(|name| book.entrs.get(name.to_str()))
+        .map(|entry| {
+            let typ = entry.args.iter().rev().fold(entry.typ.clone(), |body, arg| {
+                Expr::all(arg.range, arg.name.clone(), arg.typ.clone(), body, arg.erased)
+            });
+            (entry.range, typ)
+        })
+        .collect()
+}
+
 /// Runs the type checker but instead of running the check all function
 /// we run the "eval_main" that runs the generated version that both HVM and
 /// and the checker can understand.
-pub fn eval_api(book: &Book) -> (String, u64) {
-
-    let file = gen_checker(book, false, Vec::new());
+pub fn eval_api(book: &Book) -> Result<(String, u64), EvalError> {
+    eval_api_with_limits(book, EvalLimits::default())
+}
 
-    let file = language::syntax::read_file(&file).unwrap();
+/// Same as [eval_api] but it stops the evaluation if it crosses one of
+/// the `limits`.
+pub fn eval_api_with_limits(book: &Book, limits: EvalLimits) -> Result<(String, u64), EvalError> {
 
-    let book = language::rulebook::gen_rulebook(&file);
+    let file = read_checker_with(book, false, false, Vec::new(), "Kind.API.eval_main")?;
 
-    let mut prog = runtime::Program::new();
-    prog.add_book(&book);
+    let (prog, book) = load_program(&file, limits);
 
-    let heap = runtime::new_heap(runtime::default_heap_size(), runtime::default_heap_tids());
-    let tids = runtime::new_tids(runtime::default_heap_tids());
+    let tids = runtime::default_heap_tids();
+    let heap = runtime::new_heap(limits.real_heap_size(tids), tids);
+    let tids = runtime::new_tids(tids);
 
     // Allocates the main term
     runtime::link(
@@ -112,17 +561,17 @@ pub fn eval_api(book: &Book) -> (String, u64) {
         0,
         runtime::Fun(*book.name_to_id.get("Kind.API.eval_main").unwrap(), 0),
     );
-    let host = 0;
+    let host = HOST;
 
     // Normalizes it
-    runtime::normalize(&heap, &prog, &tids, host, false);
+    normalize_with_limits(&heap, &prog, &tids, host, false, limits)?;
 
     // Reads it back to a string
-    let term = language::readback::as_string(&heap, &prog, &tids, host).unwrap();
+    let term = language::readback::as_string(&heap, &prog.prog, &tids, host).unwrap();
 
     // Frees used memory
-    runtime::collect(&heap, &prog.aris, tids[0], runtime::load_ptr(&heap, host));
+    runtime::collect(&heap, &prog.prog.aris, tids[0], runtime::load_ptr(&heap, host));
     runtime::free(&heap, 0, 0, 1);
 
-    (term, get_cost(&heap))
+    Ok((term, get_cost(&heap)))
 }
@@ -0,0 +1,118 @@
+//! Removes the definitions that cannot be checked from a book so
+//! the checker can still report the errors of the other ones. A
+//! definition cannot be checked if it had some error in a previous
+//! stage, if it contains an [ExprKind::Err] node or if it uses a
+//! definition that cannot be checked.
+
+use fxhash::FxHashSet;
+use kind_tree::desugared::{Book, Entry, Expr, ExprKind};
+
+#[derive(Default)]
+struct References {
+    names: FxHashSet<String>,
+    has_err: bool,
+}
+
+impl References {
+    fn collect_expr(&mut self, expr: &Expr) {
+        use ExprKind::*;
+        match &expr.data {
+            All { typ, body, .. } => {
+                self.collect_expr(typ);
+                self.collect_expr(body);
+            }
+            Lambda { body, .. } => self.collect_expr(body),
+            App { fun, args } => {
+                self.collect_expr(fun);
+                for arg in args {
+                    self.collect_expr(&arg.data);
+                }
+            }
+            Fun { name, args } | Ctr { name, args } => {
+                self.names.insert(name.to_string());
+                for arg in args {
+                    self.collect_expr(arg);
+                }
+            }
+            Let { val, next, .. } => {
+                self.collect_expr(val);
+                self.collect_expr(next);
+            }
+            Ann { expr, typ } => {
+                self.collect_expr(expr);
+                self.collect_expr(typ);
+            }
+            Sub { expr, .. } => self.collect_expr(expr),
+            Binary { left, right, .. } => {
+                self.collect_expr(left);
+                self.collect_expr(right);
+            }
+            Err => self.has_err = true,
+            Var { .. } | Typ | NumTypeU60 | NumTypeF60 | NumU60 { .. } | NumF60 { .. }
+            | Str { .. } | Hole { .. } | Hlp(_) => (),
+        }
+    }
+
+    fn collect_entry(&mut self, entry: &Entry) {
+        for arg in &entry.args {
+            self.collect_expr(&arg.typ);
+        }
+
+        self.collect_expr(&entry.typ);
+
+        for rule in &entry.rules {
+            for pat in &rule.pats {
+                self.collect_expr(pat);
+            }
+            self.collect_expr(&rule.body);
+        }
+    }
+}
+
+/// Removes the `broken` definitions and every definition that depends
+/// on them from the book. It returns the names of all of the definitions
+/// that were removed.
+pub fn remove_broken_entries(book: &mut Book, broken: &FxHashSet<String>) -> FxHashSet<String> {
+    let dependencies: Vec<(String, References)> = book
+        .entrs
+        .iter()
+        .map(|(name, entry)| {
+            let mut references = References::default();
+            references.collect_entry(entry);
+
+            // A type cannot be checked without all of its constructors.
+            if let Some(family) = book.families.get(name) {
+                references.names.extend(family.constructors.iter().map(|x| x.to_string()));
+            }
+
+            (name.clone(), references)
+        })
+        .collect();
+
+    let mut removed = broken.clone();
+
+    for (name, references) in &dependencies {
+        if references.has_err {
+            removed.insert(name.clone());
+        }
+    }
+
+    // Propagates until no other definition depends on a removed one.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (name, references) in &dependencies {
+            if !removed.contains(name) && references.names.iter().any(|x| removed.contains(x)) {
+                removed.insert(name.clone());
+                changed = true;
+            }
+        }
+    }
+
+    for name in &removed {
+        book.entrs.remove(name);
+        book.families.remove(name);
+    }
+
+    removed
+}
@@ -265,13 +265,28 @@ fn parse_type_error(expr: &Term) -> Result<TypeDiagnostic, String> {
     }
 }
 
-pub(crate) fn parse_report(expr: &Term) -> Result<Vec<TypeDiagnostic>, String> {
+/// The answer of the type checker. The types are only reported
+/// when the checker is generated with `record_types`.
+pub(crate) struct Report {
+    pub errors: Vec<TypeDiagnostic>,
+    pub types: Vec<(Range, Box<Expr>)>,
+}
+
+pub(crate) fn parse_report(expr: &Term) -> Result<Report, String> {
     let args = parse_list(expr)?;
-    let mut errs = Vec::new();
+    let mut report = Report {
+        errors: Vec::new(),
+        types: Vec::new(),
+    };
 
     for arg in args {
-        errs.push(parse_type_error(&arg)?);
+        match &*arg {
+            Term::Ctr { name, args } if name == "Kind.Error.Quoted.typed" => {
+                report.types.push((parse_orig(&args[0])?, parse_expr(&args[1])?))
+            }
+            _ => report.errors.push(parse_type_error(&arg)?),
+        }
     }
 
-    Ok(errs)
+    Ok(report)
 }
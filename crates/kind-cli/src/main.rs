@@ -1,13 +1,21 @@
 use std::path::PathBuf;
+use std::sync::mpsc::Sender;
 use std::time::Instant;
-use std::{fmt, io};
+use std::{fmt, fs, io};
 
-use clap::{Parser, Subcommand};
+use clap::builder::PossibleValuesParser;
+use clap::error::ErrorKind;
+use clap::{Arg, CommandFactory, FromArgMatches, Parser, Subcommand};
 use driver::resolution::ResolutionError;
+use kind_checker::{EvalLimits, TypeInfo};
+use kind_driver::backend::{self, BackendOptions, Flag};
 use kind_driver::session::Session;
+use kind_lint::Level;
 
-use kind_report::data::{Diagnostic, Log, Severity};
-use kind_report::report::{FileCache, Report};
+use kind_report::data::{collect_diagnostics, Diagnostic, Log, Severity};
+use kind_report::explain;
+use kind_report::report::{line_and_column, parse_position, FileCache, Report};
+use kind_report::sarif::render_sarif;
 use kind_report::RenderConfig;
 
 use kind_driver as driver;
@@ -30,6 +38,18 @@ pub struct Cli {
     #[arg(short, long)]
     pub warning: bool,
 
+    /// Turns off a lint
+    #[arg(long, value_name = "LINT", value_parser = PossibleValuesParser::new(kind_lint::lint_names()))]
+    pub allow: Vec<String>,
+
+    /// Reports a lint as a warning (shown with --warning)
+    #[arg(long, value_name = "LINT", value_parser = PossibleValuesParser::new(kind_lint::lint_names()))]
+    pub warn: Vec<String>,
+
+    /// Reports a lint as an error
+    #[arg(long, value_name = "LINT", value_parser = PossibleValuesParser::new(kind_lint::lint_names()))]
+    pub deny: Vec<String>,
+
     /// Disable colors in error messages
     #[arg(short, long)]
     pub no_color: bool,
@@ -38,6 +58,17 @@ pub struct Cli {
     #[arg(long)]
     pub tids: Option<usize>,
 
+    /// Maximum number of rewrites that `run`, `eval` and the
+    /// derivations written in Kind can perform before being stopped
+    #[arg(long)]
+    pub max_rewrites: Option<u64>,
+
+    /// Size of the heap (in 64-bit nodes) used by `run`, `eval` and
+    /// the derivations written in Kind. Each thread gets 65536 more
+    /// nodes that are kept free to stop the evaluation
+    #[arg(long)]
+    pub heap_size: Option<usize>,
+
     /// Prints all of the functions and their evaluation
     #[arg(short, long)]
     pub trace: bool,
@@ -46,6 +77,10 @@ pub struct Cli {
     #[arg(short, long)]
     pub ascii: bool,
 
+    /// Also writes the diagnostics to a file in the SARIF format
+    #[arg(long, value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
     /// Entrypoint of the file that makes the erasure checker
     /// not remove the entry.
     #[arg(short, long)]
@@ -94,20 +129,144 @@ pub enum Command {
 
     /// Stringifies a file
     #[clap(aliases = &["s"])]
-    Show { file: String },
+    Show {
+        /// Only shows the definitions of the file, each one followed by
+        /// the definitions that its derivations generate
+        #[arg(long)]
+        expanded: bool,
+
+        file: String,
+    },
+
+    /// Applies the fixes that the compiler suggests in place
+    Fix { file: String },
+
+    /// Shows the type of each expression of a file
+    #[clap(aliases = &["t"])]
+    Types {
+        file: String,
+        /// Only shows the innermost expression at a position
+        #[arg(long, value_name = "LINE:COL", value_parser = parse_position)]
+        at: Option<(usize, usize)>,
+    },
+
+    /// Shows a long explanation of an error code, e.g. E0209
+    Explain { code: String },
+
+    /// Compiles a file to one of the targets
+    #[clap(aliases = &["b"])]
+    Build {
+        file: String,
+        /// The target that the file is going to be compiled to.
+        #[arg(short, long, value_parser = PossibleValuesParser::new(driver::backend::backend_names()))]
+        target: String,
+        /// The flags of the target, that are added by [parse_cli].
+        #[arg(skip)]
+        flags: Vec<(String, String)>,
+    },
 
     /// Compiles a file to Kindelia (.kdl)
     #[clap(aliases = &["kdl"])]
     ToKDL {
         file: String,
-        /// If given, a namespace that goes before each compiled name. Can be at most 10 charaters long.
-        #[clap(long, aliases = &["ns"])]
-        namespace: Option<String>,
+        /// The flags of the kdl target, that are added by [parse_cli].
+        #[arg(skip)]
+        flags: Vec<(String, String)>,
     },
 
     /// Compiles a file to HVM (.hvm)
     #[clap(aliases = &["hvm"])]
-    ToHVM { file: String },
+    ToHVM {
+        file: String,
+        /// The flags of the hvm target, that are added by [parse_cli].
+        #[arg(skip)]
+        flags: Vec<(String, String)>,
+    },
+}
+
+/// The commands that compile with backends and the names of the
+/// backends whose flags they accept. `build` accepts the flags of
+/// all of them.
+fn backend_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![
+        ("build", backend::backend_names()),
+        ("to-kdl", vec!["kdl"]),
+        ("to-hvm", vec!["hvm"]),
+    ]
+}
+
+fn flag_to_arg(flag: &Flag) -> Arg {
+    let mut arg = Arg::new(flag.name)
+        .long(flag.name)
+        .aliases(flag.aliases)
+        .help(flag.help)
+        .value_name("VALUE");
+    if let Some(values) = flag.values {
+        arg = arg.value_parser(PossibleValuesParser::new(values));
+    }
+    if let Some(value) = flag.default_missing {
+        arg = arg.num_args(0..=1).default_missing_value(value);
+    }
+    arg
+}
+
+/// The flags of the `backends` without repetitions, so the backends
+/// can share the flags that have the same name.
+fn backend_flags(backends: &[&str]) -> Vec<Flag> {
+    let mut flags: Vec<Flag> = Vec::new();
+    for name in backends {
+        let backend = backend::find_backend(name).expect("the backend should exist");
+        for flag in backend.flags() {
+            if flags.iter().all(|other| other.name != flag.name) {
+                flags.push(flag);
+            }
+        }
+    }
+    flags
+}
+
+/// Parses the command line like [Cli::parse], with the flags of the
+/// backends added to the commands that compile with them. A flag of
+/// a backend that is not the one selected by the command is an error.
+pub fn parse_cli() -> Cli {
+    let mut command = Cli::command();
+    for (name, backends) in backend_commands() {
+        let args: Vec<_> = backend_flags(&backends).iter().map(flag_to_arg).collect();
+        command = command.mut_subcommand(name, |sub| sub.args(args));
+    }
+
+    let matches = command.get_matches_mut();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    let (name, sub) = match matches.subcommand() {
+        Some(subcommand) => subcommand,
+        None => return cli,
+    };
+
+    let (target, flags) = match &mut cli.command {
+        Command::Build { target, flags, .. } => (target.clone(), flags),
+        Command::ToKDL { flags, .. } => ("kdl".to_string(), flags),
+        Command::ToHVM { flags, .. } => ("hvm".to_string(), flags),
+        _ => return cli,
+    };
+
+    let accepted = backend_flags(&[target.as_str()]);
+
+    for (_, backends) in backend_commands().into_iter().filter(|(command, _)| *command == name) {
+        for flag in backend_flags(&backends) {
+            let value = match sub.get_one::<String>(flag.name) {
+                Some(value) => value,
+                None => continue,
+            };
+            if accepted.iter().all(|other| other.name != flag.name) {
+                let message = format!("the flag '--{}' is not used by the target '{}'", flag.name, target);
+                command.error(ErrorKind::ArgumentConflict, message).exit();
+            }
+            flags.push((flag.name.to_string(), value.clone()));
+        }
+    }
+
+    cli
 }
 
 /// Helper structure to use stderr as fmt::Write
@@ -122,6 +281,28 @@ where
     }
 }
 
+/// Options of the command line that every session receives.
+#[derive(Debug, Clone, Default)]
+pub struct SessionSettings {
+    pub warnings: bool,
+    pub lint_levels: Vec<(String, Level)>,
+    /// File that receives the diagnostics in the SARIF format.
+    pub sarif: Option<PathBuf>,
+    /// Limits of the evaluations, including the ones of the
+    /// derivations that run while the files are loaded.
+    pub limits: EvalLimits,
+}
+
+impl SessionSettings {
+    pub fn new_session(&self, root: PathBuf, sender: Sender<Box<dyn Diagnostic>>) -> Session {
+        let mut session = Session::new(root, sender);
+        session.emit_warnings = self.warnings;
+        session.lint_levels.extend(self.lint_levels.iter().cloned());
+        session.eval_limits = self.limits;
+        session
+    }
+}
+
 pub fn render_to_stderr<T, E>(render_config: &RenderConfig, session: &T, err: &E)
 where
     T: FileCache,
@@ -139,13 +320,14 @@ where
 pub fn compile_in_session<T>(
     render_config: &RenderConfig,
     root: PathBuf,
+    settings: &SessionSettings,
     file: String,
     compiled: bool,
     fun: &mut dyn FnMut(&mut Session) -> anyhow::Result<T>,
 ) -> anyhow::Result<T> {
     let (rx, tx) = std::sync::mpsc::channel();
 
-    let mut session = Session::new(root, rx);
+    let mut session = settings.new_session(root, rx);
 
     eprintln!();
 
@@ -159,18 +341,31 @@ pub fn compile_in_session<T>(
 
     let res = fun(&mut session);
 
-    let diagnostics = tx.try_iter().collect::<Vec<Box<dyn Diagnostic>>>();
+    let diagnostics = collect_diagnostics(tx.try_iter(), &session);
 
-    let mut contains_error = false;
+    if let Some(path) = &settings.sarif {
+        fs::write(path, render_sarif(&diagnostics, &session))?;
+    }
+
+    let mut errors = 0;
+    let mut warnings = 0;
 
     for diagnostic in diagnostics {
-        if diagnostic.get_severity() == Severity::Error {
-            contains_error = true;
+        match diagnostic.get_severity() {
+            Severity::Error => errors += 1,
+            Severity::Warning => warnings += 1,
+            Severity::Info => (),
         }
 
         render_to_stderr(render_config, &session, &diagnostic)
     }
 
+    if errors + warnings > 0 {
+        render_to_stderr(render_config, &session, &Log::Summary { errors, warnings });
+    }
+
+    let contains_error = errors > 0;
+
     if !contains_error {
         render_to_stderr(
             render_config,
@@ -196,12 +391,136 @@ pub fn compile_in_session<T>(
     }
 }
 
+/// Compiles a file with one of the backends and prints the result
+/// to the stdout and the report of the backend to the stderr.
+pub fn build(
+    render_config: &RenderConfig,
+    root: PathBuf,
+    settings: &SessionSettings,
+    file: String,
+    target: &str,
+    entrypoints: Vec<String>,
+    options: &BackendOptions,
+) -> anyhow::Result<()> {
+    let backend = backend::find_backend(target).expect("the target should be validated by clap");
+
+    let (res, report) = compile_in_session(render_config, root, settings, file.clone(), true, &mut |session| {
+        driver::compile_book_to_backend_with_report(
+            backend.as_ref(),
+            session,
+            &PathBuf::from(file.clone()),
+            entrypoints.clone(),
+            options,
+        )
+    })?;
+
+    backend.write(res.as_ref(), &mut std::io::stdout())?;
+
+    if let Some(report) = report {
+        eprintln!("{}", report);
+    }
+
+    Ok(())
+}
+
+/// Number of times that `fix` compiles the file again to find
+/// the errors that were hidden by the ones that it fixed.
+const MAX_FIX_ROUNDS: usize = 10;
+
+/// Applies the suggestions of the diagnostics of a file in place
+/// until there's nothing else to fix. The file is type checked with
+/// the coverage, so the fixes of the checker are applied too. The
+/// errors that are left are reported and returned at the end.
+pub fn fix(
+    render_config: &RenderConfig,
+    root: PathBuf,
+    settings: &SessionSettings,
+    file: String,
+    entrypoints: Vec<String>,
+    tids: Option<usize>,
+) -> anyhow::Result<()> {
+    let path = PathBuf::from(file.clone());
+
+    let mut check = |session: &mut Session| {
+        driver::type_check_book(&backend::HvmBackend, session, &path, entrypoints.clone(), tids, true).map(|_| ())
+    };
+
+    for _ in 0..MAX_FIX_ROUNDS {
+        let (rx, tx) = std::sync::mpsc::channel();
+        let mut session = settings.new_session(root.clone(), rx);
+
+        let res = check(&mut session);
+
+        let diagnostics = collect_diagnostics(tx.try_iter(), &session);
+
+        // An error without diagnostics has nothing to fix and
+        // nothing that the last check could report.
+        if diagnostics.is_empty() {
+            res?;
+            break;
+        }
+
+        if driver::fix::fix_file(&session, &path, &diagnostics)? == 0 {
+            break;
+        }
+    }
+
+    compile_in_session(render_config, root, settings, file, false, &mut check)
+}
+
+/// Prints the types of the expressions, or only the type of the innermost
+/// expression at the position `at`.
+fn print_types(session: &Session, types: &[TypeInfo], at: Option<(usize, usize)>) -> anyhow::Result<()> {
+    let selected: Vec<&TypeInfo> = match at {
+        None => types.iter().collect(),
+        Some(position) => match driver::type_at(session, types, position) {
+            Some(info) => vec![info],
+            None => {
+                eprintln!("There's no expression at {}:{}.", position.0, position.1);
+                return Err(anyhow::anyhow!("no expression at {}:{}", position.0, position.1));
+            }
+        },
+    };
+
+    for info in selected {
+        let (_, code) = session.fetch(info.range.ctx).unwrap();
+        let (start, end) = (info.range.start.index as usize, info.range.end.index as usize);
+        let (start_line, start_column) = line_and_column(code, start);
+        let (end_line, end_column) = line_and_column(code, end);
+        let expr = code[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+
+        println!(
+            "{}:{}-{}:{} {} : {}",
+            start_line, start_column, end_line, end_column, expr, info.typ
+        );
+    }
+
+    Ok(())
+}
+
 pub fn run_cli(config: Cli) -> anyhow::Result<()> {
     kind_report::check_if_colors_are_supported(config.no_color);
 
     let render_config = kind_report::check_if_utf8_is_supported(config.ascii, 2);
     let root = config.root.unwrap_or_else(|| PathBuf::from("."));
 
+    let lint_levels = [(&config.allow, Level::Allow), (&config.warn, Level::Warn), (&config.deny, Level::Deny)]
+        .into_iter()
+        .flat_map(|(names, level)| names.iter().map(move |name| (name.clone(), level)))
+        .collect();
+
+    let limits = EvalLimits {
+        max_rewrites: config.max_rewrites,
+        heap_size: config.heap_size,
+    };
+
+    let settings = SessionSettings {
+        warnings: config.warning,
+        lint_levels,
+        sarif: config.sarif,
+        limits,
+    };
+
     let mut entrypoints = vec!["Main".to_string()];
 
     if let Some(res) = &config.entrypoint {
@@ -210,8 +529,9 @@ pub fn run_cli(config: Cli) -> anyhow::Result<()> {
 
     match config.command {
         Command::Check { file, coverage } => {
-            compile_in_session(&render_config, root, file.clone(), false, &mut |session| {
+            compile_in_session(&render_config, root, &settings, file.clone(), false, &mut |session| {
                 let (_, rewrites) = driver::type_check_book(
+                    &backend::HvmBackend,
                     session,
                     &PathBuf::from(file.clone()),
                     entrypoints.clone(),
@@ -224,27 +544,22 @@ pub fn run_cli(config: Cli) -> anyhow::Result<()> {
                 Ok(())
             })?;
         }
-        Command::ToHVM { file } => {
-            let result =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
-                    let book = driver::erase_book(
+        Command::ToHVM { file, flags } => {
+            let options = BackendOptions { trace: config.trace, flags: flags.into_iter().collect() };
+            build(&render_config, root, &settings, file, "hvm", entrypoints, &options)?;
+        }
+        Command::Run { file } => {
+            let res =
+                compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
+                    let options = BackendOptions { trace: config.trace, ..Default::default() };
+                    let (result, rewrites) = driver::run_book(
                         session,
                         &PathBuf::from(file.clone()),
                         entrypoints.clone(),
+                        &options,
+                        config.tids,
+                        limits,
                     )?;
-                    Ok(driver::compile_book_to_hvm(book, config.trace))
-                })?;
-
-            println!("{}", result);
-        }
-        Command::Run { file } => {
-            let res =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
-                    let path = PathBuf::from(file.clone());
-                    let book = driver::erase_book(session, &path, entrypoints.clone())?;
-                    driver::check_main_entry(session, &book)?;
-                    let book = driver::compile_book_to_hvm(book, config.trace);
-                    let (result, rewrites) = driver::execute_file(&book.to_string(), config.tids)?;
 
                     render_to_stderr(&render_config, session, &Log::Rewrites(rewrites));
 
@@ -252,8 +567,8 @@ pub fn run_cli(config: Cli) -> anyhow::Result<()> {
                 })?;
             println!("{}", res);
         }
-        Command::Show { file } => {
-            compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
+        Command::Show { file, expanded: false } => {
+            compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
                 driver::to_book(session, &PathBuf::from(file.clone()))
             })
             .map(|res| {
@@ -261,33 +576,57 @@ pub fn run_cli(config: Cli) -> anyhow::Result<()> {
                 res
             })?;
         }
+        Command::Show { file, expanded: true } => {
+            let res = compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
+                driver::show_expanded_book(session, &PathBuf::from(file.clone()))
+            })?;
+            print!("{}", res);
+        }
+        Command::Fix { file } => {
+            fix(&render_config, root, &settings, file, entrypoints, config.tids)?;
+        }
+        Command::Types { file, at } => {
+            compile_in_session(&render_config, root, &settings, file.clone(), false, &mut |session| {
+                let types = driver::infer_types(session, &PathBuf::from(file.clone()), config.tids)?;
+                print_types(session, &types, at)
+            })?;
+        }
+        Command::Explain { code } => {
+            match explain::parse_code(&code).and_then(explain::explain) {
+                Some(explanation) => print!("{}", explanation),
+                None => {
+                    eprintln!("There's no explanation for the code '{}'.", code);
+                    anyhow::bail!("there's no explanation for the code '{}'", code)
+                }
+            }
+        }
         Command::ToKindCore { file } => {
             let res =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
+                compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
                     driver::desugar_book(session, &PathBuf::from(file.clone()))
                 })?;
             print!("{}", res);
         }
         Command::Erase { file } => {
             let res =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
-                    driver::erase_book(session, &PathBuf::from(file.clone()), entrypoints.clone())
+                compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
+                    driver::erase_book(&backend::HvmBackend, session, &PathBuf::from(file.clone()), entrypoints.clone())
                 })?;
             print!("{}", res);
         }
         Command::GenChecker { file, coverage } => {
             let res =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
+                compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
                     driver::check_erasure_book(session, &PathBuf::from(file.clone()))
                 })?;
             print!("{}", driver::generate_checker(&res, coverage));
         }
         Command::Eval { file } => {
             let res =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
+                compile_in_session(&render_config, root, &settings, file.clone(), true, &mut |session| {
                     let book = driver::desugar_book(session, &PathBuf::from(file.clone()))?;
                     driver::check_main_desugared_entry(session, &book)?;
-                    let (res, rewrites) = driver::eval_in_checker(&book);
+                    let (res, rewrites) = driver::eval_in_checker(session, &book, limits)?;
 
                     render_to_stderr(&render_config, session, &Log::Rewrites(rewrites));
 
@@ -295,17 +634,13 @@ pub fn run_cli(config: Cli) -> anyhow::Result<()> {
                 })?;
             println!("{}", res);
         }
-        Command::ToKDL { file, namespace } => {
-            let res =
-                compile_in_session(&render_config, root, file.clone(), true, &mut |session| {
-                    driver::compile_book_to_kdl(
-                        &PathBuf::from(file.clone()),
-                        session,
-                        &namespace.clone().unwrap_or("".to_string()),
-                        entrypoints.clone(),
-                    )
-                })?;
-            println!("{}", res);
+        Command::ToKDL { file, flags } => {
+            let options = BackendOptions { trace: config.trace, flags: flags.into_iter().collect() };
+            build(&render_config, root, &settings, file, "kdl", entrypoints, &options)?;
+        }
+        Command::Build { file, target, flags } => {
+            let options = BackendOptions { trace: config.trace, flags: flags.into_iter().collect() };
+            build(&render_config, root, &settings, file, &target, entrypoints, &options)?;
         }
     }
 
@@ -313,7 +648,7 @@ pub fn run_cli(config: Cli) -> anyhow::Result<()> {
 }
 
 pub fn main() {
-    match run_cli(Cli::parse()) {
+    match run_cli(parse_cli()) {
         Ok(_) => std::process::exit(0),
         Err(_) => std::process::exit(1),
     }
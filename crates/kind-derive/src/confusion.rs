@@ -0,0 +1,385 @@
+//! Module to derive the no-confusion lemmas of a sum type: the
+//! constructors are injective and different constructors never build
+//! the same value.
+
+use fxhash::FxHashMap;
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+use kind_tree::telescope::Telescope;
+
+use crate::helpers::*;
+use crate::subst::substitute_in_expr;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+fn entry(
+    name: QualifiedIdent,
+    args: Vec<Argument>,
+    typ: Box<Expr>,
+    rules: impl IntoIterator<Item = Box<Rule>>,
+    sum: &SumTypeDecl,
+    range: Range,
+) -> concrete::Entry {
+    Entry {
+        name,
+        docs: Vec::new(),
+        args: Telescope::new(args),
+        typ,
+        rules: rules.into_iter().collect(),
+        range,
+        attrs: Vec::new(),
+        generated_by: Some(sum.name.to_string()),
+    }
+}
+
+fn rule(
+    name: &QualifiedIdent,
+    pats: impl IntoIterator<Item = Box<Pat>>,
+    body: Box<Expr>,
+    range: Range,
+) -> Box<Rule> {
+    Box::new(Rule {
+        name: name.clone(),
+        pats: pats.into_iter().collect(),
+        body,
+        range,
+    })
+}
+
+fn pat_cons(name: QualifiedIdent, fields: impl IntoIterator<Item = Box<Pat>>, range: Range) -> Box<Pat> {
+    Box::new(Pat {
+        data: PatKind::App(name, fields.into_iter().collect()),
+        range,
+    })
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn cons_name(&self, cons: &Constructor) -> QualifiedIdent {
+        self.sum.name.add_segment(cons.name.to_str())
+    }
+
+    /// The parameters, and the indices when the constructor does not
+    /// fix them, that every lemma about a constructor receives.
+    fn prefix(&self, cons: &Constructor) -> Vec<&'a Argument> {
+        let mut prefix: Vec<_> = self.sum.parameters.iter().collect();
+        if cons.typ.is_none() {
+            prefix.extend(self.sum.indices.iter());
+        }
+        prefix
+    }
+
+    /// The type of the values built by a constructor.
+    fn result(&self, cons: &Constructor) -> Box<Expr> {
+        cons.typ.clone().unwrap_or_else(|| {
+            let args = self
+                .prefix(cons)
+                .iter()
+                .map(|arg| var(arg.name.to_str(), self.range))
+                .collect::<Vec<_>>();
+            call(self.sum.name.clone(), args, self.range)
+        })
+    }
+
+    /// The fields of a constructor, renamed to `prefix.field` when they
+    /// are in `renamed`.
+    fn fields(&self, cons: &Constructor, prefix: &str, renamed: &[String]) -> Vec<Argument> {
+        let names: FxHashMap<String, String> = renamed
+            .iter()
+            .map(|name| (name.clone(), format!("{}.{}", prefix, name)))
+            .collect();
+
+        cons.args
+            .iter()
+            .map(|field| {
+                let mut field = field.clone();
+                if let Some(typ) = &mut field.typ {
+                    substitute_in_expr(typ, &names);
+                }
+                if let Some(name) = names.get(field.name.to_str()) {
+                    field.name = ident(name, self.range);
+                }
+                if field.hidden {
+                    field.to_implicit()
+                } else {
+                    field.erased = false;
+                    field
+                }
+            })
+            .collect()
+    }
+
+    /// A constructor applied to the variables of its arguments.
+    fn build(&self, cons: &Constructor, fields: &[Argument]) -> Box<Expr> {
+        let mut args: Vec<_> = self
+            .prefix(cons)
+            .iter()
+            .map(|arg| var(arg.name.to_str(), self.range))
+            .collect();
+        args.extend(fields.iter().map(|field| var(field.name.to_str(), self.range)));
+        call(self.cons_name(cons), args, self.range)
+    }
+
+    /// `T.C.get.f` that returns the field `f` of a value built with
+    /// `T.C` and `default_` otherwise.
+    fn derive_projection(&self, cons: &Constructor, field: &Argument) -> concrete::Entry {
+        let range = self.range;
+        let name = self
+            .cons_name(cons)
+            .add_segment("get")
+            .add_segment(field.name.to_str());
+        let hidden: Vec<_> = cons.args.iter().filter(|arg| arg.hidden).collect();
+
+        let mut args: Vec<_> = self.prefix(cons).iter().map(|arg| arg.to_implicit()).collect();
+        args.extend(hidden.iter().map(|arg| arg.to_implicit()));
+
+        let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+        args.push(explicit("default_", typ.clone(), range));
+        args.push(explicit("value_", self.result(cons), range));
+
+        let mut pats: Vec<_> = args[..args.len() - 2]
+            .iter()
+            .map(|arg| pat_var(arg.name.to_str(), range))
+            .collect();
+        pats.push(pat_var("default_", range));
+
+        let fields = explicit_fields(cons).into_iter().map(|arg| {
+            if arg.erased {
+                pat_hole(range)
+            } else {
+                pat_var(&field_name("value", arg), range)
+            }
+        });
+
+        pats.push(pat_cons(self.cons_name(cons), fields, range));
+
+        let mut rules = vec![rule(&name, pats, var(&field_name("value", field), range), range)];
+
+        if self.sum.constructors.len() > 1 {
+            let mut pats: Vec<_> = args[..args.len() - 2].iter().map(|_| pat_hole(range)).collect();
+            pats.push(pat_var("default_", range));
+            pats.push(pat_hole(range));
+            rules.push(rule(&name, pats, var("default_", range), range));
+        }
+
+        entry(name, args, typ, rules, self.sum, range)
+    }
+
+    /// `T.C.inj.f` that proves that the fields `f` of two equal values
+    /// built with `T.C` are equal.
+    fn derive_injectivity(&self, cons: &Constructor, field: &Argument) -> concrete::Entry {
+        let range = self.range;
+        let name = self
+            .cons_name(cons)
+            .add_segment("inj")
+            .add_segment(field.name.to_str());
+        let names: Vec<_> = explicit_fields(cons)
+            .iter()
+            .map(|arg| arg.name.to_string())
+            .collect();
+
+        let a = self.fields(cons, "a", &names);
+        let b = self.fields(cons, "b", &names);
+
+        let mut args: Vec<_> = self.prefix(cons).iter().map(|arg| arg.to_implicit()).collect();
+        args.extend(a.iter().filter(|arg| arg.hidden).cloned());
+        args.extend(a.iter().filter(|arg| !arg.hidden).cloned());
+        args.extend(b.iter().filter(|arg| !arg.hidden).cloned());
+
+        let equal = lib(
+            "Equal",
+            vec![self.result(cons), self.build(cons, &a), self.build(cons, &b)],
+            range,
+        );
+        args.push(explicit("e_", equal, range));
+
+        let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+        let a_field = var(&format!("a.{}", field.name), range);
+        let b_field = var(&format!("b.{}", field.name), range);
+        let result = lib("Equal", vec![typ, a_field.clone(), b_field], range);
+
+        // The projection receives the hidden fields shared by both
+        // values and uses the field of the first one as the default.
+        let mut projection: Vec<_> = args
+            .iter()
+            .filter(|arg| arg.hidden)
+            .map(|arg| var(arg.name.to_str(), range))
+            .collect();
+        projection.push(a_field);
+        projection.push(var("x_", range));
+
+        let get = self
+            .cons_name(cons)
+            .add_segment("get")
+            .add_segment(field.name.to_str());
+        let function = lambda("x_", None, call(get, projection, range), range);
+        let body = lib("Equal.apply", vec![function, var("e_", range)], range);
+
+        let pats: Vec<_> = args.iter().map(|arg| pat_var(arg.name.to_str(), range)).collect();
+
+        entry(
+            name.clone(),
+            args,
+            result,
+            vec![rule(&name, pats, body, range)],
+            self.sum,
+            range,
+        )
+    }
+
+    /// `T.C.is` that is `Unit` for the values built with `T.C` and
+    /// `Empty` for the other ones.
+    fn derive_discriminator(&self, cons: &Constructor) -> concrete::Entry {
+        let range = self.range;
+        let name = self.cons_name(cons).add_segment("is");
+
+        let mut args: Vec<_> = self.prefix(cons).iter().map(|arg| arg.to_implicit()).collect();
+        args.push(explicit("value_", self.result(cons), range));
+
+        let holes = || {
+            args[..args.len() - 1]
+                .iter()
+                .map(|_| pat_hole(range))
+                .collect::<Vec<_>>()
+        };
+
+        let mut pats = holes();
+        let fields = explicit_fields(cons).into_iter().map(|_| pat_hole(range));
+        pats.push(pat_cons(self.cons_name(cons), fields, range));
+
+        let mut otherwise = holes();
+        otherwise.push(pat_hole(range));
+
+        let rules = vec![
+            rule(&name, pats, lib("Unit", vec![], range), range),
+            rule(&name, otherwise, lib("Empty", vec![], range), range),
+        ];
+
+        entry(name, args, Expr::typ(range), rules, self.sum, range)
+    }
+
+    /// `T.C.disjoint.D` that proves that a value built with `T.C` is
+    /// never equal to one built with `T.D`.
+    fn derive_disjointness(&self, cons: &Constructor, other: &Constructor) -> concrete::Entry {
+        let range = self.range;
+        let name = self
+            .cons_name(cons)
+            .add_segment("disjoint")
+            .add_segment(other.name.to_str());
+
+        let all_fields = |cons: &Constructor| {
+            cons.args
+                .iter()
+                .map(|arg| arg.name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let a = self.fields(cons, "a", &all_fields(cons));
+        let b = self.fields(other, "b", &all_fields(other));
+
+        let mut args: Vec<_> = self.prefix(cons).iter().map(|arg| arg.to_implicit()).collect();
+        args.extend(a.iter().cloned());
+        args.extend(b.iter().cloned());
+
+        let equal = lib(
+            "Equal",
+            vec![self.result(cons), self.build(cons, &a), self.build(other, &b)],
+            range,
+        );
+        args.push(explicit("e_", equal, range));
+
+        let mut discriminator: Vec<_> = self
+            .prefix(cons)
+            .iter()
+            .map(|arg| var(arg.name.to_str(), range))
+            .collect();
+        discriminator.push(var("x_", range));
+
+        let is = self.cons_name(cons).add_segment("is");
+        let motive = lambda("x_", None, call(is, discriminator, range), range);
+        let body = lib(
+            "Equal.rewrite",
+            vec![var("e_", range), motive, lib("Unit.new", vec![], range)],
+            range,
+        );
+
+        let pats: Vec<_> = args.iter().map(|arg| pat_var(arg.name.to_str(), range)).collect();
+
+        entry(
+            name.clone(),
+            args,
+            lib("Empty", vec![], range),
+            vec![rule(&name, pats, body, range)],
+            self.sum,
+            range,
+        )
+    }
+
+    fn derive(&self) -> Vec<concrete::Entry> {
+        let mut entries = Vec::new();
+
+        for cons in &self.sum.constructors {
+            let explicit: Vec<_> = explicit_fields(cons)
+                .iter()
+                .map(|arg| arg.name.to_string())
+                .collect();
+
+            // Two values of the same constructor must have the same type
+            // to be compared, so the result cannot depend on the fields.
+            if cons.typ.as_ref().is_some_and(|typ| mentions(typ, &explicit)) {
+                continue;
+            }
+
+            for field in explicit_fields(cons) {
+                let depends = field.typ.as_ref().is_some_and(|typ| mentions(typ, &explicit));
+                if field.erased || depends {
+                    continue;
+                }
+                entries.push(self.derive_projection(cons, field));
+                entries.push(self.derive_injectivity(cons, field));
+            }
+        }
+
+        // Only the constructors that do not fix the indices build values
+        // of the same type, so they are the only ones that are compared.
+        let free: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .filter(|cons| cons.typ.is_none())
+            .collect();
+
+        if free.len() > 1 {
+            for cons in &free {
+                entries.push(self.derive_discriminator(cons));
+                for other in &free {
+                    if cons.name.to_str() != other.name.to_str() {
+                        entries.push(self.derive_disjointness(cons, other));
+                    }
+                }
+            }
+        }
+
+        entries
+    }
+}
+
+/// Derives the no-confusion lemmas of a sum type. For each field of a
+/// constructor, `T.C.inj.f` proves that the constructor is injective
+/// in that field, and for each pair of constructors that do not fix
+/// the indices, `T.C.disjoint.D` proves that they never build equal
+/// values. They need `Equal`, `Equal.apply`, `Equal.rewrite`, `Unit`
+/// and `Empty`.
+pub fn derive_no_confusion(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    (Derivation { range, sum }.derive(), Vec::new())
+}
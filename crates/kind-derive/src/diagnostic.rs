@@ -1,3 +1,4 @@
+use kind_report::codes;
 use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity};
 use kind_span::Range;
 
@@ -5,6 +6,17 @@ pub(crate) enum DeriveDiagnostic {
     CannotUseNamedVariable(Range),
     CannotUseAll(Range),
     InvalidReturnType(Range),
+    /// A field whose type is not supported by a derivation.
+    UnsupportedField(&'static str, Range),
+    /// A derivation that needs a type parameter on a type without one.
+    NoTypeParameter(&'static str, Range),
+    /// An occurrence of a type that a derivation cannot traverse.
+    UnsupportedOccurrence(&'static str, String, Range),
+    /// A derivation that does not support types with indices.
+    IndexedType(&'static str, Range),
+    /// A constructor with fields in a derivation that needs constructors
+    /// without them.
+    ConstructorWithFields(&'static str, Range),
 }
 
 impl Diagnostic for DeriveDiagnostic {
@@ -13,17 +25,23 @@ impl Diagnostic for DeriveDiagnostic {
             DeriveDiagnostic::CannotUseNamedVariable(range) => Some(range.ctx),
             DeriveDiagnostic::CannotUseAll(range) => Some(range.ctx),
             DeriveDiagnostic::InvalidReturnType(range) => Some(range.ctx),
+            DeriveDiagnostic::UnsupportedField(_, range) => Some(range.ctx),
+            DeriveDiagnostic::NoTypeParameter(_, range) => Some(range.ctx),
+            DeriveDiagnostic::UnsupportedOccurrence(_, _, range) => Some(range.ctx),
+            DeriveDiagnostic::IndexedType(_, range) => Some(range.ctx),
+            DeriveDiagnostic::ConstructorWithFields(_, range) => Some(range.ctx),
         }
     }
 
     fn to_diagnostic_frame(&self) -> DiagnosticFrame {
         match self {
             DeriveDiagnostic::CannotUseNamedVariable(range) => DiagnosticFrame {
-                code: 103,
+                code: codes::CANNOT_USE_NAMED_VARIABLE,
                 severity: Severity::Error,
                 title: "Cannot use named variable on match derivations".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -33,11 +51,12 @@ impl Diagnostic for DeriveDiagnostic {
                 }],
             },
             DeriveDiagnostic::CannotUseAll(range) => DiagnosticFrame {
-                code: 103,
+                code: codes::CANNOT_USE_ALL,
                 severity: Severity::Error,
                 title: "Data constructors cannot return function types.".to_string(),
                 subtitles: vec![],
                 hints: vec!["Change all of the function types sequence for explicit arguments like 'cons : x -> T' to 'cons (name: x) : T'".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -47,11 +66,90 @@ impl Diagnostic for DeriveDiagnostic {
                 }],
             },
             DeriveDiagnostic::InvalidReturnType(range) => DiagnosticFrame {
-                code: 103,
+                code: codes::INVALID_RETURN_TYPE,
                 severity: Severity::Error,
                 title: "Data constructors cannot return this type".to_string(),
                 subtitles: vec![],
                 hints: vec!["Replace it with the type that is being declarated at the current block".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DeriveDiagnostic::UnsupportedField(derive, range) => DiagnosticFrame {
+                code: codes::UNSUPPORTED_FIELD,
+                severity: Severity::Error,
+                title: format!("Cannot derive '{}' for a field of this type", derive),
+                subtitles: vec![],
+                hints: vec!["Only fields whose types are type parameters, U60, F60 or applications of other types are supported".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DeriveDiagnostic::NoTypeParameter(derive, range) => DiagnosticFrame {
+                code: codes::NO_TYPE_PARAMETER,
+                severity: Severity::Error,
+                title: format!("Cannot derive '{}' for a type without type parameters", derive),
+                subtitles: vec![],
+                hints: match *derive {
+                    "functor" => vec!["The last type parameter is the one that is mapped".to_string()],
+                    _ => vec![],
+                },
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DeriveDiagnostic::UnsupportedOccurrence(derive, name, range) => DiagnosticFrame {
+                code: codes::UNSUPPORTED_OCCURRENCE,
+                severity: Severity::Error,
+                title: format!("Cannot derive '{}' for a field where '{}' occurs in this position", derive, name),
+                subtitles: vec![],
+                hints: vec![format!("'{}' can only be the type of a field or the last argument of a type that derives 'functor'", name)],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DeriveDiagnostic::IndexedType(derive, range) => DiagnosticFrame {
+                code: codes::INDEXED_TYPE,
+                severity: Severity::Error,
+                title: format!("Cannot derive '{}' for a type with indices", derive),
+                subtitles: vec![],
+                hints: vec!["Turn the indices into parameters or into fields of the constructors".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DeriveDiagnostic::ConstructorWithFields(derive, range) => DiagnosticFrame {
+                code: codes::CONSTRUCTOR_WITH_FIELDS,
+                severity: Severity::Error,
+                title: format!("Cannot derive '{}' for a type with a constructor that has fields", derive),
+                subtitles: vec![],
+                hints: vec!["Only types whose constructors have no fields, like 'type Color { red green blue }', are supported".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -68,7 +166,12 @@ impl Diagnostic for DeriveDiagnostic {
         match self {
             CannotUseNamedVariable(_)
             | CannotUseAll(_)
-            | InvalidReturnType(_) => Severity::Error,
+            | InvalidReturnType(_)
+            | UnsupportedField(_, _)
+            | NoTypeParameter(_, _)
+            | UnsupportedOccurrence(_, _, _)
+            | IndexedType(_, _)
+            | ConstructorWithFields(_, _) => Severity::Error,
         }
     }
 }
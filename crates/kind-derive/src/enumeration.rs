@@ -0,0 +1,210 @@
+//! Module to derive the enumeration helpers of sum types whose
+//! constructors have no fields. Each constructor is numbered by its
+//! position in the declaration, so the numbers of the constructors
+//! that already exist do not change when a new one is added at the end.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+use kind_tree::telescope::Telescope;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn self_type(&self) -> Box<Expr> {
+        let params = self.sum.parameters.iter().map(|param| var(param.name.to_str(), self.range));
+        call(self.sum.name.clone(), params, self.range)
+    }
+
+    fn cons_name(&self, cons: &Constructor) -> QualifiedIdent {
+        self.sum.name.add_segment(cons.name.to_str())
+    }
+
+    fn cons(&self, cons: &Constructor) -> Box<Expr> {
+        call(self.cons_name(cons), Vec::new(), self.range)
+    }
+
+    /// The arguments of every derived function start with the
+    /// parameters of the type.
+    fn args(&self, explicit_arg: Option<(&str, Box<Expr>)>) -> Telescope<Argument> {
+        let mut args = self.sum.parameters.map(|arg| arg.to_implicit());
+        if let Some((name, typ)) = explicit_arg {
+            args.push(explicit(name, typ, self.range));
+        }
+        args
+    }
+
+    fn rule(&self, name: &QualifiedIdent, pat: Option<Box<Pat>>, body: Box<Expr>) -> Box<Rule> {
+        let mut pats: Vec<_> = self
+            .sum
+            .parameters
+            .iter()
+            .map(|param| pat_var(param.name.to_str(), self.range))
+            .collect();
+        pats.extend(pat);
+
+        Box::new(Rule {
+            name: name.clone(),
+            pats,
+            body,
+            range: self.range,
+        })
+    }
+
+    fn cons_pat(&self, cons: &Constructor) -> Box<Pat> {
+        Box::new(Pat {
+            data: PatKind::App(self.cons_name(cons), Vec::new()),
+            range: self.range,
+        })
+    }
+
+    fn entry(
+        &self,
+        name: QualifiedIdent,
+        args: Telescope<Argument>,
+        typ: Box<Expr>,
+        rules: impl IntoIterator<Item = Box<Rule>>,
+    ) -> Entry {
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ,
+            rules: rules.into_iter().collect(),
+            range: self.range,
+            attrs: Vec::new(),
+            generated_by: Some(self.sum.name.to_string()),
+        }
+    }
+
+    fn all(&self) -> Entry {
+        let range = self.range;
+        let name = self.sum.name.add_segment("all");
+
+        let list = Box::new(Expr {
+            data: ExprKind::List {
+                args: self.sum.constructors.iter().map(|cons| *self.cons(cons)).collect(),
+            },
+            range,
+        });
+
+        let typ = lib("List", vec![self.self_type()], range);
+        let rule = self.rule(&name, None, list);
+
+        self.entry(name, self.args(None), typ, vec![rule])
+    }
+
+    fn encode(&self) -> Entry {
+        let range = self.range;
+        let name = self.sum.name.add_segment("to_u60");
+
+        let rules: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .enumerate()
+            .map(|(tag, cons)| self.rule(&name, Some(self.cons_pat(cons)), number(tag as u64, range)))
+            .collect();
+
+        let args = self.args(Some(("value_", self.self_type())));
+        self.entry(name, args, u60(range), rules)
+    }
+
+    fn decode(&self) -> Entry {
+        let range = self.range;
+        let name = self.sum.name.add_segment("from_u60");
+
+        let mut rules: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .enumerate()
+            .map(|(tag, cons)| {
+                let pat = Box::new(Pat {
+                    data: PatKind::U60(tag as u64),
+                    range,
+                });
+                self.rule(&name, Some(pat), lib("Maybe.some", vec![self.cons(cons)], range))
+            })
+            .collect();
+
+        rules.push(self.rule(&name, Some(pat_hole(range)), lib("Maybe.none", vec![], range)));
+
+        let typ = lib("Maybe", vec![self.self_type()], range);
+        self.entry(name, self.args(Some(("number_", u60(range)))), typ, rules)
+    }
+
+    /// `T.to_u60.inverse` proves that `T.from_u60` gets back the value
+    /// that was given to `T.to_u60`.
+    fn inverse(&self) -> Entry {
+        let range = self.range;
+        let name = self.sum.name.add_segment("to_u60").add_segment("inverse");
+
+        let to_u60 = call(self.sum.name.add_segment("to_u60"), vec![var("value_", range)], range);
+        let from_u60 = call(self.sum.name.add_segment("from_u60"), vec![to_u60], range);
+
+        let typ = lib(
+            "Equal",
+            vec![
+                lib("Maybe", vec![self.self_type()], range),
+                from_u60,
+                lib("Maybe.some", vec![var("value_", range)], range),
+            ],
+            range,
+        );
+
+        let rules: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .map(|cons| self.rule(&name, Some(self.cons_pat(cons)), lib("Equal.refl", vec![], range)))
+            .collect();
+
+        self.entry(name, self.args(Some(("value_", self.self_type()))), typ, rules)
+    }
+
+    fn derive(&self) -> (Vec<concrete::Entry>, Errs) {
+        if !self.sum.indices.is_empty() {
+            let err: Box<dyn Diagnostic> = Box::new(DeriveDiagnostic::IndexedType("enum", self.sum.name.range));
+            return (Vec::new(), vec![err]);
+        }
+
+        let errs: Errs = self
+            .sum
+            .constructors
+            .iter()
+            .filter(|cons| !cons.args.is_empty())
+            .map(|cons| Box::new(DeriveDiagnostic::ConstructorWithFields("enum", cons.name.range)) as Box<dyn Diagnostic>)
+            .collect();
+
+        if !errs.is_empty() {
+            return (Vec::new(), errs);
+        }
+
+        let entries = vec![self.all(), self.encode(), self.decode(), self.inverse()];
+        (entries, Vec::new())
+    }
+}
+
+/// Derives the enumeration helpers of a sum type whose constructors
+/// have no fields: `T.all` with every constructor in the order of the
+/// declaration, `T.to_u60` and `T.from_u60` that convert a constructor
+/// to its position and back, and `T.to_u60.inverse` that proves that
+/// the conversion back gets the same constructor. They need `List`,
+/// `Maybe` and `Equal`.
+pub fn derive_enum(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    Derivation { range, sum }.derive()
+}
@@ -0,0 +1,751 @@
+//! Module to derive a boolean equality for sum types and records
+//! and, for the types without indices, a decidable equality that
+//! returns a proof of the equality or of its negation.
+
+use fxhash::FxHashMap;
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+use kind_tree::telescope::Telescope;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+use crate::subst::substitute_in_expr;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// The two functions that are derived to compare values.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    /// `T.equal` that returns a `Bool`.
+    Equal,
+    /// `T.equal.decide` that returns an `Either` with a proof.
+    Decide,
+}
+
+impl Comparison {
+    fn suffix(self) -> &'static str {
+        match self {
+            Comparison::Equal => "equal",
+            Comparison::Decide => "decide",
+        }
+    }
+
+    /// The path of the function after the name of the type.
+    fn entry(self) -> &'static str {
+        match self {
+            Comparison::Equal => "equal",
+            Comparison::Decide => "equal.decide",
+        }
+    }
+
+    fn entry_name(self, name: &QualifiedIdent) -> QualifiedIdent {
+        name.add_segment(self.entry())
+    }
+}
+
+/// The type `Either (Equal typ x y) ((Equal typ x y) -> Empty)` of
+/// the decisions about the equality of `x` and `y`.
+fn decision(typ: Box<Expr>, x: Box<Expr>, y: Box<Expr>, range: Range) -> Box<Expr> {
+    let equal = lib("Equal", vec![typ, x, y], range);
+    let absurd = arrow(equal.clone(), lib("Empty", vec![], range), range);
+    lib("Either", vec![equal, absurd], range)
+}
+
+/// Folds the comparisons of the fields with `Bool.and`.
+fn conjunction(
+    mut comparisons: impl DoubleEndedIterator<Item = Box<Expr>>,
+    range: Range,
+) -> Box<Expr> {
+    match comparisons.next_back() {
+        None => lib("Bool.true", vec![], range),
+        Some(last) => comparisons.rfold(last, |rest, comparison| {
+            lib("Bool.and", vec![comparison, rest], range)
+        }),
+    }
+}
+
+/// The fields that are compared, the erased ones are left out.
+fn compared_fields(cons: &Constructor) -> Vec<&Argument> {
+    cons.args
+        .iter()
+        .filter(|arg| !arg.hidden && !arg.erased)
+        .collect()
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn cons_name(&self, cons: &Constructor) -> QualifiedIdent {
+        self.sum.name.add_segment(cons.name.to_str())
+    }
+
+    fn params(&self) -> impl Iterator<Item = Box<Expr>> + '_ {
+        self.sum
+            .parameters
+            .iter()
+            .map(|param| var(param.name.to_str(), self.range))
+    }
+
+    /// The type `T ps` of a type without indices.
+    fn self_type(&self) -> Box<Expr> {
+        sum_type(self.sum, &self.sum.indices, self.range)
+    }
+
+    /// The value `C ps fs` of a constructor of a type without indices.
+    fn instance(
+        &self,
+        cons: &Constructor,
+        fields: impl IntoIterator<Item = Box<Expr>>,
+    ) -> Box<Expr> {
+        let mut args: Vec<_> = self.params().collect();
+        args.extend(fields);
+        call(self.cons_name(cons), args, self.range)
+    }
+
+    fn instance_of(&self, cons: &Constructor, prefix: &str) -> Box<Expr> {
+        let fields = cons
+            .args
+            .iter()
+            .map(|field| var(&field_name(prefix, field), self.range));
+        self.instance(cons, fields)
+    }
+
+    fn cons_pat(&self, cons: &Constructor, prefix: &str) -> Box<Pat> {
+        let fields = explicit_fields(cons)
+            .into_iter()
+            .map(|field| {
+                if field.erased {
+                    pat_hole(self.range)
+                } else {
+                    pat_var(&field_name(prefix, field), self.range)
+                }
+            })
+            .collect();
+
+        Box::new(Pat {
+            data: PatKind::App(self.cons_name(cons), fields),
+            range: self.range,
+        })
+    }
+
+    fn cons_hole_pat(&self, cons: &Constructor) -> Box<Pat> {
+        Box::new(Pat {
+            data: PatKind::App(
+                self.cons_name(cons),
+                explicit_fields(cons)
+                    .iter()
+                    .map(|_| pat_hole(self.range))
+                    .collect(),
+            ),
+            range: self.range,
+        })
+    }
+
+    fn param_pats(&self) -> impl Iterator<Item = Box<Pat>> + '_ {
+        self.sum
+            .parameters
+            .iter()
+            .map(|param| pat_var(param.name.to_str(), self.range))
+    }
+
+    fn rule(
+        &self,
+        name: &QualifiedIdent,
+        pats: impl IntoIterator<Item = Box<Pat>>,
+        body: Box<Expr>,
+    ) -> Box<Rule> {
+        Box::new(Rule {
+            name: name.clone(),
+            pats: pats.into_iter().collect(),
+            body,
+            range: self.range,
+        })
+    }
+
+    fn entry(
+        &self,
+        name: QualifiedIdent,
+        args: Telescope<Argument>,
+        typ: Box<Expr>,
+        rules: impl IntoIterator<Item = Box<Rule>>,
+    ) -> concrete::Entry {
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ,
+            rules: rules.into_iter().collect(),
+            range: self.range,
+            attrs: Vec::new(),
+            generated_by: Some(self.sum.name.to_string()),
+        }
+    }
+
+    /// The type of the function that compares two values of `typ`.
+    fn comparator_type(&self, kind: Comparison, typ: Box<Expr>) -> Box<Expr> {
+        let range = self.range;
+        match kind {
+            Comparison::Equal => arrow(
+                typ.clone(),
+                arrow(typ, lib("Bool", vec![], range), range),
+                range,
+            ),
+            Comparison::Decide => Expr::all(
+                ident("x_", range),
+                typ.clone(),
+                Expr::all(
+                    ident("y_", range),
+                    typ.clone(),
+                    decision(typ, var("x_", range), var("y_", range), range),
+                    false,
+                    range,
+                ),
+                false,
+                range,
+            ),
+        }
+    }
+
+    /// The arguments with the comparators of the type parameters.
+    fn comparators(&self, kind: Comparison) -> Vec<Argument> {
+        type_params(self.sum)
+            .into_iter()
+            .map(|param| {
+                let name = param_function(&param.name, kind.suffix());
+                let typ = self.comparator_type(kind, var(param.name.to_str(), self.range));
+                explicit(&name, typ, self.range)
+            })
+            .collect()
+    }
+
+    /// Checks if the values of `typ` can be compared with a proof.
+    fn is_decidable(&self, typ: &Expr) -> bool {
+        match &typ.data {
+            ExprKind::Var { name } => is_type_param(self.sum, name),
+            ExprKind::Constr { name, args } => {
+                let own = name.to_string() == self.sum.name.to_string();
+                args.iter().enumerate().all(|(i, arg)| match arg {
+                    _ if own && !self.sum.parameters.as_slice().get(i).is_some_and(is_type) => true,
+                    Binding::Positional(arg) => self.is_decidable(arg),
+                    Binding::Named(..) => false,
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// A function that compares two values of `typ`.
+    fn function(&self, kind: Comparison, typ: &Expr, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                var(&param_function(name, kind.suffix()), self.range)
+            }
+            _ => {
+                let (x, y) = (format!("x_{}", depth), format!("y_{}", depth));
+                let body = self.compare(
+                    kind,
+                    typ,
+                    var(&x, self.range),
+                    var(&y, self.range),
+                    depth + 1,
+                    errs,
+                );
+                lambda(&x, None, lambda(&y, None, body, self.range), self.range)
+            }
+        }
+    }
+
+    /// Compares the values `x` and `y` of the type `typ`.
+    fn compare(
+        &self,
+        kind: Comparison,
+        typ: &Expr,
+        x: Box<Expr>,
+        y: Box<Expr>,
+        depth: usize,
+        errs: &mut Errs,
+    ) -> Box<Expr> {
+        let range = self.range;
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => app(
+                var(&param_function(name, kind.suffix()), range),
+                vec![x, y],
+                range,
+            ),
+            ExprKind::Lit {
+                lit: Literal::NumTypeU60,
+            } => lib("U60.equal", vec![x, y], range),
+            ExprKind::Lit {
+                lit: Literal::NumTypeF60,
+            } => lib("F60.equal", vec![x, y], range),
+            ExprKind::Constr { name, args } => call_derived(
+                self.sum,
+                (name, args),
+                kind.entry(),
+                vec![x, y],
+                errs,
+                |arg, errs| self.function(kind, arg, depth, errs),
+                range,
+            ),
+            _ => {
+                errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                    "eq", typ.range,
+                )));
+                Expr::hole(range)
+            }
+        }
+    }
+
+    /// Derives `T.equal` that compares two values with indices that
+    /// can be different.
+    fn derive_equal(&self, errs: &mut Errs) -> concrete::Entry {
+        let range = self.range;
+        let sum = self.sum;
+        let name = Comparison::Equal.entry_name(&sum.name);
+
+        // The indices of the second value are renamed in their types.
+        let renames = FxHashMap::from_iter(
+            sum.indices
+                .iter()
+                .map(|index| (index.name.to_string(), format!("{}_", index.name))),
+        );
+
+        let other_indices = sum.indices.map(|index| {
+            let mut index = index.to_implicit();
+            index.name = index.name.add_underscore();
+            if let Some(typ) = &mut index.typ {
+                substitute_in_expr(typ, &renames);
+            }
+            index
+        });
+
+        let mut args = sum
+            .parameters
+            .extend(&sum.indices)
+            .map(|arg| arg.to_implicit())
+            .extend(&other_indices);
+
+        for comparator in self.comparators(Comparison::Equal) {
+            args.push(comparator);
+        }
+
+        args.push(explicit("a_", sum_type(sum, &sum.indices, range), range));
+        args.push(explicit("b_", sum_type(sum, &other_indices, range), range));
+
+        let prefix = || {
+            prefix_pats(
+                sum,
+                sum.indices.len() * 2,
+                Comparison::Equal.suffix(),
+                range,
+            )
+        };
+
+        let mut rules = Vec::new();
+
+        for cons in &sum.constructors {
+            let comparisons: Vec<_> = compared_fields(cons)
+                .into_iter()
+                .map(|field| {
+                    let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+                    let x = var(&field_name("a", field), range);
+                    let y = var(&field_name("b", field), range);
+                    self.compare(Comparison::Equal, &typ, x, y, 0, errs)
+                })
+                .collect();
+
+            let mut pats: Vec<_> = prefix().collect();
+            pats.push(self.cons_pat(cons, "a"));
+            pats.push(self.cons_pat(cons, "b"));
+
+            rules.push(self.rule(&name, pats, conjunction(comparisons.into_iter(), range)));
+        }
+
+        if sum.constructors.len() != 1 {
+            let mut pats: Vec<_> = prefix().collect();
+            pats.push(pat_hole(range));
+            pats.push(pat_hole(range));
+
+            let body = if sum.constructors.is_empty() {
+                "Bool.true"
+            } else {
+                "Bool.false"
+            };
+            rules.push(self.rule(&name, pats, lib(body, vec![], range)));
+        }
+
+        self.entry(name, args, lib("Bool", vec![], range), rules)
+    }
+
+    fn is_decidable_type(&self) -> bool {
+        self.sum.indices.is_empty()
+            && !self.sum.constructors.is_empty()
+            && self.sum.constructors.iter().all(|cons| {
+                cons.args.iter().all(|field| {
+                    !field.hidden
+                        && !field.erased
+                        && field.typ.as_ref().is_some_and(|typ| self.is_decidable(typ))
+                })
+            })
+    }
+
+    /// Derives `T.equal.decide` with the helpers that prove its
+    /// results.
+    fn derive_decide(&self) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let sum = self.sum;
+        let name = Comparison::Decide.entry_name(&sum.name);
+        let same = name.add_segment("same");
+
+        let mut entries = Vec::new();
+
+        let mut args = sum.parameters.map(|arg| arg.to_implicit());
+
+        for comparator in self.comparators(Comparison::Decide) {
+            args.push(comparator);
+        }
+
+        args.push(explicit("a_", self.self_type(), range));
+        args.push(explicit("b_", self.self_type(), range));
+
+        let typ = decision(self.self_type(), var("a_", range), var("b_", range), range);
+
+        let mut rules = Vec::new();
+
+        for left in &sum.constructors {
+            for right in &sum.constructors {
+                let mut pats: Vec<_> =
+                    prefix_pats(sum, 0, Comparison::Decide.suffix(), range).collect();
+                pats.push(self.cons_pat(left, "a"));
+                pats.push(self.cons_pat(right, "b"));
+
+                let body = if left.name.to_str() != right.name.to_str() {
+                    // The constructors are different so the equality
+                    // rewrites `Unit` into `Empty` with the `same` function.
+                    let a = self.instance_of(left, "a");
+                    let b = self.instance_of(right, "b");
+                    let equal = lib("Equal", vec![self.self_type(), a.clone(), b], range);
+                    let motive = lambda(
+                        "x_",
+                        None,
+                        call(
+                            same.clone(),
+                            self.params().chain([a, var("x_", range)]),
+                            range,
+                        ),
+                        range,
+                    );
+                    let rewrite = lib(
+                        "Equal.rewrite",
+                        vec![var("e_", range), motive, lib("Unit.new", vec![], range)],
+                        range,
+                    );
+                    lib(
+                        "Either.right",
+                        vec![lambda("e_", Some(equal), rewrite, range)],
+                        range,
+                    )
+                } else if left.args.is_empty() {
+                    lib("Either.left", vec![lib("Equal.refl", vec![], range)], range)
+                } else {
+                    let mut spine: Vec<_> = self.params().collect();
+                    spine.extend(
+                        left.args
+                            .iter()
+                            .map(|field| var(&field_name("a", field), range)),
+                    );
+                    spine.extend(
+                        left.args
+                            .iter()
+                            .map(|field| var(&field_name("b", field), range)),
+                    );
+
+                    for field in left.args.iter() {
+                        let typ = field.typ.clone().unwrap();
+                        let x = var(&field_name("a", field), range);
+                        let y = var(&field_name("b", field), range);
+                        spine.push(self.compare(
+                            Comparison::Decide,
+                            &typ,
+                            x,
+                            y,
+                            0,
+                            &mut Vec::new(),
+                        ));
+                    }
+
+                    call(name.add_segment(left.name.to_str()), spine, range)
+                };
+
+                rules.push(self.rule(&name, pats, body));
+            }
+        }
+
+        entries.push(self.entry(name.clone(), args, typ, rules));
+
+        if sum.constructors.len() > 1 {
+            entries.push(self.derive_same(&same));
+        }
+
+        for cons in sum.constructors.iter().filter(|cons| !cons.args.is_empty()) {
+            entries.extend(self.derive_decide_cons(&name, cons));
+        }
+
+        entries
+    }
+
+    /// Derives `T.equal.decide.same` that is `Unit` for values with the
+    /// same constructor and `Empty` otherwise.
+    fn derive_same(&self, name: &QualifiedIdent) -> concrete::Entry {
+        let range = self.range;
+
+        let mut args = self.sum.parameters.map(|arg| arg.to_implicit());
+        args.push(explicit("a_", self.self_type(), range));
+        args.push(explicit("b_", self.self_type(), range));
+
+        let mut rules = Vec::new();
+
+        for cons in &self.sum.constructors {
+            let mut pats: Vec<_> = self.param_pats().collect();
+            pats.push(self.cons_hole_pat(cons));
+            pats.push(self.cons_hole_pat(cons));
+            rules.push(self.rule(name, pats, lib("Unit", vec![], range)));
+        }
+
+        let mut pats: Vec<_> = self.param_pats().collect();
+        pats.push(pat_hole(range));
+        pats.push(pat_hole(range));
+        rules.push(self.rule(name, pats, lib("Empty", vec![], range)));
+
+        self.entry(name.clone(), args, Expr::typ(range), rules)
+    }
+
+    /// Derives `T.equal.decide.C` that joins the decisions about the
+    /// fields of the constructor `C`, and the projections of the
+    /// fields that are used to refute the equality.
+    fn derive_decide_cons(
+        &self,
+        decide: &QualifiedIdent,
+        cons: &Constructor,
+    ) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let name = decide.add_segment(cons.name.to_str());
+        let fields = cons.args.as_slice();
+
+        let field_type = |field: &Argument| field.typ.clone().unwrap();
+        let field_var = |prefix: &str, field: &Argument| var(&field_name(prefix, field), range);
+
+        let a = self.instance_of(cons, "a");
+        let b = self.instance_of(cons, "b");
+
+        let mut args = self.sum.parameters.map(|arg| arg.to_implicit());
+
+        for prefix in ["a", "b"] {
+            for field in fields {
+                args.push(explicit(
+                    &field_name(prefix, field),
+                    field_type(field),
+                    range,
+                ));
+            }
+        }
+
+        for field in fields {
+            let typ = decision(
+                field_type(field),
+                field_var("a", field),
+                field_var("b", field),
+                range,
+            );
+            args.push(explicit(&field_name("d", field), typ, range));
+        }
+
+        let typ = decision(self.self_type(), a.clone(), b.clone(), range);
+
+        let prefix = || {
+            let mut pats: Vec<_> = self.param_pats().collect();
+            for prefix in ["a", "b"] {
+                pats.extend(
+                    fields
+                        .iter()
+                        .map(|field| pat_var(&field_name(prefix, field), range)),
+                );
+            }
+            pats
+        };
+
+        let decision_pat = |side: &str, field: &Argument| {
+            Box::new(Pat {
+                data: PatKind::App(
+                    QualifiedIdent::new_static(side, None, range),
+                    vec![pat_var(&field_name("d", field), range)],
+                ),
+                range,
+            })
+        };
+
+        let mut rules = Vec::new();
+
+        // Every field is equal so the fields of `a` are rewritten one by
+        // one into the fields of `b`.
+        let mut pats = prefix();
+        pats.extend(
+            fields
+                .iter()
+                .map(|field| decision_pat("Either.left", field)),
+        );
+
+        let refl = Box::new(Expr {
+            data: ExprKind::Ann {
+                val: lib("Equal.refl", vec![], range),
+                typ: lib("Equal", vec![self.self_type(), a.clone(), a.clone()], range),
+            },
+            range,
+        });
+
+        let proof = fields.iter().enumerate().fold(refl, |proof, (i, field)| {
+            let values = fields.iter().enumerate().map(|(j, other)| match j.cmp(&i) {
+                std::cmp::Ordering::Less => field_var("b", other),
+                std::cmp::Ordering::Equal => var("x_", range),
+                std::cmp::Ordering::Greater => field_var("a", other),
+            });
+
+            let motive = lambda(
+                "x_",
+                None,
+                lib(
+                    "Equal",
+                    vec![self.self_type(), a.clone(), self.instance(cons, values)],
+                    range,
+                ),
+                range,
+            );
+
+            lib(
+                "Equal.rewrite",
+                vec![field_var("d", field), motive, proof],
+                range,
+            )
+        });
+
+        rules.push(self.rule(&name, pats, lib("Either.left", vec![proof], range)));
+
+        // A field is different so the equality of the values is
+        // refuted with the projection of the field.
+        for (i, field) in fields.iter().enumerate() {
+            let mut pats = prefix();
+            pats.extend(fields.iter().enumerate().map(|(j, other)| {
+                if i == j {
+                    decision_pat("Either.right", other)
+                } else {
+                    pat_hole(range)
+                }
+            }));
+
+            let mut projection_args: Vec<_> = self.params().collect();
+            projection_args.push(field_var("a", field));
+            projection_args.push(var("x_", range));
+
+            let projection = lambda(
+                "x_",
+                None,
+                call(
+                    name.add_segment(field.name.to_str()),
+                    projection_args,
+                    range,
+                ),
+                range,
+            );
+
+            let applied = lib("Equal.apply", vec![projection, var("e_", range)], range);
+            let refutation = app(field_var("d", field), vec![applied], range);
+
+            let equal = lib("Equal", vec![self.self_type(), a.clone(), b.clone()], range);
+            let body = lib(
+                "Either.right",
+                vec![lambda("e_", Some(equal), refutation, range)],
+                range,
+            );
+
+            rules.push(self.rule(&name, pats, body));
+        }
+
+        let mut entries = vec![self.entry(name.clone(), args, typ, rules)];
+
+        for field in fields {
+            entries.push(self.derive_projection(&name, cons, field));
+        }
+
+        entries
+    }
+
+    /// Derives `T.equal.decide.C.f` that gets the field `f` of the
+    /// constructor `C` or a default value for the other constructors.
+    fn derive_projection(
+        &self,
+        decide: &QualifiedIdent,
+        cons: &Constructor,
+        field: &Argument,
+    ) -> concrete::Entry {
+        let range = self.range;
+        let name = decide.add_segment(field.name.to_str());
+        let typ = field.typ.clone().unwrap();
+
+        let mut args = self.sum.parameters.map(|arg| arg.to_implicit());
+        args.push(explicit("default_", typ.clone(), range));
+        args.push(explicit("value_", self.self_type(), range));
+
+        let mut pats: Vec<_> = self.param_pats().collect();
+        pats.push(pat_hole(range));
+        pats.push(self.cons_pat(cons, "a"));
+
+        let mut rules = vec![self.rule(&name, pats, var(&field_name("a", field), range))];
+
+        if self.sum.constructors.len() > 1 {
+            let mut pats: Vec<_> = self.param_pats().collect();
+            pats.push(pat_var("default_", range));
+            pats.push(pat_hole(range));
+            rules.push(self.rule(&name, pats, var("default_", range)));
+        }
+
+        self.entry(name, args, typ, rules)
+    }
+}
+
+/// Derives the equality functions of a sum type. `T.equal` compares
+/// two values and returns a `Bool`, it receives a comparator for each
+/// type parameter. If the type has no indices and every field can be
+/// compared with a proof, `T.equal.decide` is derived too and returns
+/// `Either (Equal (T ps) a b) ((Equal (T ps) a b) -> Empty)`.
+pub fn derive_eq(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let derivation = Derivation { range, sum };
+
+    let mut entries = vec![derivation.derive_equal(&mut errs)];
+
+    if !errs.is_empty() {
+        return (Vec::new(), errs);
+    }
+
+    if derivation.is_decidable_type() {
+        entries.extend(derivation.derive_decide());
+    }
+
+    (entries, errs)
+}
+
+/// Derives the equality functions of a record as a sum type with a
+/// single constructor.
+pub fn derive_eq_rec(range: Range, rec: &RecordDecl) -> (Vec<concrete::Entry>, Errs) {
+    derive_eq(range, &record_as_sum(rec))
+}
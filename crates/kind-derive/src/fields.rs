@@ -0,0 +1,213 @@
+//! Module to derive getters, setters and mutters for sum types. They
+//! access the fields that all of the constructors share, that are the
+//! explicit fields with the same name and the same type in every one
+//! of them, so `!Shape s .color` works for any shape.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::Pat;
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::{Ident, QualifiedIdent};
+use kind_tree::telescope::Telescope;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// The type of `field` in `cons` if it's an explicit field.
+fn field_type<'a>(cons: &'a Constructor, field: &str) -> Option<&'a Expr> {
+    cons.args
+        .iter()
+        .find(|arg| !arg.hidden && arg.name.to_str() == field)
+        .and_then(|arg| arg.typ.as_deref())
+}
+
+/// The fields that are in all of the constructors with the same type.
+pub fn shared_fields(sum: &SumTypeDecl) -> Vec<(Ident, Box<Expr>)> {
+    let Some(first) = sum.constructors.first() else { return Vec::new() };
+
+    explicit_fields(first)
+        .into_iter()
+        .filter_map(|field| {
+            let typ = field.typ.clone()?;
+            let shared = sum.constructors.iter().all(|cons| {
+                field_type(cons, field.name.to_str()).map(|other| other.to_string()) == Some(typ.to_string())
+            });
+            shared.then(|| (field.name.clone(), typ))
+        })
+        .collect()
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn self_type(&self) -> Box<Expr> {
+        let params = self.sum.parameters.iter().map(|param| var(param.name.to_str(), self.range));
+        call(self.sum.name.clone(), params, self.range)
+    }
+
+    /// The arguments of every derived function start with the
+    /// parameters of the type and the value that is accessed.
+    fn args(&self) -> Telescope<Argument> {
+        let mut args = self.sum.parameters.map(|arg| arg.to_implicit());
+        args.push(explicit("scrutinee", self.self_type(), self.range));
+        args
+    }
+
+    fn cons_name(&self, cons: &Constructor) -> QualifiedIdent {
+        self.sum.name.add_segment(cons.name.to_str())
+    }
+
+    fn var_name(field: &Ident) -> String {
+        format!("{}_", field)
+    }
+
+    fn cons_pat(&self, cons: &Constructor) -> Box<Pat> {
+        let fields = explicit_fields(cons);
+        Box::new(Pat {
+            data: concrete::pat::PatKind::App(
+                self.cons_name(cons),
+                fields
+                    .iter()
+                    .map(|field| pat_var(&Self::var_name(&field.name), self.range))
+                    .collect(),
+            ),
+            range: self.range,
+        })
+    }
+
+    /// Builds the constructor again with the field `name` replaced by
+    /// `update` applied to its old value.
+    fn rebuild(&self, cons: &Constructor, name: &Ident, update: impl Fn(Box<Expr>) -> Box<Expr>) -> Box<Expr> {
+        let args = explicit_fields(cons).into_iter().map(|field| {
+            let value = var(&Self::var_name(&field.name), self.range);
+            if field.name.to_str() == name.to_str() {
+                update(value)
+            } else {
+                value
+            }
+        });
+        call(self.cons_name(cons), args, self.range)
+    }
+
+    fn entry(
+        &self,
+        name: QualifiedIdent,
+        args: Telescope<Argument>,
+        typ: Box<Expr>,
+        rules: impl IntoIterator<Item = Box<Rule>>,
+    ) -> Entry {
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ,
+            rules: rules.into_iter().collect(),
+            range: self.range,
+            attrs: Vec::new(),
+            generated_by: Some(self.sum.name.to_string()),
+        }
+    }
+
+    fn rule(&self, name: &QualifiedIdent, pats: impl IntoIterator<Item = Box<Pat>>, body: Box<Expr>) -> Box<Rule> {
+        Box::new(Rule {
+            name: name.clone(),
+            pats: pats.into_iter().collect(),
+            body,
+            range: self.range,
+        })
+    }
+
+    fn getter(&self, field: &Ident, typ: Box<Expr>) -> Entry {
+        let name = self.sum.name.add_segment(field.to_str()).add_segment("get");
+
+        let rules: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .map(|cons| {
+                let body = var(&Self::var_name(field), self.range);
+                self.rule(&name, vec![self.cons_pat(cons)], body)
+            })
+            .collect();
+
+        self.entry(name, self.args(), typ, rules)
+    }
+
+    fn setter(&self, field: &Ident, typ: Box<Expr>) -> Entry {
+        let name = self.sum.name.add_segment(field.to_str()).add_segment("set");
+
+        let mut args = self.args();
+        args.push(explicit("set", typ, self.range));
+
+        let rules: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .map(|cons| {
+                let body = self.rebuild(cons, field, |_| var("_new", self.range));
+                self.rule(&name, vec![self.cons_pat(cons), pat_var("_new", self.range)], body)
+            })
+            .collect();
+
+        self.entry(name, args, self.self_type(), rules)
+    }
+
+    fn mutter(&self, field: &Ident, typ: Box<Expr>) -> Entry {
+        let name = self.sum.name.add_segment(field.to_str()).add_segment("mut");
+
+        let mut args = self.args();
+        args.push(explicit("mut", arrow(typ.clone(), typ, self.range), self.range));
+
+        let rules: Vec<_> = self
+            .sum
+            .constructors
+            .iter()
+            .map(|cons| {
+                let body = self.rebuild(cons, field, |value| app(var("_fn", self.range), vec![value], self.range));
+                self.rule(&name, vec![self.cons_pat(cons), pat_var("_fn", self.range)], body)
+            })
+            .collect();
+
+        self.entry(name, args, self.self_type(), rules)
+    }
+
+    fn derive(&self, derive: &'static str, make: impl Fn(&Self, &Ident, Box<Expr>) -> Entry) -> (Vec<Entry>, Errs) {
+        if !self.sum.indices.is_empty() {
+            let err: Box<dyn Diagnostic> = Box::new(DeriveDiagnostic::IndexedType(derive, self.sum.name.range));
+            return (Vec::new(), vec![err]);
+        }
+
+        let entries = shared_fields(self.sum)
+            .into_iter()
+            .map(|(field, typ)| make(self, &field, typ))
+            .collect();
+
+        (entries, Vec::new())
+    }
+}
+
+/// Derives `T.field.get` for the fields that all of the constructors
+/// of `sum` share.
+pub fn derive_getters_sum(range: Range, sum: &SumTypeDecl) -> (Vec<Entry>, Errs) {
+    Derivation { range, sum }.derive("getters", Derivation::getter)
+}
+
+/// Derives `T.field.set` for the fields that all of the constructors
+/// of `sum` share.
+pub fn derive_setters_sum(range: Range, sum: &SumTypeDecl) -> (Vec<Entry>, Errs) {
+    Derivation { range, sum }.derive("setters", Derivation::setter)
+}
+
+/// Derives `T.field.mut` for the fields that all of the constructors
+/// of `sum` share.
+pub fn derive_mutters_sum(range: Range, sum: &SumTypeDecl) -> (Vec<Entry>, Errs) {
+    Derivation { range, sum }.derive("mutters", Derivation::mutter)
+}
@@ -0,0 +1,365 @@
+//! Module to derive `map` and `fold` for sum types. The map applies a
+//! function to every value of the last type parameter and the fold
+//! replaces every constructor by a function, from the leaves to the
+//! root.
+
+use fxhash::FxHashMap;
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+use kind_tree::telescope::Telescope;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+use crate::subst::substitute_in_expr;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// Splits the arguments of a type in the last one, where the mapped
+/// occurrences can be, and the other ones.
+fn split_last(args: &[Binding]) -> Option<(&Expr, &[Binding])> {
+    match args.split_last() {
+        Some((Binding::Positional(last), rest)) => Some((last, rest)),
+        _ => None,
+    }
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn cons_name(&self, cons: &Constructor) -> QualifiedIdent {
+        self.sum.name.add_segment(cons.name.to_str())
+    }
+
+    /// The type `T ps is` of the values of the type.
+    fn self_type(&self) -> Box<Expr> {
+        let args: Vec<_> = self
+            .sum
+            .parameters
+            .extend(&self.sum.indices)
+            .iter()
+            .map(|arg| var(arg.name.to_str(), self.range))
+            .collect();
+        call(self.sum.name.clone(), args, self.range)
+    }
+
+    /// The patterns of the parameters and of the indices.
+    fn prefix_pats(&self) -> impl Iterator<Item = Box<Pat>> + '_ {
+        self.sum
+            .parameters
+            .iter()
+            .chain(self.sum.indices.iter())
+            .map(|arg| pat_var(arg.name.to_str(), self.range))
+    }
+
+    /// The pattern of a constructor with all of its arguments that
+    /// binds every field `f` as `value.f`.
+    fn cons_pat(&self, cons: &Constructor) -> Box<Pat> {
+        let range = self.range;
+        let mut args: Vec<_> = self.sum.parameters.iter().map(|_| pat_hole(range)).collect();
+
+        if cons.typ.is_none() {
+            args.extend(self.sum.indices.iter().map(|_| pat_hole(range)));
+        }
+
+        args.extend(
+            cons.args
+                .iter()
+                .map(|field| pat_var(&field_name("value", field), range)),
+        );
+
+        Box::new(Pat {
+            data: PatKind::App(self.cons_name(cons), args),
+            range,
+        })
+    }
+
+    fn rule(
+        &self,
+        name: &QualifiedIdent,
+        pats: impl IntoIterator<Item = Box<Pat>>,
+        body: Box<Expr>,
+    ) -> Box<Rule> {
+        Box::new(Rule {
+            name: name.clone(),
+            pats: pats.into_iter().collect(),
+            body,
+            range: self.range,
+        })
+    }
+
+    fn entry(
+        &self,
+        name: QualifiedIdent,
+        args: Telescope<Argument>,
+        typ: Box<Expr>,
+        rules: impl IntoIterator<Item = Box<Rule>>,
+    ) -> concrete::Entry {
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ,
+            rules: rules.into_iter().collect(),
+            range: self.range,
+            attrs: Vec::new(),
+            generated_by: Some(self.sum.name.to_string()),
+        }
+    }
+
+    /// Applies `f_` to the values of the type parameter `param` that
+    /// are inside of the value `x` of the type `typ`.
+    fn map(&self, param: &str, typ: &Expr, x: Box<Expr>, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        let range = self.range;
+
+        if !mentions(typ, &[param.to_string()]) {
+            return x;
+        }
+
+        match &typ.data {
+            ExprKind::Var { name } if name.to_str() == param => app(var("f_", range), vec![x], range),
+            ExprKind::Constr { name, .. } if name.to_string() == self.sum.name.to_string() => {
+                call(name.add_segment("map"), vec![var("f_", range), x], range)
+            }
+            ExprKind::Constr { name, args } => match split_last(args) {
+                Some((last, rest)) if !rest.iter().any(|arg| mentions_binding(arg, param)) => {
+                    let y = format!("x_{}", depth);
+                    let body = self.map(param, last, var(&y, range), depth + 1, errs);
+                    call(
+                        name.add_segment("map"),
+                        vec![lambda(&y, None, body, range), x],
+                        range,
+                    )
+                }
+                _ => self.unsupported("functor", param, typ, errs),
+            },
+            _ => self.unsupported("functor", param, typ, errs),
+        }
+    }
+
+    /// Folds the values of the type that are inside of the value `x`
+    /// of the type `typ`. Returns the folded value and its type.
+    fn fold(&self, typ: &Expr, x: Box<Expr>, depth: usize, errs: &mut Errs) -> (Box<Expr>, Box<Expr>) {
+        let range = self.range;
+        let own = self.sum.name.to_string();
+
+        if !mentions(typ, std::slice::from_ref(&own)) {
+            return (x, Box::new(typ.clone()));
+        }
+
+        match &typ.data {
+            ExprKind::Constr { name, .. } if name.to_string() == own => {
+                let mut args: Vec<_> = self
+                    .sum
+                    .constructors
+                    .iter()
+                    .map(|cons| var(&format!("{}_", cons.name), range))
+                    .collect();
+                args.push(x);
+                (call(name.add_segment("fold"), args, range), var("result_", range))
+            }
+            ExprKind::Constr { name, args } => match split_last(args) {
+                Some((last, rest)) if !rest.iter().any(|arg| mentions_binding(arg, &own)) => {
+                    let y = format!("x_{}", depth);
+                    let (body, last_typ) = self.fold(last, var(&y, range), depth + 1, errs);
+
+                    let mut args = rest.to_vec();
+                    args.push(Binding::Positional(last_typ));
+
+                    let value = call(
+                        name.add_segment("map"),
+                        vec![lambda(&y, None, body, range), x],
+                        range,
+                    );
+                    (value, Expr::cons(name.clone(), args, range))
+                }
+                _ => {
+                    let hole = self.unsupported("fold", &own, typ, errs);
+                    (hole.clone(), hole)
+                }
+            },
+            _ => {
+                let hole = self.unsupported("fold", &own, typ, errs);
+                (hole.clone(), hole)
+            }
+        }
+    }
+
+    fn unsupported(&self, derive: &'static str, name: &str, typ: &Expr, errs: &mut Errs) -> Box<Expr> {
+        errs.push(Box::new(DeriveDiagnostic::UnsupportedOccurrence(
+            derive,
+            name.to_string(),
+            typ.range,
+        )));
+        Expr::hole(self.range)
+    }
+
+    /// Derives `T.map` that maps the last type parameter of the type.
+    fn derive_map(&self, errs: &mut Errs) -> Option<concrete::Entry> {
+        let range = self.range;
+        let sum = self.sum;
+        let name = sum.name.add_segment("map");
+
+        let Some(param) = sum.parameters.iter().rfind(|param| is_type(param)) else {
+            errs.push(Box::new(DeriveDiagnostic::NoTypeParameter(
+                "functor",
+                sum.name.range,
+            )));
+            return None;
+        };
+
+        let target = format!("{}_", param.name);
+
+        let mut args = sum.parameters.map(|arg| arg.to_implicit());
+        args.push(explicit(&target, Expr::typ(range), range).to_implicit());
+        args = args.extend(&sum.indices.map(|arg| arg.to_implicit()));
+
+        args.push(explicit(
+            "f_",
+            arrow(var(param.name.to_str(), range), var(&target, range), range),
+            range,
+        ));
+        args.push(explicit("value_", self.self_type(), range));
+
+        // The type of the result is the type of the value with the
+        // parameter replaced by the target.
+        let renames = FxHashMap::from_iter([(param.name.to_string(), target.clone())]);
+        let mut typ = self.self_type();
+        substitute_in_expr(&mut typ, &renames);
+
+        let mut rules = Vec::new();
+
+        for cons in &sum.constructors {
+            let fields: Vec<_> = explicit_fields(cons)
+                .into_iter()
+                .map(|field| {
+                    let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+                    let value = var(&field_name("value", field), range);
+                    self.map(param.name.to_str(), &typ, value, 0, errs)
+                })
+                .collect();
+
+            let mut pats: Vec<_> = self.prefix_pats().collect();
+            pats.insert(sum.parameters.len(), pat_var(&target, range));
+            pats.push(pat_var("f_", range));
+            pats.push(self.cons_pat(cons));
+
+            rules.push(self.rule(&name, pats, call(self.cons_name(cons), fields, range)));
+        }
+
+        Some(self.entry(name, args, typ, rules))
+    }
+
+    /// Derives `T.fold` that receives a function for each constructor
+    /// and applies it to the fields of the constructor with the values
+    /// of the type already folded.
+    fn derive_fold(&self, errs: &mut Errs) -> concrete::Entry {
+        let range = self.range;
+        let sum = self.sum;
+        let name = sum.name.add_segment("fold");
+
+        let mut args = sum.parameters.extend(&sum.indices).map(|arg| arg.to_implicit());
+        args.push(explicit("result_", Expr::typ(range), range).to_implicit());
+
+        let mut rules = Vec::new();
+        let mut cases = Vec::new();
+
+        for cons in &sum.constructors {
+            let folded: Vec<_> = cons
+                .args
+                .iter()
+                .map(|field| {
+                    let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+                    let value = var(&field_name("value", field), range);
+                    self.fold(&typ, value, 0, errs)
+                })
+                .collect();
+
+            let case_typ = cons
+                .args
+                .iter()
+                .zip(&folded)
+                .rfold(var("result_", range), |out, (field, (_, typ))| {
+                    Expr::all(field.name.clone(), typ.clone(), out, field.erased, range)
+                });
+
+            cases.push(explicit(&format!("{}_", cons.name), case_typ, range));
+
+            let spine = cons
+                .args
+                .iter()
+                .zip(folded)
+                .map(|(field, (value, _))| AppBinding {
+                    data: value,
+                    erased: field.erased,
+                })
+                .collect();
+
+            let mut pats: Vec<_> = self.prefix_pats().collect();
+            pats.push(pat_var("result_", range));
+            pats.extend(
+                sum.constructors
+                    .iter()
+                    .map(|cons| pat_var(&format!("{}_", cons.name), range)),
+            );
+            pats.push(self.cons_pat(cons));
+
+            let body = Expr::app(var(&format!("{}_", cons.name), range), spine, range);
+
+            rules.push(self.rule(&name, pats, body));
+        }
+
+        for case in cases {
+            args.push(case);
+        }
+
+        args.push(explicit("value_", self.self_type(), range));
+
+        self.entry(name, args, var("result_", range), rules)
+    }
+}
+
+fn mentions_binding(binding: &Binding, name: &str) -> bool {
+    match binding {
+        Binding::Positional(expr) | Binding::Named(_, _, expr) => mentions(expr, &[name.to_string()]),
+    }
+}
+
+/// Derives `T.map` that applies a function to every value of the last
+/// type parameter of a sum type. The values can be fields, be inside
+/// of values of the type itself or inside of the last argument of
+/// other types that derive `functor`, like `List (Tree t)`.
+pub fn derive_functor(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let entry = Derivation { range, sum }.derive_map(&mut errs);
+
+    match entry {
+        Some(entry) if errs.is_empty() => (vec![entry], errs),
+        _ => (Vec::new(), errs),
+    }
+}
+
+/// Derives `T.fold` that replaces each constructor of a sum type by a
+/// function that receives the fields of the constructor. The values of
+/// the type inside of the fields are folded first, even if they are
+/// inside of the last argument of a type that derives `functor`.
+pub fn derive_fold(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let entry = Derivation { range, sum }.derive_fold(&mut errs);
+
+    if errs.is_empty() {
+        (vec![entry], errs)
+    } else {
+        (Vec::new(), errs)
+    }
+}
@@ -0,0 +1,195 @@
+//! Module to derive a hash function for sum types and records. The
+//! hash of a value mixes the position of its constructor in the
+//! declaration with the hashes of its fields.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::Operator;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// The multiplier that mixes the hashes of the fields. The result of
+/// the derived functions depends on it, so it must not change.
+const MULTIPLIER: u64 = 1000003;
+
+fn binary(op: Operator, fst: Box<Expr>, snd: Box<Expr>, range: Range) -> Box<Expr> {
+    Box::new(Expr {
+        data: ExprKind::Binary { op, fst, snd },
+        range,
+    })
+}
+
+/// Mixes the hashes as `(+ (* hash MULTIPLIER) field)` from the
+/// position of the constructor.
+fn mix(tag: usize, hashes: impl Iterator<Item = Box<Expr>>, range: Range) -> Box<Expr> {
+    hashes.fold(number(tag as u64, range), |hash, field| {
+        let scaled = binary(Operator::Mul, hash, number(MULTIPLIER, range), range);
+        binary(Operator::Add, scaled, field, range)
+    })
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    /// A function that hashes the values of `typ`.
+    fn function(&self, typ: &Expr, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                var(&param_function(name, "hash"), self.range)
+            }
+            _ => {
+                let x = format!("x_{}", depth);
+                let body = self.hash(typ, var(&x, self.range), depth + 1, errs);
+                lambda(&x, None, body, self.range)
+            }
+        }
+    }
+
+    /// Hashes the value `x` of the type `typ`. A `U60` is its own hash.
+    fn hash(&self, typ: &Expr, x: Box<Expr>, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        let range = self.range;
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                app(var(&param_function(name, "hash"), range), vec![x], range)
+            }
+            ExprKind::Lit {
+                lit: Literal::NumTypeU60,
+            } => x,
+            ExprKind::Lit {
+                lit: Literal::NumTypeF60,
+            } => lib("F60.hash", vec![x], range),
+            ExprKind::Constr { name, args } => call_derived(
+                self.sum,
+                (name, args),
+                "hash",
+                vec![x],
+                errs,
+                |arg, errs| self.function(arg, depth, errs),
+                range,
+            ),
+            _ => {
+                errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                    "hash", typ.range,
+                )));
+                Expr::hole(range)
+            }
+        }
+    }
+
+    fn derive(&self, errs: &mut Errs) -> concrete::Entry {
+        let range = self.range;
+        let sum = self.sum;
+        let name = sum.name.add_segment("hash");
+
+        let mut args = sum
+            .parameters
+            .extend(&sum.indices)
+            .map(|arg| arg.to_implicit());
+
+        for param in type_params(self.sum) {
+            let typ = arrow(var(param.name.to_str(), range), u60(range), range);
+            args.push(explicit(&param_function(&param.name, "hash"), typ, range));
+        }
+
+        args.push(explicit(
+            "value_",
+            sum_type(sum, &sum.indices, range),
+            range,
+        ));
+
+        let prefix = || prefix_pats(sum, sum.indices.len(), "hash", range);
+
+        let mut rules = Vec::new();
+
+        for (tag, cons) in sum.constructors.iter().enumerate() {
+            let fields: Vec<_> = explicit_fields(cons);
+
+            let hashes = fields.iter().filter(|field| !field.erased).map(|field| {
+                let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+                self.hash(&typ, var(&field_name("value", field), range), 0, errs)
+            });
+
+            let mut pats: Vec<_> = prefix().collect();
+            pats.push(Box::new(Pat {
+                data: PatKind::App(
+                    sum.name.add_segment(cons.name.to_str()),
+                    fields
+                        .iter()
+                        .map(|field| {
+                            if field.erased {
+                                pat_hole(range)
+                            } else {
+                                pat_var(&field_name("value", field), range)
+                            }
+                        })
+                        .collect(),
+                ),
+                range,
+            }));
+
+            rules.push(Box::new(Rule {
+                name: name.clone(),
+                pats,
+                body: mix(tag, hashes, range),
+                range,
+            }));
+        }
+
+        if sum.constructors.is_empty() {
+            let mut pats: Vec<_> = prefix().collect();
+            pats.push(pat_hole(range));
+            rules.push(Box::new(Rule {
+                name: name.clone(),
+                pats,
+                body: number(0, range),
+                range,
+            }));
+        }
+
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ: u60(range),
+            rules,
+            range,
+            attrs: Vec::new(),
+            generated_by: Some(sum.name.to_string()),
+        }
+    }
+}
+
+/// Derives `T.hash` that hashes a value of a sum type into a `U60`.
+/// The hash of `(T.C f1 f2)`, where `C` is the constructor at the
+/// position `i` of the declaration, is `(i * M + h1) * M + h2` modulo
+/// 2^60 with `M = 1000003` and the hashes of the fields `h1` and `h2`.
+/// It receives a function that hashes each type parameter and uses the
+/// `hash` function of the types of the other fields.
+pub fn derive_hash(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let entry = Derivation { range, sum }.derive(&mut errs);
+
+    if errs.is_empty() {
+        (vec![entry], errs)
+    } else {
+        (Vec::new(), errs)
+    }
+}
+
+/// Derives `T.hash` for a record as a sum type with a single
+/// constructor.
+pub fn derive_hash_rec(range: Range, rec: &RecordDecl) -> (Vec<concrete::Entry>, Errs) {
+    derive_hash(range, &record_as_sum(rec))
+}
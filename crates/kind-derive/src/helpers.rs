@@ -0,0 +1,278 @@
+//! Small builders of the concrete syntax that are shared by the
+//! derivations.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatIdent, PatKind};
+use kind_tree::concrete::visitor::Visitor;
+use kind_tree::concrete::*;
+use kind_tree::symbol::{Ident, QualifiedIdent};
+use kind_tree::telescope::Telescope;
+
+use crate::diagnostic::DeriveDiagnostic;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+pub(crate) fn ident(name: &str, range: Range) -> Ident {
+    Ident::new_by_sugar(name, range)
+}
+
+pub(crate) fn var(name: &str, range: Range) -> Box<Expr> {
+    Expr::var(ident(name, range))
+}
+
+pub(crate) fn number(num: u64, range: Range) -> Box<Expr> {
+    Box::new(Expr {
+        data: ExprKind::Lit {
+            lit: Literal::NumU60(num),
+        },
+        range,
+    })
+}
+
+pub(crate) fn u60(range: Range) -> Box<Expr> {
+    Box::new(Expr {
+        data: ExprKind::Lit {
+            lit: Literal::NumTypeU60,
+        },
+        range,
+    })
+}
+
+pub(crate) fn string(str: String, range: Range) -> Box<Expr> {
+    Box::new(Expr {
+        data: ExprKind::Lit {
+            lit: Literal::String(str),
+        },
+        range,
+    })
+}
+
+pub(crate) fn call(
+    name: QualifiedIdent,
+    args: impl IntoIterator<Item = Box<Expr>>,
+    range: Range,
+) -> Box<Expr> {
+    Expr::cons(
+        name,
+        args.into_iter().map(Binding::Positional).collect(),
+        range,
+    )
+}
+
+pub(crate) fn lib(
+    name: &str,
+    args: impl IntoIterator<Item = Box<Expr>>,
+    range: Range,
+) -> Box<Expr> {
+    call(QualifiedIdent::new_static(name, None, range), args, range)
+}
+
+pub(crate) fn app(
+    fun: Box<Expr>,
+    args: impl IntoIterator<Item = Box<Expr>>,
+    range: Range,
+) -> Box<Expr> {
+    Expr::app(
+        fun,
+        args.into_iter().map(AppBinding::explicit).collect(),
+        range,
+    )
+}
+
+/// Joins the `parts` of a string with the function `concat`, that is
+/// `String.concat` or a generated name of it when the desugarer has to
+/// check that it's implemented.
+pub(crate) fn concat(
+    concat: &QualifiedIdent,
+    parts: impl DoubleEndedIterator<Item = Box<Expr>>,
+    range: Range,
+) -> Box<Expr> {
+    let mut parts = parts.rev();
+    let last = parts.next().unwrap_or_else(|| string(String::new(), range));
+    parts.fold(last, |rest, part| {
+        call(concat.clone(), vec![part, rest], range)
+    })
+}
+
+pub(crate) fn lambda(
+    name: &str,
+    typ: Option<Box<Expr>>,
+    body: Box<Expr>,
+    range: Range,
+) -> Box<Expr> {
+    Expr::lambda(ident(name, range), typ, body, false, range)
+}
+
+pub(crate) fn arrow(typ: Box<Expr>, body: Box<Expr>, range: Range) -> Box<Expr> {
+    Box::new(Expr {
+        data: ExprKind::All {
+            param: None,
+            typ,
+            body,
+            erased: false,
+        },
+        range,
+    })
+}
+
+pub(crate) fn pat_var(name: &str, range: Range) -> Box<Pat> {
+    Box::new(Pat {
+        data: PatKind::Var(PatIdent(ident(name, range))),
+        range,
+    })
+}
+
+pub(crate) fn pat_hole(range: Range) -> Box<Pat> {
+    Box::new(Pat {
+        data: PatKind::Hole,
+        range,
+    })
+}
+
+pub(crate) fn explicit(name: &str, typ: Box<Expr>, range: Range) -> Argument {
+    Argument::new_explicit(ident(name, range), typ, range)
+}
+
+pub(crate) fn is_type(arg: &Argument) -> bool {
+    match &arg.typ {
+        None => true,
+        Some(typ) => matches!(typ.data, ExprKind::Lit { lit: Literal::Type }),
+    }
+}
+
+/// If `name` is one of the parameters of the type that are types.
+pub(crate) fn is_type_param(sum: &SumTypeDecl, name: &Ident) -> bool {
+    sum.parameters
+        .iter()
+        .any(|param| param.name.to_str() == name.to_str() && is_type(param))
+}
+
+pub(crate) fn type_params(sum: &SumTypeDecl) -> Vec<&Argument> {
+    sum.parameters
+        .iter()
+        .filter(|param| is_type(param))
+        .collect()
+}
+
+/// The name of the function `suffix` that is received for a type
+/// parameter, e.g. `a_show`.
+pub(crate) fn param_function(param: &Ident, suffix: &str) -> String {
+    format!("{}_{}", param, suffix)
+}
+
+/// The type `T ps is` of the values of `sum` with the `indices`.
+pub(crate) fn sum_type(
+    sum: &SumTypeDecl,
+    indices: &Telescope<Argument>,
+    range: Range,
+) -> Box<Expr> {
+    let params = sum
+        .parameters
+        .iter()
+        .map(|param| var(param.name.to_str(), range));
+    let indices = indices.iter().map(|index| var(index.name.to_str(), range));
+    call(sum.name.clone(), params.chain(indices), range)
+}
+
+/// The patterns of the parameters, of `holes` ignored indices and of the
+/// functions `suffix` of the type parameters, that start the rules of a
+/// derived function.
+pub(crate) fn prefix_pats(
+    sum: &SumTypeDecl,
+    holes: usize,
+    suffix: &str,
+    range: Range,
+) -> impl Iterator<Item = Box<Pat>> {
+    let mut pats: Vec<_> = sum
+        .parameters
+        .iter()
+        .map(|param| pat_var(param.name.to_str(), range))
+        .collect();
+    pats.extend((0..holes).map(|_| pat_hole(range)));
+    pats.extend(
+        type_params(sum)
+            .into_iter()
+            .map(|param| pat_var(&param_function(&param.name, suffix), range)),
+    );
+    pats.into_iter()
+}
+
+/// Calls the derived function `entry` of the type `name` on the `values`
+/// with the functions of the arguments `args` of the type, made with
+/// `function`.
+pub(crate) fn call_derived(
+    sum: &SumTypeDecl,
+    (name, args): (&QualifiedIdent, &[Binding]),
+    entry: &str,
+    values: impl IntoIterator<Item = Box<Expr>>,
+    errs: &mut Errs,
+    mut function: impl FnMut(&Expr, &mut Errs) -> Box<Expr>,
+    range: Range,
+) -> Box<Expr> {
+    // The recursive occurrences only receive the functions of the type
+    // parameters, the indices are hidden.
+    let own = name.to_string() == sum.name.to_string();
+    let mut spine = Vec::new();
+
+    for (i, arg) in args.iter().enumerate() {
+        if own && !sum.parameters.as_slice().get(i).is_some_and(is_type) {
+            continue;
+        }
+        match arg {
+            Binding::Positional(arg) => spine.push(function(arg, errs)),
+            Binding::Named(_, name, _) => errs.push(Box::new(
+                DeriveDiagnostic::CannotUseNamedVariable(name.range),
+            )),
+        }
+    }
+
+    spine.extend(values);
+    call(name.add_segment(entry), spine, range)
+}
+
+/// The fields that are written in the patterns of a constructor.
+pub(crate) fn explicit_fields(cons: &Constructor) -> Vec<&Argument> {
+    cons.args.iter().filter(|arg| !arg.hidden).collect()
+}
+
+pub(crate) fn field_name(prefix: &str, field: &Argument) -> String {
+    format!("{}.{}", prefix, field.name)
+}
+
+/// A record seen as a sum type with a single constructor.
+pub(crate) fn record_as_sum(rec: &RecordDecl) -> SumTypeDecl {
+    SumTypeDecl {
+        name: rec.name.clone(),
+        docs: rec.docs.clone(),
+        parameters: rec.parameters.clone(),
+        indices: Telescope::default(),
+        constructors: vec![rec.get_constructor()],
+        attrs: rec.attrs.clone(),
+    }
+}
+
+/// Finds if any of the `names` is used by an expression, as a
+/// variable or as the name of a type or function.
+struct Uses<'a> {
+    names: &'a [String],
+    used: bool,
+}
+
+impl<'a> Visitor for Uses<'a> {
+    fn visit_ident(&mut self, ident: &mut Ident) {
+        self.used |= self.names.iter().any(|name| name == ident.to_str());
+    }
+
+    fn visit_qualified_ident(&mut self, ident: &mut QualifiedIdent) {
+        self.used |= self.names.iter().any(|name| *name == ident.to_string());
+    }
+}
+
+pub(crate) fn mentions(expr: &Expr, names: &[String]) -> bool {
+    let mut uses = Uses { names, used: false };
+    uses.visit_expr(&mut expr.clone());
+    uses.used
+}
@@ -0,0 +1,125 @@
+//! Module to derive an induction principle out of a sum type
+//! declaration. It's the eliminator of [derive_match] with an
+//! induction hypothesis for each recursive field of the constructors.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::Ident;
+
+use crate::matching::derive_match;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// The indices of a recursive occurrence of the type, or `None` if
+/// the type of the field is not the type being declared.
+fn recursive_indices<'a>(sum: &SumTypeDecl, typ: &'a Expr) -> Option<Vec<&'a Expr>> {
+    match &typ.data {
+        ExprKind::Constr { name, args }
+            if name.to_string() == sum.name.to_string()
+                && args.len() == sum.parameters.len() + sum.indices.len() =>
+        {
+            args[sum.parameters.len()..]
+                .iter()
+                .map(|arg| match arg {
+                    Binding::Positional(expr) => Some(expr.as_ref()),
+                    Binding::Named(..) => None,
+                })
+                .collect()
+        }
+        _ => None,
+    }
+}
+
+/// Puts the hypotheses before the return type of a case that binds
+/// `depth` fields.
+fn add_hypotheses(typ: &mut Box<Expr>, depth: usize, hypotheses: &[(Ident, Box<Expr>)], range: Range) {
+    match &mut typ.data {
+        ExprKind::All { body, .. } if depth > 0 => add_hypotheses(body, depth - 1, hypotheses, range),
+        _ => {
+            *typ = hypotheses.iter().rfold(typ.clone(), |out, (name, hypothesis)| {
+                Expr::all(name.clone(), hypothesis.clone(), out, false, range)
+            })
+        }
+    }
+}
+
+/// Derives `T.induction` from a sum type declaration. The case of each
+/// constructor receives, after its fields, the motive applied to every
+/// field whose type is the type being declared.
+pub fn derive_induction(range: Range, sum: &SumTypeDecl) -> (concrete::Entry, Errs) {
+    let (mut entry, errs) = derive_match(range, sum);
+
+    entry.name = sum.name.add_segment("induction");
+
+    if !errs.is_empty() {
+        return (entry, errs);
+    }
+
+    let motive = Ident::new_static("motive", range);
+    let first_case = sum.parameters.len() + sum.indices.len() + 2;
+
+    for (i, (cons, rule)) in sum.constructors.iter().zip(entry.rules.iter_mut()).enumerate() {
+        rule.name = entry.name.clone();
+
+        let fields = if cons.typ.is_some() {
+            cons.args.clone()
+        } else {
+            sum.indices.extend(&cons.args)
+        };
+
+        let recursive: Vec<_> = cons
+            .args
+            .iter()
+            .filter(|arg| !arg.erased)
+            .filter_map(|arg| Some((arg, recursive_indices(sum, arg.typ.as_ref()?)?)))
+            .collect();
+
+        let hypotheses: Vec<_> = recursive
+            .iter()
+            .map(|(arg, indices)| {
+                let mut args: Vec<_> = indices
+                    .iter()
+                    .map(|index| AppBinding::explicit(Box::new((*index).clone())))
+                    .collect();
+                args.push(AppBinding::explicit(Expr::var(arg.name.clone())));
+                let hypothesis = Expr::app(Expr::var(motive.clone()), args, range);
+                (arg.name.add_segment("ih"), hypothesis)
+            })
+            .collect();
+
+        let case = &mut entry.args.get_vec()[first_case + i];
+
+        if let Some(typ) = &mut case.typ {
+            add_hypotheses(typ, fields.len(), &hypotheses, range);
+        }
+
+        // The rules of the eliminator bind the fields with a trailing
+        // underscore and the cases with the names of the constructors.
+        let ExprKind::Ann { val, .. } = &mut rule.body.data else {
+            continue;
+        };
+
+        let ExprKind::App { args, .. } = &mut val.data else {
+            continue;
+        };
+
+        for (arg, _) in &recursive {
+            let mut spine = vec![
+                Binding::Positional(Expr::var(arg.name.add_underscore())),
+                Binding::Positional(Expr::var(Ident::generate("motive"))),
+            ];
+            spine.extend(
+                sum.constructors
+                    .iter()
+                    .map(|cons| Binding::Positional(Expr::var(cons.name.clone()))),
+            );
+            args.push(AppBinding::explicit(Expr::cons(entry.name.clone(), spine, range)));
+        }
+    }
+
+    (entry, errs)
+}
@@ -2,10 +2,22 @@
 
 //! Utility to derive functions from their definitions.
 
+pub mod confusion;
 pub mod diagnostic;
+pub mod enumeration;
+pub mod equal;
+pub mod fields;
+pub mod functor;
+mod helpers;
+pub mod induction;
 pub mod matching;
 pub mod open;
+pub mod order;
+pub mod reflect;
 pub mod subst;
 pub mod getters;
+pub mod hash;
+pub mod serialize;
 pub mod setters;
+pub mod show;
 pub mod mutters;
\ No newline at end of file
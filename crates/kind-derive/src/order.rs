@@ -0,0 +1,324 @@
+//! Module to derive a total order for sum types and records. Values
+//! are ordered by the position of their constructors in the
+//! declaration and then by their fields, from the first to the last.
+
+use fxhash::FxHashMap;
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+use crate::subst::substitute_in_expr;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// Joins the comparisons of the fields with `Cmp.then`, so the first
+/// one that is not `Cmp.eql` decides the order.
+fn lexicographic(
+    comparisons: impl DoubleEndedIterator<Item = Box<Expr>>,
+    range: Range,
+) -> Box<Expr> {
+    let mut comparisons = comparisons.rev();
+    match comparisons.next() {
+        None => lib("Cmp.eql", vec![], range),
+        Some(last) => comparisons.fold(last, |rest, comparison| {
+            lib("Cmp.then", vec![comparison, rest], range)
+        }),
+    }
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn cons_pat(&self, cons: &Constructor, prefix: &str) -> Box<Pat> {
+        let fields = explicit_fields(cons)
+            .into_iter()
+            .map(|field| {
+                if field.erased {
+                    pat_hole(self.range)
+                } else {
+                    pat_var(&field_name(prefix, field), self.range)
+                }
+            })
+            .collect();
+
+        Box::new(Pat {
+            data: PatKind::App(self.sum.name.add_segment(cons.name.to_str()), fields),
+            range: self.range,
+        })
+    }
+
+    /// A function that compares two values of `typ`.
+    fn function(&self, typ: &Expr, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                var(&param_function(name, "compare"), self.range)
+            }
+            _ => {
+                let (x, y) = (format!("x_{}", depth), format!("y_{}", depth));
+                let body = self.compare(
+                    typ,
+                    var(&x, self.range),
+                    var(&y, self.range),
+                    depth + 1,
+                    errs,
+                );
+                lambda(&x, None, lambda(&y, None, body, self.range), self.range)
+            }
+        }
+    }
+
+    /// Compares the values `x` and `y` of the type `typ`.
+    fn compare(
+        &self,
+        typ: &Expr,
+        x: Box<Expr>,
+        y: Box<Expr>,
+        depth: usize,
+        errs: &mut Errs,
+    ) -> Box<Expr> {
+        let range = self.range;
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => app(
+                var(&param_function(name, "compare"), range),
+                vec![x, y],
+                range,
+            ),
+            ExprKind::Lit {
+                lit: Literal::NumTypeU60,
+            } => lib("U60.cmp", vec![x, y], range),
+            ExprKind::Lit {
+                lit: Literal::NumTypeF60,
+            } => lib("F60.cmp", vec![x, y], range),
+            ExprKind::Constr { name, args } => call_derived(
+                self.sum,
+                (name, args),
+                "compare",
+                vec![x, y],
+                errs,
+                |arg, errs| self.function(arg, depth, errs),
+                range,
+            ),
+            _ => {
+                errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                    "ord", typ.range,
+                )));
+                Expr::hole(range)
+            }
+        }
+    }
+
+    /// Derives `T.compare.tag` that is the position of the constructor
+    /// of a value in the declaration.
+    fn derive_tag(&self, name: &QualifiedIdent) -> concrete::Entry {
+        let range = self.range;
+        let sum = self.sum;
+
+        let mut args = sum
+            .parameters
+            .extend(&sum.indices)
+            .map(|arg| arg.to_implicit());
+        args.push(explicit(
+            "value_",
+            sum_type(sum, &sum.indices, range),
+            range,
+        ));
+
+        let rules = sum
+            .constructors
+            .iter()
+            .enumerate()
+            .map(|(i, cons)| {
+                let mut pats: Vec<_> = sum
+                    .parameters
+                    .iter()
+                    .map(|param| pat_var(param.name.to_str(), range))
+                    .collect();
+                pats.extend(sum.indices.iter().map(|_| pat_hole(range)));
+                pats.push(Box::new(Pat {
+                    data: PatKind::App(
+                        sum.name.add_segment(cons.name.to_str()),
+                        explicit_fields(cons)
+                            .iter()
+                            .map(|_| pat_hole(range))
+                            .collect(),
+                    ),
+                    range,
+                }));
+                Box::new(Rule {
+                    name: name.clone(),
+                    pats,
+                    body: number(i as u64, range),
+                    range,
+                })
+            })
+            .collect();
+
+        Entry {
+            name: name.clone(),
+            docs: Vec::new(),
+            args,
+            typ: Box::new(Expr {
+                data: ExprKind::Lit {
+                    lit: Literal::NumTypeU60,
+                },
+                range,
+            }),
+            rules,
+            range,
+            attrs: Vec::new(),
+            generated_by: Some(sum.name.to_string()),
+        }
+    }
+
+    /// Derives `T.compare` that compares two values with indices that
+    /// can be different.
+    fn derive_compare(&self, errs: &mut Errs) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let sum = self.sum;
+        let name = sum.name.add_segment("compare");
+        let tag = name.add_segment("tag");
+
+        // The indices of the second value are renamed in their types.
+        let renames = FxHashMap::from_iter(
+            sum.indices
+                .iter()
+                .map(|index| (index.name.to_string(), format!("{}_", index.name))),
+        );
+
+        let other_indices = sum.indices.map(|index| {
+            let mut index = index.to_implicit();
+            index.name = index.name.add_underscore();
+            if let Some(typ) = &mut index.typ {
+                substitute_in_expr(typ, &renames);
+            }
+            index
+        });
+
+        let mut args = sum
+            .parameters
+            .extend(&sum.indices)
+            .map(|arg| arg.to_implicit())
+            .extend(&other_indices);
+
+        for param in type_params(self.sum) {
+            let typ = var(param.name.to_str(), range);
+            let cmp = arrow(
+                typ.clone(),
+                arrow(typ, lib("Cmp", vec![], range), range),
+                range,
+            );
+            args.push(explicit(
+                &param_function(&param.name, "compare"),
+                cmp,
+                range,
+            ));
+        }
+
+        args.push(explicit("a_", sum_type(sum, &sum.indices, range), range));
+        args.push(explicit("b_", sum_type(sum, &other_indices, range), range));
+
+        let prefix = || prefix_pats(sum, sum.indices.len() * 2, "compare", range);
+
+        let mut rules = Vec::new();
+
+        for cons in &sum.constructors {
+            let comparisons = cons
+                .args
+                .iter()
+                .filter(|field| !field.hidden && !field.erased)
+                .map(|field| {
+                    let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+                    let x = var(&field_name("a", field), range);
+                    let y = var(&field_name("b", field), range);
+                    self.compare(&typ, x, y, 0, errs)
+                });
+
+            let mut pats: Vec<_> = prefix().collect();
+            pats.push(self.cons_pat(cons, "a"));
+            pats.push(self.cons_pat(cons, "b"));
+
+            rules.push(Box::new(Rule {
+                name: name.clone(),
+                pats,
+                body: lexicographic(comparisons, range),
+                range,
+            }));
+        }
+
+        let mut entries = Vec::new();
+
+        // Values with different constructors are ordered by the
+        // positions of their constructors.
+        if sum.constructors.len() != 1 {
+            let mut pats: Vec<_> = prefix().collect();
+            pats.push(pat_var("a_", range));
+            pats.push(pat_var("b_", range));
+
+            let body = if sum.constructors.is_empty() {
+                lib("Cmp.eql", vec![], range)
+            } else {
+                let a = call(tag.clone(), vec![var("a_", range)], range);
+                let b = call(tag.clone(), vec![var("b_", range)], range);
+                lib("U60.cmp", vec![a, b], range)
+            };
+
+            rules.push(Box::new(Rule {
+                name: name.clone(),
+                pats,
+                body,
+                range,
+            }));
+        }
+
+        if sum.constructors.len() > 1 {
+            entries.push(self.derive_tag(&tag));
+        }
+
+        entries.push(Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ: lib("Cmp", vec![], range),
+            rules,
+            range,
+            attrs: Vec::new(),
+            generated_by: Some(sum.name.to_string()),
+        });
+
+        entries
+    }
+}
+
+/// Derives `T.compare` that orders two values of a sum type and
+/// returns a `Cmp`. The constructors are ordered by their position in
+/// the declaration, with the help of `T.compare.tag`, and the fields
+/// of values with the same constructor are compared in order. It
+/// receives a comparator for each type parameter and uses the
+/// `compare` function of the types of the other fields.
+pub fn derive_ord(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let entries = Derivation { range, sum }.derive_compare(&mut errs);
+
+    if errs.is_empty() {
+        (entries, errs)
+    } else {
+        (Vec::new(), errs)
+    }
+}
+
+/// Derives `T.compare` for a record as a sum type with a single
+/// constructor.
+pub fn derive_ord_rec(range: Range, rec: &RecordDecl) -> (Vec<concrete::Entry>, Errs) {
+    derive_ord(range, &record_as_sum(rec))
+}
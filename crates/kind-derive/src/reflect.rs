@@ -0,0 +1,132 @@
+//! Module to describe the declaration of a type as a value of Kind,
+//! so it can be given to the derivations that are written in Kind.
+//!
+//! The description uses these types, that have to be defined by the
+//! library of the derivation:
+//!
+//! ```kind2
+//! record Kind.SumType {
+//!   name         : String
+//!   parameters   : List Kind.Argument
+//!   indices      : List Kind.Argument
+//!   constructors : List Kind.Constructor
+//! }
+//!
+//! record Kind.Constructor {
+//!   name : String
+//!   args : List Kind.Argument
+//!   typ  : Maybe String
+//! }
+//!
+//! record Kind.Argument {
+//!   name   : String
+//!   typ    : Maybe String
+//!   hidden : Bool
+//!   erased : Bool
+//! }
+//! ```
+//!
+//! The types are written in the syntax of Kind and a record is
+//! described as a sum type with a single constructor.
+
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::*;
+use kind_tree::symbol::QualifiedIdent;
+use kind_tree::telescope::Telescope;
+
+use crate::helpers::*;
+
+fn list(args: impl IntoIterator<Item = Box<Expr>>, range: Range) -> Box<Expr> {
+    Box::new(Expr {
+        data: ExprKind::List {
+            args: args.into_iter().map(|arg| *arg).collect(),
+        },
+        range,
+    })
+}
+
+fn bool(value: bool, range: Range) -> Box<Expr> {
+    lib(if value { "Bool.true" } else { "Bool.false" }, vec![], range)
+}
+
+fn maybe_type(typ: &Option<Box<Expr>>, range: Range) -> Box<Expr> {
+    match typ {
+        Some(typ) => lib("Maybe.some", vec![string(typ.to_string(), range)], range),
+        None => lib("Maybe.none", vec![], range),
+    }
+}
+
+fn argument(arg: &Argument, range: Range) -> Box<Expr> {
+    lib(
+        "Kind.Argument.new",
+        vec![
+            string(arg.name.to_string(), range),
+            maybe_type(&arg.typ, range),
+            bool(arg.hidden, range),
+            bool(arg.erased, range),
+        ],
+        range,
+    )
+}
+
+fn arguments<'a>(args: impl IntoIterator<Item = &'a Argument>, range: Range) -> Box<Expr> {
+    list(args.into_iter().map(|arg| argument(arg, range)), range)
+}
+
+fn constructor(cons: &Constructor, range: Range) -> Box<Expr> {
+    lib(
+        "Kind.Constructor.new",
+        vec![
+            string(cons.name.to_string(), range),
+            arguments(cons.args.iter(), range),
+            maybe_type(&cons.typ, range),
+        ],
+        range,
+    )
+}
+
+/// Describes a sum type as a value of `Kind.SumType`.
+pub fn reflect_sum_type(range: Range, sum: &SumTypeDecl) -> Box<Expr> {
+    lib(
+        "Kind.SumType.new",
+        vec![
+            string(sum.name.to_string(), range),
+            arguments(sum.parameters.iter(), range),
+            arguments(sum.indices.iter(), range),
+            list(sum.constructors.iter().map(|cons| constructor(cons, range)), range),
+        ],
+        range,
+    )
+}
+
+/// Describes a record as a value of `Kind.SumType` with a single
+/// constructor.
+pub fn reflect_record_type(range: Range, rec: &RecordDecl) -> Box<Expr> {
+    reflect_sum_type(range, &record_as_sum(rec))
+}
+
+/// Creates the definition `name : String` that applies the
+/// `derivation` to the description of a type.
+pub fn derivation_entry(name: &str, derivation: &str, decl: Box<Expr>, range: Range) -> Entry {
+    let name = QualifiedIdent::new_static(name, None, range);
+
+    let rule = Box::new(Rule {
+        name: name.clone(),
+        pats: Vec::new(),
+        body: lib(derivation, vec![decl], range),
+        range,
+    });
+
+    Entry {
+        name,
+        docs: Vec::new(),
+        args: Telescope::default(),
+        typ: lib("String", vec![], range),
+        rules: vec![rule],
+        range,
+        attrs: Vec::new(),
+        generated_by: Some(derivation.to_string()),
+    }
+}
@@ -0,0 +1,760 @@
+//! Module to derive the functions that write the values of sum types
+//! and records to strings and read them back.
+//!
+//! The format is a subset of JSON. The value `(T.C f1 f2)` is written
+//! as `["C",e1,e2]`, where `C` is the last segment of the name of the
+//! constructor and `e1` and `e2` are the fields written in the same
+//! format, and a `U60` is written in decimal. There are no spaces and
+//! the values of other types are written by their own `encode`
+//! functions, so a `List U60` is `["cons",1,["cons",2,["nil"]]]`. The
+//! `wire` module of the driver reads and writes the same format.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+use kind_tree::telescope::Telescope;
+use kind_tree::Operator;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+/// A pattern of a string that starts with `prefix` and binds the rest
+/// of it to `rest`.
+fn prefix_pat(prefix: &str, rest: Box<Pat>, range: Range) -> Box<Pat> {
+    prefix.chars().rev().fold(rest, |rest, chr| {
+        let chr = Box::new(Pat {
+            data: PatKind::Char(chr),
+            range,
+        });
+        Box::new(Pat {
+            data: PatKind::App(
+                QualifiedIdent::new_static("String.cons", None, range),
+                vec![chr, rest],
+            ),
+            range,
+        })
+    })
+}
+
+fn app_pat(name: &str, args: impl IntoIterator<Item = Box<Pat>>, range: Range) -> Box<Pat> {
+    Box::new(Pat {
+        data: PatKind::App(
+            QualifiedIdent::new_static(name, None, range),
+            args.into_iter().collect(),
+        ),
+        range,
+    })
+}
+
+/// The type `Maybe (Pair typ String)` of the results of the parsers.
+fn parsed(typ: Box<Expr>, range: Range) -> Box<Expr> {
+    let pair = lib("Pair", vec![typ, lib("String", vec![], range)], range);
+    lib("Maybe", vec![pair], range)
+}
+
+/// The result of a parser that read `value` and left `rest`.
+fn some(value: Box<Expr>, rest: Box<Expr>, range: Range) -> Box<Expr> {
+    lib(
+        "Maybe.some",
+        vec![lib("Pair.new", vec![value, rest], range)],
+        range,
+    )
+}
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    fn cons_name(&self, cons: &Constructor) -> QualifiedIdent {
+        self.sum.name.add_segment(cons.name.to_str())
+    }
+
+    fn self_type(&self) -> Box<Expr> {
+        sum_type(self.sum, &self.sum.indices, self.range)
+    }
+
+    fn field_type(&self, field: &Argument) -> Box<Expr> {
+        field.typ.clone().unwrap_or_else(|| Expr::typ(self.range))
+    }
+
+    /// The arguments with the parameters and with a function for each
+    /// type parameter that has the `suffix`.
+    fn prefix(&self, suffix: &str, typ: impl Fn(Box<Expr>) -> Box<Expr>) -> Telescope<Argument> {
+        let mut args = self.sum.parameters.map(|arg| arg.to_implicit());
+        for param in type_params(self.sum) {
+            let param_typ = typ(var(param.name.to_str(), self.range));
+            args.push(explicit(
+                &param_function(&param.name, suffix),
+                param_typ,
+                self.range,
+            ));
+        }
+        args
+    }
+
+    fn prefix_pats(&self, suffix: &str) -> impl Iterator<Item = Box<Pat>> {
+        prefix_pats(self.sum, 0, suffix, self.range)
+    }
+
+    fn prefix_vars(&self, suffix: &str) -> impl Iterator<Item = Box<Expr>> {
+        let vars: Vec<_> = type_params(self.sum)
+            .into_iter()
+            .map(|param| var(&param_function(&param.name, suffix), self.range))
+            .collect();
+        vars.into_iter()
+    }
+
+    fn rule(
+        &self,
+        name: &QualifiedIdent,
+        pats: impl IntoIterator<Item = Box<Pat>>,
+        body: Box<Expr>,
+    ) -> Box<Rule> {
+        Box::new(Rule {
+            name: name.clone(),
+            pats: pats.into_iter().collect(),
+            body,
+            range: self.range,
+        })
+    }
+
+    fn entry(
+        &self,
+        name: QualifiedIdent,
+        args: Telescope<Argument>,
+        typ: Box<Expr>,
+        rules: impl IntoIterator<Item = Box<Rule>>,
+    ) -> concrete::Entry {
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ,
+            rules: rules.into_iter().collect(),
+            range: self.range,
+            attrs: Vec::new(),
+            generated_by: Some(self.sum.name.to_string()),
+        }
+    }
+
+    /// Encodes the value `x` of the type `typ`.
+    fn encode(&self, typ: &Expr, x: Box<Expr>, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        let range = self.range;
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                app(var(&param_function(name, "encode"), range), vec![x], range)
+            }
+            ExprKind::Lit {
+                lit: Literal::NumTypeU60,
+            } => lib("U60.show", vec![x], range),
+            ExprKind::Constr { name, args } => call_derived(
+                self.sum,
+                (name, args),
+                "encode",
+                vec![x],
+                errs,
+                |arg, errs| match &arg.data {
+                    ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                        var(&param_function(name, "encode"), range)
+                    }
+                    _ => {
+                        let y = format!("x_{}", depth);
+                        lambda(
+                            &y,
+                            None,
+                            self.encode(arg, var(&y, range), depth + 1, errs),
+                            range,
+                        )
+                    }
+                },
+                range,
+            ),
+            _ => {
+                errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                    "serialize",
+                    typ.range,
+                )));
+                Expr::hole(range)
+            }
+        }
+    }
+
+    /// Parses a value of the type `typ` from the string `input`.
+    fn parse(&self, typ: &Expr, input: Box<Expr>, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        let range = self.range;
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => app(
+                var(&param_function(name, "parse"), range),
+                vec![input],
+                range,
+            ),
+            ExprKind::Lit {
+                lit: Literal::NumTypeU60,
+            } => lib("U60.parse", vec![input], range),
+            ExprKind::Constr { name, args } => call_derived(
+                self.sum,
+                (name, args),
+                "parse",
+                vec![input],
+                errs,
+                |arg, errs| match &arg.data {
+                    ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                        var(&param_function(name, "parse"), range)
+                    }
+                    _ => {
+                        let y = format!("x_{}", depth);
+                        lambda(
+                            &y,
+                            None,
+                            self.parse(arg, var(&y, range), depth + 1, errs),
+                            range,
+                        )
+                    }
+                },
+                range,
+            ),
+            _ => {
+                errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                    "serialize",
+                    typ.range,
+                )));
+                Expr::hole(range)
+            }
+        }
+    }
+
+    /// Derives `T.encode` that writes a value as `["C",e1,e2]`.
+    fn derive_encode(&self, errs: &mut Errs) -> concrete::Entry {
+        let range = self.range;
+        let name = self.sum.name.add_segment("encode");
+
+        let mut args = self.prefix("encode", |typ| {
+            arrow(typ, lib("String", vec![], range), range)
+        });
+        args.push(explicit("value_", self.self_type(), range));
+
+        let mut rules = Vec::new();
+
+        for cons in &self.sum.constructors {
+            let mut parts = Vec::new();
+            let mut text = format!("[\"{}\"", cons.name);
+
+            for field in cons.args.iter() {
+                text.push(',');
+                parts.push(string(std::mem::take(&mut text), range));
+                let value = var(&field_name("value", field), range);
+                parts.push(self.encode(&self.field_type(field), value, 0, errs));
+            }
+
+            text.push(']');
+            parts.push(string(text, range));
+
+            let fields = cons
+                .args
+                .iter()
+                .map(|field| pat_var(&field_name("value", field), range))
+                .collect();
+
+            let mut pats: Vec<_> = self.prefix_pats("encode").collect();
+            pats.push(Box::new(Pat {
+                data: PatKind::App(self.cons_name(cons), fields),
+                range,
+            }));
+
+            let concat_name = QualifiedIdent::new_static("String.concat", None, range);
+            rules.push(self.rule(&name, pats, concat(&concat_name, parts.into_iter(), range)));
+        }
+
+        self.entry(name, args, lib("String", vec![], range), rules)
+    }
+
+    /// Derives `T.parse_prefix` that removes a prefix from a string. It
+    /// is used instead of patterns of the names of the constructors, so
+    /// the names can start with the same characters.
+    fn derive_prefix(&self, name: &QualifiedIdent) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let string_type = || lib("String", vec![], range);
+        let maybe = || lib("Maybe", vec![string_type()], range);
+        let chr = name.add_segment("chr");
+
+        let cons = |x: &str, xs: &str| {
+            app_pat(
+                "String.cons",
+                vec![pat_var(x, range), pat_var(xs, range)],
+                range,
+            )
+        };
+
+        let args = Telescope::new(vec![
+            explicit("prefix_", string_type(), range),
+            explicit("input_", string_type(), range),
+        ]);
+
+        let rules = vec![
+            self.rule(
+                name,
+                vec![
+                    app_pat("String.nil", vec![], range),
+                    pat_var("input_", range),
+                ],
+                lib("Maybe.some", vec![var("input_", range)], range),
+            ),
+            self.rule(
+                name,
+                vec![cons("x_", "xs_"), cons("y_", "ys_")],
+                call(
+                    chr.clone(),
+                    vec![
+                        Box::new(Expr {
+                            data: ExprKind::Binary {
+                                op: Operator::Eql,
+                                fst: var("x_", range),
+                                snd: var("y_", range),
+                            },
+                            range,
+                        }),
+                        var("xs_", range),
+                        var("ys_", range),
+                    ],
+                    range,
+                ),
+            ),
+            self.rule(
+                name,
+                vec![pat_hole(range), pat_hole(range)],
+                lib("Maybe.none", vec![], range),
+            ),
+        ];
+
+        let prefix = self.entry(name.clone(), args, maybe(), rules);
+
+        let args = Telescope::new(vec![
+            explicit("equal_", u60(range), range),
+            explicit("prefix_", string_type(), range),
+            explicit("input_", string_type(), range),
+        ]);
+
+        let number = Box::new(Pat {
+            data: PatKind::U60(0),
+            range,
+        });
+
+        let rules = vec![
+            self.rule(
+                &chr,
+                vec![number, pat_hole(range), pat_hole(range)],
+                lib("Maybe.none", vec![], range),
+            ),
+            self.rule(
+                &chr,
+                vec![
+                    pat_hole(range),
+                    pat_var("prefix_", range),
+                    pat_var("input_", range),
+                ],
+                call(
+                    name.clone(),
+                    vec![var("prefix_", range), var("input_", range)],
+                    range,
+                ),
+            ),
+        ];
+
+        vec![prefix, self.entry(chr, args, maybe(), rules)]
+    }
+
+    /// Derives `T.parse` that reads the name of the constructor, with
+    /// `T.parse.C.name` that tries the constructor `C` and then the next
+    /// one, and `T.parse.C` and `T.parse.C.i` that read the fields of
+    /// `C` one by one.
+    fn derive_parse(&self, errs: &mut Errs) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let sum = self.sum;
+        let name = sum.name.add_segment("parse");
+        let prefix = QualifiedIdent::new_static(&format!("{}.parse_prefix", sum.name), None, range);
+        let string_type = || lib("String", vec![], range);
+        let maybe = || lib("Maybe", vec![string_type()], range);
+
+        let parser_type = |typ| arrow(string_type(), parsed(typ, range), range);
+
+        // Tries to read the name of the constructor `cons` from `input_`.
+        let attempt = |cons: &Constructor, input: Box<Expr>| {
+            let tag = string(format!("[\"{}\"", cons.name), range);
+            let mut spine: Vec<_> = self.prefix_vars("parse").collect();
+            spine.push(call(prefix.clone(), vec![tag, input.clone()], range));
+            spine.push(input);
+            call(
+                name.add_segment(cons.name.to_str()).add_segment("name"),
+                spine,
+                range,
+            )
+        };
+
+        let mut entries = self.derive_prefix(&prefix);
+
+        for (i, cons) in sum.constructors.iter().enumerate() {
+            let cons_parser = name.add_segment(cons.name.to_str());
+            let step = cons_parser.add_segment("name");
+
+            let mut args = self.prefix("parse", parser_type);
+            args.push(explicit("tag_", maybe(), range));
+            args.push(explicit("input_", string_type(), range));
+
+            let mut pats: Vec<_> = self.prefix_pats("parse").collect();
+            pats.push(app_pat("Maybe.some", vec![pat_var("rest_", range)], range));
+            pats.push(pat_hole(range));
+
+            let mut spine: Vec<_> = self.prefix_vars("parse").collect();
+            spine.push(var("rest_", range));
+
+            let mut otherwise: Vec<_> = self.prefix_pats("parse").collect();
+            otherwise.push(pat_hole(range));
+            otherwise.push(pat_var("input_", range));
+
+            let next = match sum.constructors.get(i + 1) {
+                Some(next) => attempt(next, var("input_", range)),
+                None => lib("Maybe.none", vec![], range),
+            };
+
+            let rules = vec![
+                self.rule(&step, pats, call(cons_parser.clone(), spine, range)),
+                self.rule(&step, otherwise, next),
+            ];
+
+            entries.push(self.entry(step, args, parsed(self.self_type(), range), rules));
+            entries.extend(self.derive_parse_cons(&cons_parser, cons, errs));
+        }
+
+        let mut pats: Vec<_> = self.prefix_pats("parse").collect();
+        pats.push(pat_var("input_", range));
+
+        let body = match sum.constructors.first() {
+            Some(first) => attempt(first, var("input_", range)),
+            None => lib("Maybe.none", vec![], range),
+        };
+
+        let mut args = self.prefix("parse", parser_type);
+        args.push(explicit("input_", string_type(), range));
+
+        let rules = vec![self.rule(&name, pats, body)];
+
+        entries.push(self.entry(name, args, parsed(self.self_type(), range), rules));
+
+        entries
+    }
+
+    /// Derives `name` that receives the arguments `args`, bound by the
+    /// patterns `pats`, and a `Maybe` of `typ`. It passes the arguments
+    /// and the value inside of `Maybe.some` to `then` and returns
+    /// `Maybe.none` otherwise. The checker cannot find the types of the
+    /// values inside of nested patterns of `Maybe` and `Pair`, so they
+    /// are matched by different functions.
+    fn unwrap(
+        &self,
+        name: QualifiedIdent,
+        then: QualifiedIdent,
+        (args, pats): (Telescope<Argument>, impl IntoIterator<Item = Box<Pat>>),
+        spine: impl IntoIterator<Item = Box<Expr>>,
+        typ: Box<Expr>,
+        ret: Box<Expr>,
+    ) -> concrete::Entry {
+        let range = self.range;
+
+        let args = args.extend(&Telescope::new(vec![explicit(
+            "result_",
+            lib("Maybe", vec![typ], range),
+            range,
+        )]));
+
+        let mut pats: Vec<_> = pats.into_iter().collect();
+        let mut otherwise: Vec<_> = pats.iter().map(|_| pat_hole(range)).collect();
+        otherwise.push(pat_hole(range));
+
+        pats.push(app_pat("Maybe.some", vec![pat_var("value_", range)], range));
+
+        let mut spine: Vec<_> = spine.into_iter().collect();
+        spine.push(var("value_", range));
+
+        let rules = vec![
+            self.rule(&name, pats, call(then, spine, range)),
+            self.rule(&name, otherwise, lib("Maybe.none", vec![], range)),
+        ];
+
+        self.entry(name, args, ret, rules)
+    }
+
+    /// Derives the parsers of the fields of a constructor. The parser
+    /// `T.parse.C.i` receives the fields before the field `i` and the
+    /// result of the parser of its field, and `T.parse.C.i.value` reads
+    /// the `,` that follows it, or the `]` if it's the last one.
+    fn derive_parse_cons(
+        &self,
+        name: &QualifiedIdent,
+        cons: &Constructor,
+        errs: &mut Errs,
+    ) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let fields = cons.args.as_slice();
+        let string_type = || lib("String", vec![], range);
+        let parser_type = |typ| arrow(string_type(), parsed(typ, range), range);
+
+        let finish = || {
+            let values = fields
+                .iter()
+                .map(|field| var(&field_name("value", field), range));
+            some(
+                call(self.cons_name(cons), values, range),
+                var("rest_", range),
+                range,
+            )
+        };
+
+        let next = |i: usize, errs: &mut Errs| {
+            let mut spine: Vec<_> = self.prefix_vars("parse").collect();
+            spine.extend(
+                fields[..i]
+                    .iter()
+                    .map(|field| var(&field_name("value", field), range)),
+            );
+            spine.push(self.parse(&self.field_type(&fields[i]), var("rest_", range), 0, errs));
+            call(name.add_segment(&(i + 1).to_string()), spine, range)
+        };
+
+        // The arguments with the parsers and the fields before `i`.
+        let before = |i: usize| {
+            let mut args = self.prefix("parse", parser_type);
+            for field in &fields[..i] {
+                args.push(explicit(
+                    &field_name("value", field),
+                    self.field_type(field),
+                    range,
+                ));
+            }
+            let mut pats: Vec<_> = self.prefix_pats("parse").collect();
+            pats.extend(
+                fields[..i]
+                    .iter()
+                    .map(|field| pat_var(&field_name("value", field), range)),
+            );
+            (args, pats)
+        };
+
+        let mut entries = Vec::new();
+
+        // Reads the separator after the name of the constructor.
+        let mut args = self.prefix("parse", parser_type);
+        args.push(explicit("input_", string_type(), range));
+
+        let mut pats: Vec<_> = self.prefix_pats("parse").collect();
+        let body = if fields.is_empty() {
+            pats.push(prefix_pat("]", pat_var("rest_", range), range));
+            finish()
+        } else {
+            pats.push(prefix_pat(",", pat_var("rest_", range), range));
+            next(0, errs)
+        };
+
+        let mut otherwise: Vec<_> = self.prefix_pats("parse").collect();
+        otherwise.push(pat_hole(range));
+
+        let rules = vec![
+            self.rule(name, pats, body),
+            self.rule(name, otherwise, lib("Maybe.none", vec![], range)),
+        ];
+
+        entries.push(self.entry(name.clone(), args, parsed(self.self_type(), range), rules));
+
+        for (i, field) in fields.iter().enumerate() {
+            let step = name.add_segment(&(i + 1).to_string());
+            let value = step.add_segment("value");
+            let last = i + 1 == fields.len();
+            let pair = lib("Pair", vec![self.field_type(field), string_type()], range);
+
+            let mut spine: Vec<_> = self.prefix_vars("parse").collect();
+            spine.extend(
+                fields[..i]
+                    .iter()
+                    .map(|field| var(&field_name("value", field), range)),
+            );
+
+            entries.push(self.unwrap(
+                step,
+                value.clone(),
+                before(i),
+                spine,
+                pair.clone(),
+                parsed(self.self_type(), range),
+            ));
+
+            let (mut args, mut pats) = before(i);
+            args.push(explicit("pair_", pair, range));
+
+            let separator = if last { "]" } else { "," };
+            let rest = prefix_pat(separator, pat_var("rest_", range), range);
+            pats.push(app_pat(
+                "Pair.new",
+                vec![pat_var(&field_name("value", field), range), rest],
+                range,
+            ));
+
+            let body = if last { finish() } else { next(i + 1, errs) };
+
+            let mut otherwise: Vec<_> = before(i).1.iter().map(|_| pat_hole(range)).collect();
+            otherwise.push(pat_hole(range));
+
+            let rules = vec![
+                self.rule(&value, pats, body),
+                self.rule(&value, otherwise, lib("Maybe.none", vec![], range)),
+            ];
+
+            entries.push(self.entry(value, args, parsed(self.self_type(), range), rules));
+        }
+
+        entries
+    }
+
+    /// Derives `T.decode` that parses a whole string, with
+    /// `T.decode.done` and `T.decode.done.value` that check that nothing
+    /// is left after the value.
+    fn derive_decode(&self) -> Vec<concrete::Entry> {
+        let range = self.range;
+        let name = self.sum.name.add_segment("decode");
+        let done = name.add_segment("done");
+        let value = done.add_segment("value");
+        let string_type = || lib("String", vec![], range);
+        let maybe = || lib("Maybe", vec![self.self_type()], range);
+        let pair = || lib("Pair", vec![self.self_type(), string_type()], range);
+
+        let mut args = self.prefix("parse", |typ| {
+            arrow(string_type(), parsed(typ, range), range)
+        });
+        args.push(explicit("input_", string_type(), range));
+
+        let mut spine: Vec<_> = self.prefix_vars("parse").collect();
+        spine.push(var("input_", range));
+
+        let mut pats: Vec<_> = self.prefix_pats("parse").collect();
+        pats.push(pat_var("input_", range));
+
+        let parse = call(self.sum.name.add_segment("parse"), spine, range);
+        let body = call(done.clone(), vec![parse], range);
+
+        let decode = self.entry(
+            name.clone(),
+            args,
+            maybe(),
+            vec![self.rule(&name, pats, body)],
+        );
+
+        let params = || -> Vec<_> {
+            self.sum
+                .parameters
+                .iter()
+                .map(|_| pat_hole(range))
+                .collect()
+        };
+        let implicit = || self.sum.parameters.map(|arg| arg.to_implicit());
+
+        let done = self.unwrap(
+            done,
+            value.clone(),
+            (implicit(), params()),
+            Vec::new(),
+            pair(),
+            maybe(),
+        );
+
+        let mut args = implicit();
+        args.push(explicit("pair_", pair(), range));
+
+        let mut pats = params();
+        let nil = app_pat("String.nil", vec![], range);
+        pats.push(app_pat(
+            "Pair.new",
+            vec![pat_var("value_", range), nil],
+            range,
+        ));
+
+        let mut otherwise = params();
+        otherwise.push(pat_hole(range));
+
+        let rules = vec![
+            self.rule(
+                &value,
+                pats,
+                lib("Maybe.some", vec![var("value_", range)], range),
+            ),
+            self.rule(&value, otherwise, lib("Maybe.none", vec![], range)),
+        ];
+
+        vec![decode, done, self.entry(value, args, maybe(), rules)]
+    }
+
+    /// The fields that cannot be written are the erased ones, that do
+    /// not exist at runtime, and the hidden ones, that cannot be
+    /// inferred from the other fields when they are read.
+    fn check_fields(&self, errs: &mut Errs) {
+        if !self.sum.indices.is_empty() {
+            errs.push(Box::new(DeriveDiagnostic::IndexedType(
+                "serialize",
+                self.sum.name.range,
+            )));
+        }
+
+        for cons in &self.sum.constructors {
+            for field in cons.args.iter() {
+                if field.hidden || field.erased {
+                    errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                        "serialize",
+                        field.range,
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Derives `T.encode` and `T.decode` that write a value of a sum type
+/// to a string in the format that is described in this module and read
+/// it back. `T.parse` reads a value from the start of a string and
+/// returns the rest of it, so the types of the fields are read with
+/// their `parse` functions. The functions receive the encoders or the
+/// parsers of the type parameters. Besides `String` and
+/// `String.concat`, they need `U60.show`, `U60.parse`, `Maybe` and a
+/// `Pair` record.
+pub fn derive_serialize(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let derivation = Derivation { range, sum };
+
+    derivation.check_fields(&mut errs);
+
+    if !errs.is_empty() {
+        return (Vec::new(), errs);
+    }
+
+    let mut entries = vec![derivation.derive_encode(&mut errs)];
+    entries.extend(derivation.derive_parse(&mut errs));
+    entries.extend(derivation.derive_decode());
+
+    if errs.is_empty() {
+        (entries, errs)
+    } else {
+        (Vec::new(), errs)
+    }
+}
+
+/// Derives `T.encode` and `T.decode` for a record as a sum type with a
+/// single constructor.
+pub fn derive_serialize_rec(range: Range, rec: &RecordDecl) -> (Vec<concrete::Entry>, Errs) {
+    derive_serialize(range, &record_as_sum(rec))
+}
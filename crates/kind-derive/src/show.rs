@@ -0,0 +1,191 @@
+//! Module to derive a function that renders the values of sum types
+//! and records as strings in the surface syntax.
+
+use kind_report::data::Diagnostic;
+use kind_span::Range;
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatKind};
+use kind_tree::concrete::*;
+use kind_tree::concrete::{self};
+use kind_tree::symbol::QualifiedIdent;
+
+use crate::diagnostic::DeriveDiagnostic;
+use crate::helpers::*;
+
+type Errs = Vec<Box<dyn Diagnostic>>;
+
+struct Derivation<'a> {
+    range: Range,
+    sum: &'a SumTypeDecl,
+}
+
+impl<'a> Derivation<'a> {
+    /// A function that renders the values of `typ`.
+    fn function(&self, typ: &Expr, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                var(&param_function(name, "show"), self.range)
+            }
+            _ => {
+                let x = format!("x_{}", depth);
+                let body = self.show(typ, var(&x, self.range), depth + 1, errs);
+                lambda(&x, None, body, self.range)
+            }
+        }
+    }
+
+    /// Renders the value `x` of the type `typ`.
+    fn show(&self, typ: &Expr, x: Box<Expr>, depth: usize, errs: &mut Errs) -> Box<Expr> {
+        let range = self.range;
+        match &typ.data {
+            ExprKind::Var { name } if is_type_param(self.sum, name) => {
+                app(var(&param_function(name, "show"), range), vec![x], range)
+            }
+            ExprKind::Lit {
+                lit: Literal::NumTypeU60,
+            } => lib("U60.show", vec![x], range),
+            ExprKind::Lit {
+                lit: Literal::NumTypeF60,
+            } => lib("F60.show", vec![x], range),
+            ExprKind::Constr { name, args } => call_derived(
+                self.sum,
+                (name, args),
+                "show",
+                vec![x],
+                errs,
+                |arg, errs| self.function(arg, depth, errs),
+                range,
+            ),
+            _ => {
+                errs.push(Box::new(DeriveDiagnostic::UnsupportedField(
+                    "show", typ.range,
+                )));
+                Expr::hole(range)
+            }
+        }
+    }
+
+    /// Renders a constructor as `T.C` or `(T.C f1 f2 ...)`. The erased
+    /// fields are rendered as `_`.
+    fn render(&self, cons: &Constructor, errs: &mut Errs) -> Box<Expr> {
+        let range = self.range;
+        let name = self.sum.name.add_segment(cons.name.to_str());
+        let fields = explicit_fields(cons);
+
+        if fields.is_empty() {
+            return string(name.to_string(), range);
+        }
+
+        let mut parts = Vec::new();
+        let mut text = format!("({}", name);
+
+        for field in fields {
+            if field.erased {
+                text.push_str(" _");
+                continue;
+            }
+
+            text.push(' ');
+            parts.push(string(std::mem::take(&mut text), range));
+
+            let typ = field.typ.clone().unwrap_or_else(|| Expr::typ(range));
+            parts.push(self.show(&typ, var(&field_name("value", field), range), 0, errs));
+        }
+
+        text.push(')');
+        parts.push(string(text, range));
+
+        // It's generated so the desugarer can check that it's implemented.
+        let concat_name = QualifiedIdent::new_static("String.concat", None, range).to_generated();
+        concat(&concat_name, parts.into_iter(), range)
+    }
+
+    fn derive(&self, errs: &mut Errs) -> concrete::Entry {
+        let range = self.range;
+        let sum = self.sum;
+        let name = sum.name.add_segment("show");
+
+        let mut args = sum
+            .parameters
+            .extend(&sum.indices)
+            .map(|arg| arg.to_implicit());
+
+        for param in type_params(self.sum) {
+            let typ = arrow(
+                var(param.name.to_str(), range),
+                lib("String", vec![], range),
+                range,
+            );
+            args.push(explicit(&param_function(&param.name, "show"), typ, range));
+        }
+
+        args.push(explicit(
+            "value_",
+            sum_type(sum, &sum.indices, range),
+            range,
+        ));
+
+        let mut rules = Vec::new();
+
+        for cons in &sum.constructors {
+            let mut pats: Vec<_> = prefix_pats(sum, sum.indices.len(), "show", range).collect();
+
+            let fields = explicit_fields(cons)
+                .into_iter()
+                .map(|field| {
+                    if field.erased {
+                        pat_hole(range)
+                    } else {
+                        pat_var(&field_name("value", field), range)
+                    }
+                })
+                .collect();
+
+            pats.push(Box::new(Pat {
+                data: PatKind::App(sum.name.add_segment(cons.name.to_str()), fields),
+                range,
+            }));
+
+            rules.push(Box::new(Rule {
+                name: name.clone(),
+                pats,
+                body: self.render(cons, errs),
+                range,
+            }));
+        }
+
+        Entry {
+            name,
+            docs: Vec::new(),
+            args,
+            typ: lib("String", vec![], range),
+            rules,
+            range,
+            attrs: Vec::new(),
+            generated_by: Some(sum.name.to_string()),
+        }
+    }
+}
+
+/// Derives `T.show` that renders a value of a sum type in the surface
+/// syntax, like `(List.cons 1 List.nil)`. It receives a function that
+/// renders each type parameter and uses the `show` function of the
+/// types of the other fields.
+pub fn derive_show(range: Range, sum: &SumTypeDecl) -> (Vec<concrete::Entry>, Errs) {
+    let mut errs: Errs = Vec::new();
+
+    let entry = Derivation { range, sum }.derive(&mut errs);
+
+    if errs.is_empty() {
+        (vec![entry], errs)
+    } else {
+        (Vec::new(), errs)
+    }
+}
+
+/// Derives `T.show` for a record as a sum type with a single
+/// constructor.
+pub fn derive_show_rec(range: Range, rec: &RecordDecl) -> (Vec<concrete::Entry>, Errs) {
+    derive_show(range, &record_as_sum(rec))
+}
@@ -0,0 +1,243 @@
+//! Describes the targets that the compiler can generate code for.
+//! Every target goes through the same pipeline: the book is
+//! desugared, erased, the [passes][Backend::passes] of the backend
+//! run over the erased book and then the backend compiles it.
+
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
+
+use fxhash::FxHashMap;
+
+use kind_pass::inline::inline_book;
+use kind_pass::strictness::infer_strictness;
+use kind_target_kdl::{Signatures, StatsFormat};
+use kind_tree::untyped;
+
+use crate::diagnostic::{DriverDiagnostic, GenericDriverError};
+use crate::session::Session;
+
+/// A transformation over the erased book that runs before the
+/// backend compiles it.
+pub type Pass = fn(&mut untyped::Book);
+
+/// A command line flag that a backend reads. The flags of all the
+/// backends are added to the `build` command, and each backend parses
+/// the values of its own ones.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub help: &'static str,
+    /// The values that the flag accepts, if only some of them are valid.
+    pub values: Option<&'static [&'static str]>,
+    /// The value used when the flag is given without one. If it's
+    /// not given, then the flag always needs a value.
+    pub default_missing: Option<&'static str>,
+}
+
+/// Options that are given by the user to the backends.
+#[derive(Debug, Clone, Default)]
+pub struct BackendOptions {
+    /// Prints all of the functions and their evaluation.
+    pub trace: bool,
+    /// The values of the [flags][Backend::flags] of the backend.
+    pub flags: FxHashMap<String, String>,
+}
+
+impl BackendOptions {
+    pub fn with_flag(mut self, name: &str, value: impl Into<String>) -> BackendOptions {
+        self.flags.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn flag(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).map(|value| value.as_str())
+    }
+}
+
+pub trait Backend {
+    /// Name used to select the backend in the command line.
+    fn name(&self) -> &'static str;
+
+    /// Flags that the backend reads from the [options][BackendOptions].
+    fn flags(&self) -> Vec<Flag> {
+        Vec::new()
+    }
+
+    /// Passes that must run over the erased book before it's compiled
+    /// by this backend.
+    fn passes(&self) -> Vec<Pass> {
+        vec![inline_book, infer_strictness]
+    }
+
+    /// Compiles an erased book that already went through all of the
+    /// [passes][Backend::passes].
+    fn compile(
+        &self,
+        session: &mut Session,
+        book: untyped::Book,
+        options: &BackendOptions,
+    ) -> anyhow::Result<Box<dyn Display>>;
+
+    /// Compiles the book like [Backend::compile] and also returns a
+    /// report about the compiled output, if the options ask for one
+    /// and the backend can make it.
+    fn compile_with_report(
+        &self,
+        session: &mut Session,
+        book: untyped::Book,
+        options: &BackendOptions,
+    ) -> anyhow::Result<(Box<dyn Display>, Option<String>)> {
+        Ok((self.compile(session, book, options)?, None))
+    }
+
+    /// Writes the compiled output.
+    fn write(&self, output: &dyn Display, writer: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(writer, "{}", output)
+    }
+}
+
+/// Compiles to the [HVM](https://github.com/Kindelia/HVM).
+pub struct HvmBackend;
+
+impl Backend for HvmBackend {
+    fn name(&self) -> &'static str {
+        "hvm"
+    }
+
+    fn compile(
+        &self,
+        _session: &mut Session,
+        book: untyped::Book,
+        options: &BackendOptions,
+    ) -> anyhow::Result<Box<dyn Display>> {
+        Ok(Box::new(kind_target_hvm::compile_book(book, options.trace)))
+    }
+}
+
+/// Compiles to [Kindelia](https://github.com/Kindelia/Kindelia) statements.
+pub struct KdlBackend;
+
+impl Backend for KdlBackend {
+    fn name(&self) -> &'static str {
+        "kdl"
+    }
+
+    /// Kindelia cannot make an argument strict, so the strictness
+    /// of the accumulators is not inferred.
+    fn passes(&self) -> Vec<Pass> {
+        vec![inline_book]
+    }
+
+    fn compile(
+        &self,
+        session: &mut Session,
+        book: untyped::Book,
+        options: &BackendOptions,
+    ) -> anyhow::Result<Box<dyn Display>> {
+        self.compile_with_report(session, book, options).map(|(file, _)| file)
+    }
+
+    fn compile_with_report(
+        &self,
+        session: &mut Session,
+        book: untyped::Book,
+        options: &BackendOptions,
+    ) -> anyhow::Result<(Box<dyn Display>, Option<String>)> {
+        let options = KdlOptions::parse(session, options)?;
+        let sender = session.diagnostic_sender.clone();
+        let (file, stats) =
+            kind_target_kdl::compile_book_with_stats(book, sender, &options.namespace, &options.signatures)?;
+        let report = options.stats.map(|format| stats.render(format));
+        Ok((Box::new(file), report))
+    }
+
+    fn flags(&self) -> Vec<Flag> {
+        vec![
+            Flag {
+                name: "namespace",
+                aliases: &["ns"],
+                help: "A namespace that goes before each compiled name. Can be at most 10 characters long.",
+                values: None,
+                default_missing: None,
+            },
+            Flag {
+                name: "signatures",
+                aliases: &[],
+                help: "File with the signatures, already made by the Kindelia client, of the entries \
+                       marked with #kdl_sign. Each line contains the name of an entry and the hex of its \
+                       signature. The compiler does not sign statements.",
+                values: None,
+                default_missing: None,
+            },
+            Flag {
+                name: "stats",
+                aliases: &[],
+                help: "Prints the size and cost of each compiled function to the stderr, as a table or as JSON.",
+                values: Some(&["table", "json"]),
+                default_missing: Some("table"),
+            },
+        ]
+    }
+}
+
+/// The options of the [KdlBackend].
+struct KdlOptions {
+    namespace: String,
+    signatures: Signatures,
+    stats: Option<StatsFormat>,
+}
+
+impl KdlOptions {
+    /// Reads the flags of the backend and the signatures file that
+    /// they point to, if any.
+    fn parse(session: &mut Session, options: &BackendOptions) -> anyhow::Result<KdlOptions> {
+        let stats = match options.flag("stats") {
+            None => None,
+            Some("table") => Some(StatsFormat::Table),
+            Some("json") => Some(StatsFormat::Json),
+            Some(other) => anyhow::bail!("invalid stats format '{}', expected 'table' or 'json'", other),
+        };
+
+        Ok(KdlOptions {
+            namespace: options.flag("namespace").unwrap_or_default().to_string(),
+            signatures: read_signatures(session, options.flag("signatures").map(PathBuf::from))?,
+            stats,
+        })
+    }
+}
+
+/// Reads the signatures file at `path`, if any.
+fn read_signatures(session: &mut Session, path: Option<PathBuf>) -> anyhow::Result<Signatures> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(Signatures::default()),
+    };
+
+    let file = path.to_string_lossy().to_string();
+
+    let diagnostic = match std::fs::read_to_string(&path) {
+        Ok(code) => match kind_target_kdl::parse_signatures(&code) {
+            Ok(signatures) => return Ok(signatures),
+            Err(message) => DriverDiagnostic::InvalidSignatures(file, message),
+        },
+        Err(_) => DriverDiagnostic::CannotFindFile(file),
+    };
+
+    session.diagnostic_sender.send(Box::new(diagnostic)).unwrap();
+    Err(GenericDriverError.into())
+}
+
+/// All of the backends that are available to the compiler.
+pub fn backends() -> Vec<Box<dyn Backend>> {
+    vec![Box::new(HvmBackend), Box::new(KdlBackend)]
+}
+
+pub fn backend_names() -> Vec<&'static str> {
+    backends().iter().map(|backend| backend.name()).collect()
+}
+
+pub fn find_backend(name: &str) -> Option<Box<dyn Backend>> {
+    backends().into_iter().find(|backend| backend.name() == name)
+}
@@ -0,0 +1,201 @@
+//! Runs the derivations that are written in Kind. A derivation is a
+//! definition marked with `#derivation` that receives the description
+//! of a type as a `Kind.SumType` and returns the code of the derived
+//! definitions as a `String`. It's used like the derivations of the
+//! compiler, e.g. `#derive[Derive.Lens]`.
+//!
+//! The derivation is evaluated by HVM in a book with just the
+//! definitions that it uses, so it runs before the rest of the book is
+//! checked, and the code that it returns is loaded like the code of a
+//! new file.
+
+use std::path::PathBuf;
+
+use fxhash::FxHashSet;
+use kind_derive::reflect::{derivation_entry, reflect_record_type, reflect_sum_type};
+use kind_pass::desugar;
+use kind_pass::expand::user_derivations;
+use kind_pass::unbound::UnboundCollector;
+use kind_span::SyntaxCtxIndex;
+use kind_tree::concrete::visitor::Visitor;
+use kind_tree::concrete::{Book, TopLevel};
+use kind_tree::symbol::Ident;
+
+use crate::backend::{Backend, BackendOptions, HvmBackend};
+use crate::diagnostic::DriverDiagnostic;
+use crate::resolution::parse_and_store_source;
+use crate::session::Session;
+
+/// Number of rounds of derivations that are run. Each round runs the
+/// derivations of the types that were created by the one before it, so
+/// a derivation that creates a type with the same derivation stops.
+const MAX_ROUNDS: usize = 16;
+
+/// Runs all of the derivations written in Kind of the types of the
+/// book, including the ones of the types that are created by other
+/// derivations.
+pub(crate) fn run_derivations(session: &mut Session, book: &mut Book) -> bool {
+    let mut failed = false;
+    let mut done = FxHashSet::default();
+
+    for round in 0.. {
+        let pending: Vec<(String, Ident)> = book
+            .entries
+            .iter()
+            .flat_map(|(name, entry)| {
+                let derivations = match entry {
+                    TopLevel::SumType(sum) => user_derivations(&sum.attrs),
+                    TopLevel::RecordType(rec) => user_derivations(&rec.attrs),
+                    TopLevel::Entry(_) => Vec::new(),
+                };
+                derivations.into_iter().map(move |derivation| (name.clone(), derivation))
+            })
+            .filter(|(name, derivation)| !done.contains(&(name.clone(), derivation.to_string())))
+            .collect();
+
+        if pending.is_empty() {
+            break;
+        }
+
+        if round == MAX_ROUNDS {
+            for (name, derivation) in pending {
+                let err = Box::new(DriverDiagnostic::TooManyDerivationRounds(derivation, name, MAX_ROUNDS));
+                session.diagnostic_sender.send(err).unwrap();
+            }
+            return true;
+        }
+
+        for (name, derivation) in pending {
+            done.insert((name.clone(), derivation.to_string()));
+
+            match run_derivation(session, book, &name, &derivation) {
+                Some(code) => {
+                    let ctx = SyntaxCtxIndex::new(session.book_counter);
+                    let path = PathBuf::from(format!("<{} derived by {}>", name, derivation));
+                    failed |= parse_and_store_source(session, path, code, book);
+                    mark_generated(book, ctx, &name);
+                }
+                None => failed = true,
+            }
+        }
+    }
+
+    failed
+}
+
+/// Marks the definitions of the code of a derivation as generated by
+/// the type, like the ones of the derivations of the compiler.
+fn mark_generated(book: &mut Book, ctx: SyntaxCtxIndex, name: &str) {
+    for entry in book.entries.values_mut() {
+        if let TopLevel::Entry(entry) = entry {
+            if entry.range.ctx == ctx {
+                entry.generated_by = Some(name.to_string());
+            }
+        }
+    }
+}
+
+fn is_derivation(book: &Book, name: &str) -> bool {
+    match book.entries.get(name) {
+        Some(TopLevel::Entry(entry)) => entry.attrs.iter().any(|attr| attr.name.to_str() == "derivation"),
+        _ => false,
+    }
+}
+
+/// Evaluates the `derivation` of the type `name` and returns the code
+/// that it generates.
+fn run_derivation(session: &mut Session, book: &Book, name: &str, derivation: &Ident) -> Option<String> {
+    if !is_derivation(book, derivation.to_str()) {
+        let err = Box::new(DriverDiagnostic::NotADerivation(derivation.clone()));
+        session.diagnostic_sender.send(err).unwrap();
+        return None;
+    }
+
+    let decl = match book.entries.get(name)? {
+        TopLevel::SumType(sum) => reflect_sum_type(derivation.range, sum),
+        TopLevel::RecordType(rec) => reflect_record_type(derivation.range, rec),
+        TopLevel::Entry(_) => return None,
+    };
+
+    let main_name = main_name(book);
+    let main = derivation_entry(&main_name, derivation.to_str(), decl, derivation.range);
+
+    // The names that cannot be found are reported after the
+    // derivations run, with all of the other unbound names.
+    let book = dependencies(session, book, &main_name, TopLevel::Entry(main))?;
+
+    let desugared = desugar::desugar_book(session.diagnostic_sender.clone(), &book).ok()?;
+    let untyped = crate::lower_book(session, &desugared, vec![main_name.clone()], &[]).ok()?;
+    let file = HvmBackend.compile(session, untyped, &BackendOptions::default()).ok()?;
+
+    let result = kind_checker::eval_with_limits(&file.to_string(), &main_name, false, None, session.eval_limits);
+    let (result, _) = result
+        .map_err(|err| crate::report_eval_error(session, err, Some(derivation.range)))
+        .ok()?;
+
+    match kind_target_hvm::read_string(&result) {
+        Some(code) => Some(code),
+        None => {
+            let result = kind_target_hvm::show_term(&result);
+            let err = Box::new(DriverDiagnostic::DerivationDidNotReturnString(derivation.clone(), result));
+            session.diagnostic_sender.send(err).unwrap();
+            None
+        }
+    }
+}
+
+/// Name of the definition that applies the derivation to the type. The
+/// HVM reads the same names that can be written in Kind, so it's a name
+/// that no definition of the book has.
+fn main_name(book: &Book) -> String {
+    let mut name = "Main$".to_string();
+    while book.names.contains_key(&name) || book.entries.contains_key(&name) {
+        name.push('$');
+    }
+    name
+}
+
+/// Creates a book with `main`, that is named `main_name`, and all of the
+/// definitions that it uses. It returns `None` if one of them cannot be
+/// found.
+fn dependencies(session: &Session, book: &Book, main_name: &str, main: TopLevel) -> Option<Book> {
+    let mut new_book = Book {
+        names: book.names.clone(),
+        entries: Default::default(),
+        meta: book.meta.clone(),
+    };
+
+    if let TopLevel::Entry(entry) = &main {
+        new_book.names.insert(main_name.to_string(), entry.name.clone());
+        new_book.meta.insert(main_name.to_string(), entry.extract_book_info());
+    }
+
+    let mut queue = vec![(main_name.to_string(), main)];
+    let mut seen = FxHashSet::default();
+    seen.insert(main_name.to_string());
+
+    while let Some((name, mut entry)) = queue.pop() {
+        let mut state = UnboundCollector::new(session.diagnostic_sender.clone(), false);
+        state.visit_top_level(&mut entry);
+
+        for used in state.unbound_top_level.keys() {
+            // Constructors are defined by the entry of their type.
+            let used = if book.entries.contains_key(used) {
+                used.as_str()
+            } else {
+                match used.rsplit_once('.') {
+                    Some((parent, _)) if book.entries.contains_key(parent) => parent,
+                    _ => return None,
+                }
+            };
+
+            if seen.insert(used.to_string()) {
+                queue.push((used.to_string(), book.entries[used].clone()));
+            }
+        }
+
+        new_book.entries.insert(name, entry);
+    }
+
+    Some(new_book)
+}
@@ -3,7 +3,9 @@
 
 use std::{path::PathBuf, fmt::Display, error::Error};
 
-use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity, Subtitle, Word};
+use kind_report::codes;
+use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity, Subtitle, Suggestion, Word};
+use kind_span::Range;
 use kind_tree::symbol::{Ident, QualifiedIdent};
 
 #[derive(Debug)]
@@ -25,6 +27,30 @@ pub(crate) enum DriverDiagnostic {
     MultiplePaths(QualifiedIdent, Vec<PathBuf>),
     DefinedMultipleTimes(QualifiedIdent, QualifiedIdent),
     ThereIsntAMain,
+    /// The limit, the rewrites and the derivation that was evaluated,
+    /// if it was one.
+    RewriteLimitExceeded(u64, u64, Option<Range>),
+    HeapLimitExceeded(usize, u64, Option<Range>),
+    InvalidSignatures(String, String),
+    NotADerivation(Ident),
+    DerivationDidNotReturnString(Ident, String),
+    /// The derivation that was not run, the type and the number of
+    /// rounds of derivations that were run.
+    TooManyDerivationRounds(Ident, String, usize),
+}
+
+/// Marks the derivation that was evaluated, if there's one.
+fn derived_at(range: Option<Range>) -> Vec<Marker> {
+    range
+        .into_iter()
+        .map(|position| Marker {
+            position,
+            color: Color::Fst,
+            text: "Derived here!".to_string(),
+            no_code: false,
+            main: true,
+        })
+        .collect()
 }
 
 impl Diagnostic for DriverDiagnostic {
@@ -32,16 +58,22 @@ impl Diagnostic for DriverDiagnostic {
         match self {
             DriverDiagnostic::CannotFindFile(_) => None,
             DriverDiagnostic::ThereIsntAMain => None,
+            DriverDiagnostic::RewriteLimitExceeded(_, _, range) => range.map(|range| range.ctx),
+            DriverDiagnostic::HeapLimitExceeded(_, _, range) => range.map(|range| range.ctx),
+            DriverDiagnostic::InvalidSignatures(_, _) => None,
             DriverDiagnostic::UnboundVariable(v, _) => Some(v[0].range.ctx),
             DriverDiagnostic::MultiplePaths(id, _) => Some(id.range.ctx),
             DriverDiagnostic::DefinedMultipleTimes(fst, _) => Some(fst.range.ctx),
+            DriverDiagnostic::NotADerivation(ident) => Some(ident.range.ctx),
+            DriverDiagnostic::DerivationDidNotReturnString(ident, _) => Some(ident.range.ctx),
+            DriverDiagnostic::TooManyDerivationRounds(ident, _, _) => Some(ident.range.ctx),
         }
     }
 
     fn to_diagnostic_frame(&self) -> DiagnosticFrame {
         match self {
             DriverDiagnostic::UnboundVariable(idents, suggestions) => DiagnosticFrame {
-                code: 100,
+                code: codes::UNBOUND_VARIABLE,
                 severity: Severity::Error,
                 title: format!("Cannot find the definition '{}'.", idents[0].to_str()),
                 subtitles: vec![],
@@ -53,6 +85,16 @@ impl Diagnostic for DriverDiagnostic {
                 } else {
                     "Take a look at the rules for name searching at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()
                 }],
+                suggestions: match suggestions.first() {
+                    Some(name) => idents
+                        .iter()
+                        .map(|ident| Suggestion {
+                            position: ident.range,
+                            replacement: name.clone(),
+                        })
+                        .collect(),
+                    None => vec![],
+                },
                 positions: idents
                     .iter()
                     .map(|ident| Marker {
@@ -65,7 +107,7 @@ impl Diagnostic for DriverDiagnostic {
                     .collect(),
             },
             DriverDiagnostic::MultiplePaths(ident, paths) => DiagnosticFrame {
-                code: 101,
+                code: codes::MULTIPLE_PATHS,
                 severity: Severity::Error,
                 title: "Ambiguous definition location for the same name".to_string(),
                 subtitles: paths
@@ -73,6 +115,7 @@ impl Diagnostic for DriverDiagnostic {
                     .map(|path| Subtitle::Phrase(Color::Fst, vec![Word::White(path.display().to_string())]))
                     .collect(),
                 hints: vec!["Take a look at the rules for name searching at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: ident.range,
                     color: Color::Fst,
@@ -82,11 +125,12 @@ impl Diagnostic for DriverDiagnostic {
                 }],
             },
             DriverDiagnostic::DefinedMultipleTimes(fst, snd) => DiagnosticFrame {
-                code: 102,
+                code: codes::DEFINED_MULTIPLE_TIMES,
                 severity: Severity::Error,
                 title: "Defined multiple times for the same name".to_string(),
                 subtitles: vec![],
                 hints: vec!["Rename one of the definitions or remove and look at how names work in Kind at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: fst.range,
@@ -105,22 +149,111 @@ impl Diagnostic for DriverDiagnostic {
                 ],
             },
             DriverDiagnostic::CannotFindFile(file) => DiagnosticFrame {
-                code: 103,
+                code: codes::CANNOT_FIND_FILE,
                 severity: Severity::Error,
                 title: format!("Cannot find file '{}'", file),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![],
             },
 
             DriverDiagnostic::ThereIsntAMain => DiagnosticFrame {
-                code: 103,
+                code: codes::THERE_ISNT_A_MAIN,
                 severity: Severity::Error,
                 title: "Cannot find 'Main' function to run the file.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
+                positions: vec![],
+            },
+            DriverDiagnostic::RewriteLimitExceeded(limit, rewrites, range) => DiagnosticFrame {
+                code: codes::REWRITE_LIMIT_EXCEEDED,
+                severity: Severity::Error,
+                title: format!("The evaluation exceeded the limit of {} rewrites.", limit),
+                subtitles: vec![Subtitle::Phrase(
+                    Color::Fst,
+                    vec![Word::White(format!(
+                        "It was stopped after {} rewrites and its partial value was dropped.",
+                        rewrites
+                    ))],
+                )],
+                hints: vec!["Use '--max-rewrites' to change the limit.".to_string()],
+                suggestions: vec![],
+                positions: derived_at(*range),
+            },
+            DriverDiagnostic::HeapLimitExceeded(size, rewrites, range) => DiagnosticFrame {
+                code: codes::HEAP_LIMIT_EXCEEDED,
+                severity: Severity::Error,
+                title: format!("The evaluation ran out of space in a heap of {} nodes.", size),
+                subtitles: vec![Subtitle::Phrase(
+                    Color::Fst,
+                    vec![Word::White(format!(
+                        "It was stopped after {} rewrites and its partial value was dropped.",
+                        rewrites
+                    ))],
+                )],
+                hints: vec!["Use '--heap-size' to change the size of the heap.".to_string()],
+                suggestions: vec![],
+                positions: derived_at(*range),
+            },
+            DriverDiagnostic::InvalidSignatures(file, message) => DiagnosticFrame {
+                code: codes::INVALID_SIGNATURES,
+                severity: Severity::Error,
+                title: format!("Invalid signatures file '{}'.", file),
+                subtitles: vec![Subtitle::Phrase(Color::Fst, vec![Word::White(message.clone())])],
+                hints: vec!["Each line should contain the name of an entry and the hex of its signature.".to_string()],
+                suggestions: vec![],
                 positions: vec![],
             },
+            DriverDiagnostic::NotADerivation(ident) => DiagnosticFrame {
+                code: codes::NOT_A_DERIVATION,
+                severity: Severity::Error,
+                title: format!("Cannot find the derivation '{}'.", ident),
+                subtitles: vec![],
+                hints: vec!["Derivations are definitions marked with '#derivation' that receive a 'Kind.SumType' and return the derived code as a 'String'".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: ident.range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DriverDiagnostic::DerivationDidNotReturnString(ident, result) => DiagnosticFrame {
+                code: codes::DERIVATION_DID_NOT_RETURN_STRING,
+                severity: Severity::Error,
+                title: format!("The derivation '{}' did not return a string.", ident),
+                subtitles: vec![Subtitle::Phrase(
+                    Color::Fst,
+                    vec![Word::White(format!("It returned '{}'.", result))],
+                )],
+                hints: vec!["The result should be the derived code in a 'String' without free variables.".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: ident.range,
+                    color: Color::Fst,
+                    text: "Derived here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            DriverDiagnostic::TooManyDerivationRounds(ident, name, rounds) => DiagnosticFrame {
+                code: codes::TOO_MANY_DERIVATION_ROUNDS,
+                severity: Severity::Error,
+                title: format!("The derivation '{}' of '{}' was not run after {} rounds of derivations.", ident, name, rounds),
+                subtitles: vec![],
+                hints: vec!["A derivation that creates a type with the same derivation never ends.".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: ident.range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
         }
     }
 
@@ -131,7 +264,13 @@ impl Diagnostic for DriverDiagnostic {
             | UnboundVariable(_, _)
             | MultiplePaths(_, _)
             | DefinedMultipleTimes(_, _)
-            | ThereIsntAMain => Severity::Error
+            | ThereIsntAMain
+            | RewriteLimitExceeded(_, _, _)
+            | HeapLimitExceeded(_, _, _)
+            | InvalidSignatures(_, _)
+            | NotADerivation(_)
+            | DerivationDidNotReturnString(_, _)
+            | TooManyDerivationRounds(_, _, _) => Severity::Error
         }
     }
 }
@@ -0,0 +1,64 @@
+//! Applies the [suggestions][Suggestion] that are attached to the
+//! diagnostics directly on the source files.
+
+use std::path::Path;
+
+use kind_report::data::{Diagnostic, Suggestion};
+
+use crate::session::Session;
+
+/// Applies the suggestions to the code. Suggestions that overlap with
+/// another one that was already applied are ignored, so they can be
+/// applied in a next round. Returns the new code and the number of
+/// suggestions that were applied.
+pub fn apply_suggestions(code: &str, suggestions: &[Suggestion]) -> (String, usize) {
+    let mut suggestions = suggestions.to_vec();
+    suggestions.sort_by_key(|suggestion| (suggestion.position.start, suggestion.position.end));
+    suggestions.dedup_by(|x, y| x.position == y.position && x.replacement == y.replacement);
+
+    let mut res = String::with_capacity(code.len());
+    let mut last = 0;
+    let mut applied = 0;
+
+    for suggestion in suggestions {
+        let start = suggestion.position.start.index as usize;
+        let end = suggestion.position.end.index as usize;
+
+        if start < last || end > code.len() {
+            continue;
+        }
+
+        res.push_str(&code[last..start]);
+        res.push_str(&suggestion.replacement);
+        last = end;
+        applied += 1;
+    }
+
+    res.push_str(&code[last..]);
+    (res, applied)
+}
+
+/// Applies the suggestions of the diagnostics to the file at `path`.
+/// The suggestions for other files, like the dependencies that were
+/// loaded from the root, are ignored so they are never rewritten.
+/// Returns the number of suggestions that were applied.
+pub fn fix_file(session: &Session, path: &Path, diagnostics: &[Box<dyn Diagnostic>]) -> anyhow::Result<usize> {
+    let ctx = match session.loaded_paths_map.get(&path.canonicalize()?) {
+        Some(ctx) => *ctx,
+        None => return Ok(0),
+    };
+
+    let suggestions = diagnostics
+        .iter()
+        .flat_map(|diagnostic| diagnostic.to_diagnostic_frame().suggestions)
+        .filter(|suggestion| suggestion.position.ctx.0 == ctx)
+        .collect::<Vec<_>>();
+
+    let (code, applied) = apply_suggestions(&session.loaded_sources[ctx], &suggestions);
+
+    if applied > 0 {
+        std::fs::write(path, code)?;
+    }
+
+    Ok(applied)
+}
@@ -1,20 +1,26 @@
-use checker::eval;
+use backend::{Backend, BackendOptions, HvmBackend, Pass};
+use checker::{EvalError, EvalLimits};
 use diagnostic::{DriverDiagnostic, GenericDriverError};
-use kind_pass::{desugar, erasure, inline::inline_book};
-use kind_report::report::FileCache;
-use kind_span::SyntaxCtxIndex;
+use kind_pass::{desugar, erasure, unused};
+use kind_report::report::{position_to_index, FileCache};
+use kind_span::{Range, SyntaxCtxIndex};
 
-use hvm::language::{syntax as backend};
 use kind_tree::{concrete, desugared, untyped};
 use resolution::ResolutionError;
 use session::Session;
-use std::{path::PathBuf};
+use fxhash::{FxHashMap, FxHashSet};
+use std::fmt::{Display, Write};
+use std::path::PathBuf;
 
 use kind_checker as checker;
 
+pub mod backend;
+pub mod derivation;
 pub mod diagnostic;
+pub mod fix;
 pub mod resolution;
 pub mod session;
+pub mod wire;
 
 impl FileCache for Session {
     fn fetch(&self, ctx: SyntaxCtxIndex) -> Option<(PathBuf, &String)> {
@@ -23,15 +29,24 @@ impl FileCache for Session {
     }
 }
 
+/// Type checks the file at `path` and erases it for the `backend`.
 pub fn type_check_book(
+    backend: &dyn Backend,
     session: &mut Session,
     path: &PathBuf,
     entrypoints: Vec<String>,
     tids: Option<usize>,
     check_coverage: bool,
 ) -> anyhow::Result<(untyped::Book, u64)> {
-    let concrete_book = to_book(session, path)?;
-    let desugared_book = desugar::desugar_book(session.diagnostic_sender.clone(), &concrete_book)?;
+    let (concrete_book, unresolved) = to_book_partial(session, path)?;
+
+    // The definitions that failed to resolve or to desugar and the ones
+    // that depend on them are not checked, so the errors of all the other
+    // ones are reported in the same run.
+    let (mut desugared_book, broken) =
+        desugar::desugar_book_partial(session.diagnostic_sender.clone(), &concrete_book, unresolved);
+
+    checker::partial::remove_broken_entries(&mut desugared_book, &broken);
 
     let all = desugared_book.entrs.iter().map(|x| x.0).cloned().collect();
 
@@ -43,43 +58,179 @@ pub fn type_check_book(
         tids,
     );
 
-    if result.is_none() {
-        return Err(ResolutionError.into());
+    let rewrites = match result {
+        Some(rewrites) if broken.is_empty() => rewrites,
+        _ => return Err(ResolutionError.into()),
+    };
+
+    let book = lower_book(session, &desugared_book, entrypoints, &backend.passes())?;
+
+    Ok((book, rewrites))
+}
+
+/// Type checks the definitions of the file at `path` and returns the
+/// type of each of their expressions. The definitions that have errors
+/// are reported and skipped like in [type_check_book].
+pub fn infer_types(
+    session: &mut Session,
+    path: &PathBuf,
+    tids: Option<usize>,
+) -> anyhow::Result<Vec<checker::TypeInfo>> {
+    let ctx = SyntaxCtxIndex::new(session.book_counter);
+
+    let (concrete_book, unresolved) = to_book_partial(session, path)?;
+
+    let (mut desugared_book, broken) =
+        desugar::desugar_book_partial(session.diagnostic_sender.clone(), &concrete_book, unresolved);
+
+    checker::partial::remove_broken_entries(&mut desugared_book, &broken);
+
+    let in_file = desugared_book
+        .entrs
+        .values()
+        .filter(|entry| entry.range.ctx == ctx)
+        .map(|entry| entry.name.to_string())
+        .collect();
+
+    let types = checker::infer_types(&desugared_book, session.diagnostic_sender.clone(), in_file, tids);
+
+    Ok(types.into_iter().filter(|info| info.range.ctx == ctx).collect())
+}
+
+/// The innermost expression of `types` at a line and column of its
+/// file, both starting at 1.
+pub fn type_at<'a>(
+    session: &Session,
+    types: &'a [checker::TypeInfo],
+    position: (usize, usize),
+) -> Option<&'a checker::TypeInfo> {
+    types
+        .iter()
+        .filter(|info| {
+            let (_, code) = session.fetch(info.range.ctx).unwrap();
+            position_to_index(code, position).is_some_and(|index| {
+                info.range.start.index as usize <= index && index < info.range.end.index as usize
+            })
+        })
+        .min_by_key(|info| info.range.end.index - info.range.start.index)
+}
+
+pub fn to_book(session: &mut Session, path: &PathBuf) -> anyhow::Result<concrete::Book> {
+    let (concrete_book, unresolved) = to_book_partial(session, path)?;
+
+    if unresolved.is_empty() {
+        Ok(concrete_book)
+    } else {
+        Err(ResolutionError.into())
     }
+}
 
-    let mut book = erasure::erase_book(
-        &desugared_book,
+/// Parses and resolves the file at `path` like [to_book], but the
+/// definitions that use names that cannot be resolved do not stop it.
+/// Their names are returned with the book so the next stages can skip
+/// them and still report the errors of the other definitions.
+pub fn to_book_partial(
+    session: &mut Session,
+    path: &PathBuf,
+) -> anyhow::Result<(concrete::Book, FxHashSet<String>)> {
+    let ctx = SyntaxCtxIndex::new(session.book_counter);
+
+    let mut concrete_book = resolution::parse_and_store_book(session, path)?;
+
+    let unbound = resolution::check_unbound_top_level_partial(session, &mut concrete_book);
+
+    let unused_aliases = session
+        .unused_aliases
+        .iter()
+        .filter(|(_, range)| range.ctx == ctx)
+        .cloned()
+        .collect();
+
+    let usage = unused::book_usage(&concrete_book, &unbound, unused_aliases);
+
+    kind_lint::lint_book(
         session.diagnostic_sender.clone(),
-        entrypoints,
+        &mut concrete_book,
+        ctx,
+        &session.lint_levels,
+        &usage,
+        session.emit_warnings,
     )?;
-    inline_book(&mut book);
 
-    Ok((book, result.unwrap()))
+    Ok((concrete_book, unbound.with_unbound))
 }
 
-pub fn to_book(session: &mut Session, path: &PathBuf) -> anyhow::Result<concrete::Book> {
-    let mut concrete_book = resolution::parse_and_store_book(session, path)?;
+/// Shows the definitions of the file at `path` after the expansion of
+/// the derivations. The definitions that a type derives come after it
+/// with a comment that tells where they come from.
+pub fn show_expanded_book(session: &mut Session, path: &PathBuf) -> anyhow::Result<String> {
+    let ctx = SyntaxCtxIndex::new(session.book_counter);
 
-    resolution::check_unbound_top_level(session, &mut concrete_book)?;
+    let book = to_book(session, path)?;
 
-    Ok(concrete_book)
+    let mut derived: FxHashMap<&str, Vec<&concrete::Entry>> = FxHashMap::default();
+
+    for top_level in book.entries.values() {
+        if let concrete::TopLevel::Entry(entry) = top_level {
+            if let Some(typ) = &entry.generated_by {
+                derived.entry(typ.as_str()).or_default().push(entry);
+            }
+        }
+    }
+
+    let mut result = String::new();
+
+    for (name, ident) in &book.names {
+        let top_level = match book.entries.get(name) {
+            Some(concrete::TopLevel::Entry(entry)) if entry.generated_by.is_some() => continue,
+            Some(top_level) if ident.range.ctx == ctx => top_level,
+            _ => continue,
+        };
+
+        writeln!(result, "{}\n", top_level.to_string().trim_end())?;
+
+        let mut entries = derived.remove(name.as_str()).unwrap_or_default();
+        entries.sort_by_key(|entry| entry.name.to_string());
+
+        for entry in entries {
+            writeln!(result, "// Derived from '{}'", name)?;
+            writeln!(result, "{}\n", entry.to_string().trim_end())?;
+        }
+    }
+
+    Ok(result)
 }
 
+/// Erases a file and runs the [passes][Backend::passes] of the
+/// `backend` over it.
 pub fn erase_book(
+    backend: &dyn Backend,
     session: &mut Session,
     path: &PathBuf,
     entrypoints: Vec<String>,
 ) -> anyhow::Result<untyped::Book> {
     let concrete_book = to_book(session, path)?;
     let desugared_book = desugar::desugar_book(session.diagnostic_sender.clone(), &concrete_book)?;
+    lower_book(session, &desugared_book, entrypoints, &backend.passes())
+}
 
+/// Erases a desugared book and runs all of the `passes` over it.
+fn lower_book(
+    session: &mut Session,
+    desugared_book: &desugared::Book,
+    entrypoints: Vec<String>,
+    passes: &[Pass],
+) -> anyhow::Result<untyped::Book> {
     let mut book = erasure::erase_book(
-        &desugared_book,
+        desugared_book,
         session.diagnostic_sender.clone(),
         entrypoints,
     )?;
 
-    inline_book(&mut book);
+    for pass in passes {
+        pass(&mut book);
+    }
+
     Ok(book)
 }
 
@@ -96,30 +247,44 @@ pub fn check_erasure_book(
     desugar::desugar_book(session.diagnostic_sender.clone(), &concrete_book)
 }
 
-pub fn compile_book_to_hvm(book: untyped::Book, trace: bool) -> backend::File {
-    kind_target_hvm::compile_book(book, trace)
+/// Compiles a file using one of the [backends][backend::backends].
+pub fn compile_book_to_backend(
+    backend: &dyn Backend,
+    session: &mut Session,
+    path: &PathBuf,
+    entrypoints: Vec<String>,
+    options: &BackendOptions,
+) -> anyhow::Result<Box<dyn Display>> {
+    let book = erase_book(backend, session, path, entrypoints)?;
+    backend.compile(session, book, options)
 }
 
-pub fn compile_book_to_kdl(
-    path: &PathBuf,
+/// Compiles a file like [compile_book_to_backend] and also returns
+/// the report that the options ask for, if the backend makes one.
+pub fn compile_book_to_backend_with_report(
+    backend: &dyn Backend,
     session: &mut Session,
-    namespace: &str,
+    path: &PathBuf,
     entrypoints: Vec<String>,
-) -> anyhow::Result<kind_target_kdl::File> {
-    let concrete_book = to_book(session, path)?;
-    let desugared_book = desugar::desugar_book(session.diagnostic_sender.clone(), &concrete_book)?;
-
-    let mut book = erasure::erase_book(
-        &desugared_book,
-        session.diagnostic_sender.clone(),
-        entrypoints,
-    )?;
-
-    inline_book(&mut book);
-
-    let res = kind_target_kdl::compile_book(book, session.diagnostic_sender.clone(), namespace)?;
+    options: &BackendOptions,
+) -> anyhow::Result<(Box<dyn Display>, Option<String>)> {
+    let book = erase_book(backend, session, path, entrypoints)?;
+    backend.compile_with_report(session, book, options)
+}
 
-    Ok(res)
+/// Compiles a file with the [HvmBackend] and runs its `Main`.
+pub fn run_book(
+    session: &mut Session,
+    path: &PathBuf,
+    entrypoints: Vec<String>,
+    options: &BackendOptions,
+    tids: Option<usize>,
+    limits: EvalLimits,
+) -> anyhow::Result<(String, u64)> {
+    let book = erase_book(&HvmBackend, session, path, entrypoints)?;
+    check_main_entry(session, &book)?;
+    let file = HvmBackend.compile(session, book, options)?;
+    execute_file(session, &file.to_string(), tids, limits)
 }
 
 pub fn check_main_entry(session: &mut Session, book: &untyped::Book) -> anyhow::Result<()> {
@@ -145,17 +310,40 @@ pub fn check_main_desugared_entry(
     }
 }
 
-pub fn execute_file(file: &str, tids: Option<usize>) -> anyhow::Result<(String, u64)> {
-    match eval(file, "Main", false, tids) {
+pub fn execute_file(
+    session: &mut Session,
+    file: &str,
+    tids: Option<usize>,
+    limits: EvalLimits,
+) -> anyhow::Result<(String, u64)> {
+    match checker::eval_with_limits(file, "Main", false, tids, limits) {
         Ok((res, rewrites)) => {
-            Ok((res.to_string(), rewrites))
+            Ok((kind_target_hvm::show_term(&res), rewrites))
         },
-        Err(_) => anyhow::Result::Err(GenericDriverError.into()),
+        Err(err) => Err(report_eval_error(session, err, None)),
     }
 }
 
-pub fn eval_in_checker(book: &desugared::Book) -> (String, u64) {
-    checker::eval_api(book)
+pub fn eval_in_checker(
+    session: &mut Session,
+    book: &desugared::Book,
+    limits: EvalLimits,
+) -> anyhow::Result<(String, u64)> {
+    checker::eval_api_with_limits(book, limits).map_err(|err| report_eval_error(session, err, None))
+}
+
+/// Reports the limit that stopped an evaluation, at the `range` of the
+/// derivation that was evaluated if it was one.
+pub(crate) fn report_eval_error(session: &mut Session, err: EvalError, range: Option<Range>) -> anyhow::Error {
+    let diagnostic = match err {
+        EvalError::Syntax(_) => return GenericDriverError.into(),
+        EvalError::RewriteLimit(limit, rewrites) => {
+            DriverDiagnostic::RewriteLimitExceeded(limit, rewrites, range)
+        }
+        EvalError::HeapLimit(size, rewrites) => DriverDiagnostic::HeapLimitExceeded(size, rewrites, range),
+    };
+    session.diagnostic_sender.send(Box::new(diagnostic)).unwrap();
+    GenericDriverError.into()
 }
 
 pub fn generate_checker(book: &desugared::Book, check_coverage: bool) -> String {
@@ -5,7 +5,7 @@
 
 use core::fmt;
 use fxhash::FxHashSet;
-use kind_pass::expand::expand_module;
+use kind_pass::expand::{expand_module, user_derivations};
 use kind_pass::expand::uses::expand_uses;
 use std::error::Error;
 use std::fs;
@@ -19,7 +19,7 @@ use kind_tree::concrete::visitor::Visitor;
 use kind_tree::concrete::{Book, Module, TopLevel};
 use kind_tree::symbol::{Ident, QualifiedIdent};
 
-use crate::{diagnostic::DriverDiagnostic, session::Session};
+use crate::{derivation, diagnostic::DriverDiagnostic, session::Session};
 
 /// The extension of kind2 files.
 const EXT: &str = "kind2";
@@ -220,19 +220,37 @@ fn parse_and_store_book_by_path(session: &mut Session, path: &PathBuf, book: &mu
         }
     };
 
+    parse_and_store_source(session, fs::canonicalize(path).unwrap(), input, book)
+}
+
+/// Parses the code of a file or of a derivation that is stored at
+/// `path` and loads all of the definitions that it uses.
+pub(crate) fn parse_and_store_source(
+    session: &mut Session,
+    path: PathBuf,
+    input: String,
+    book: &mut Book,
+) -> bool {
     let ctx_id = session.book_counter;
-    session.add_path(Rc::new(fs::canonicalize(path).unwrap()), input.clone());
+    session.add_path(Rc::new(path), input.clone());
 
     let tx = session.diagnostic_sender.clone();
 
     let (mut module, mut failed) = kind_parser::parse_book(tx.clone(), ctx_id, &input);
 
-    expand_uses(&mut module, tx.clone());
+    // The names with unknown aliases cannot be resolved.
+    match expand_uses(&mut module, tx.clone()) {
+        Some(unused) => session.unused_aliases.extend(unused),
+        None => return true,
+    }
+
     expand_module(tx.clone(), &mut module);
 
     let mut state = UnboundCollector::new(tx.clone(), false);
     state.visit_module(&mut module);
 
+    let derivations = module_derivations(&module);
+
     module_to_book(&mut failed, session, module, book);
 
     for idents in state.unbound_top_level.values() {
@@ -242,9 +260,30 @@ fn parse_and_store_book_by_path(session: &mut Session, path: &PathBuf, book: &mu
         }
     }
 
+    for derivation in derivations {
+        if !book.names.contains_key(&derivation.to_string()) {
+            failed |= parse_and_store_book_by_identifier(session, &derivation, book);
+        }
+    }
+
     failed
 }
 
+/// The names of the derivations written in Kind that the types of
+/// the module use.
+fn module_derivations(module: &Module) -> Vec<QualifiedIdent> {
+    module
+        .entries
+        .iter()
+        .flat_map(|entry| match entry {
+            TopLevel::SumType(sum) => user_derivations(&sum.attrs),
+            TopLevel::RecordType(rec) => user_derivations(&rec.attrs),
+            TopLevel::Entry(_) => Vec::new(),
+        })
+        .map(|ident| QualifiedIdent::new_static(ident.to_str(), None, ident.range))
+        .collect()
+}
+
 fn unbound_variable(session: &mut Session, book: &Book, idents: &[Ident]) {
     let mut similar_names = book
         .names
@@ -253,7 +292,8 @@ fn unbound_variable(session: &mut Session, book: &Book, idents: &[Ident]) {
         .filter(|x| x.0 > 0.8)
         .collect::<Vec<_>>();
 
-    similar_names.sort_by(|x, y| x.0.total_cmp(&y.0));
+    // The most similar names come first.
+    similar_names.sort_by(|x, y| y.0.total_cmp(&x.0));
 
     let err = Box::new(DriverDiagnostic::UnboundVariable(
         idents.to_vec(),
@@ -265,20 +305,38 @@ fn unbound_variable(session: &mut Session, book: &Book, idents: &[Ident]) {
 
 pub fn parse_and_store_book(session: &mut Session, path: &PathBuf) -> anyhow::Result<Book> {
     let mut book = Book::default();
-    if parse_and_store_book_by_path(session, path, &mut book) {
+    if parse_and_store_book_by_path(session, path, &mut book)
+        || derivation::run_derivations(session, &mut book)
+    {
         Err(ResolutionError.into())
     } else {
         Ok(book)
     }
 }
 
-pub fn check_unbound_top_level(session: &mut Session, book: &mut Book) -> anyhow::Result<()> {
-    let mut failed = false;
+/// Reports the names of the book that are not bound and fails if there
+/// are any. It returns the collector, that also knows which variables
+/// and definitions are used.
+pub fn check_unbound_top_level(
+    session: &mut Session,
+    book: &mut Book,
+) -> anyhow::Result<UnboundCollector> {
+    let state = check_unbound_top_level_partial(session, book);
 
-    let (unbound_names, unbound_tops) =
-        unbound::get_book_unbound(session.diagnostic_sender.clone(), book, true);
+    if state.with_unbound.is_empty() {
+        Ok(state)
+    } else {
+        Err(ResolutionError.into())
+    }
+}
+
+/// Reports the names of the book that are not bound without failing.
+/// The definitions that use them are in the `with_unbound` field of the
+/// collector.
+pub fn check_unbound_top_level_partial(session: &mut Session, book: &mut Book) -> UnboundCollector {
+    let state = unbound::get_book_unbound(session.diagnostic_sender.clone(), book, true);
 
-    for unbound in unbound_tops.values() {
+    for unbound in state.unbound_top_level.values() {
         let res: Vec<Ident> = unbound
             .iter()
             .filter(|x| !x.generated)
@@ -287,18 +345,12 @@ pub fn check_unbound_top_level(session: &mut Session, book: &mut Book) -> anyhow
 
         if !res.is_empty() {
             unbound_variable(session, book, &res);
-            failed = true;
         }
     }
 
-    for unbound in unbound_names.values() {
+    for unbound in state.unbound.values() {
         unbound_variable(session, book, unbound);
-        failed = true;
     }
 
-    if failed {
-        Err(ResolutionError.into())
-    } else {
-        Ok(())
-    }
+    state
 }
@@ -7,7 +7,10 @@ use std::rc::Rc;
 use std::sync::mpsc::Sender;
 
 use fxhash::{FxHashMap, FxHashSet};
+use kind_checker::EvalLimits;
+use kind_lint::Level;
 use kind_report::data::Diagnostic;
+use kind_span::Range;
 
 #[derive(Debug, Clone)]
 pub struct Session {
@@ -24,6 +27,20 @@ pub struct Session {
     pub root: PathBuf,
 
     pub book_counter: usize,
+
+    /// Runs the lints that only emit warnings, like the
+    /// ones that look for unused variables.
+    pub emit_warnings: bool,
+
+    /// Levels of the lints that replace their default ones.
+    pub lint_levels: FxHashMap<String, Level>,
+
+    /// Aliases of the loaded files that are never used. The
+    /// lints only report the ones of the file that is compiled.
+    pub unused_aliases: Vec<(String, Range)>,
+
+    /// Limits of the evaluations of the derivations written in Kind.
+    pub eval_limits: EvalLimits,
 }
 
 impl Session {
@@ -36,13 +53,17 @@ impl Session {
             root,
             book_counter: 0,
             diagnostic_sender: sender,
+            emit_warnings: false,
+            lint_levels: FxHashMap::default(),
+            unused_aliases: Vec::new(),
+            eval_limits: EvalLimits::default(),
         }
     }
     pub fn add_path(&mut self, path: Rc<PathBuf>, code: String) -> usize {
         let id = self.book_counter;
         self.book_counter += 1;
         self.loaded_paths_map
-            .insert((*path).clone(), self.book_counter);
+            .insert((*path).clone(), id);
         self.loaded_paths.push(path);
         self.loaded_sources.push(code);
         id
@@ -0,0 +1,905 @@
+//! Reads and writes Rust values in the format of the functions that
+//! `#derive[serialize]` generates, so programs that embed the compiler
+//! can exchange values with Kind through strings.
+//!
+//! A value built with the constructor `C` is written as `["C",f1,f2]`
+//! with its fields in order and without spaces, and a `U60` is written
+//! in decimal. The Rust types are written as the Kind types that have
+//! the same shape:
+//!
+//! - `bool` is `["true"]` or `["false"]`, like `Bool`.
+//! - The integers from 0 to 2^60 - 1 are `U60`s and a `char` is the
+//!   `U60` of its code point. Floats and the other integers cannot be
+//!   written.
+//! - Strings, bytes and sequences are lists, like `List`, so `"hi"` is
+//!   `["cons",104,["cons",105,["nil"]]]`.
+//! - `Option` is `["none"]` or `["some",x]`, like `Maybe`.
+//! - `()` and the unit structs are `["new"]`, like `Unit`, and the
+//!   tuples, the newtypes and the structs are `["new",f1,f2]`, like
+//!   `Pair` and the records.
+//! - The variants of enums are written with their names, like the
+//!   constructors of sum types.
+//!
+//! Maps cannot be written because Kind has no map type that derives
+//! `serialize`.
+
+use std::fmt::Display;
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize};
+
+/// The largest number that fits in a `U60`.
+const U60_MAX: u64 = (1 << 60) - 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A message from the implementation of [Serialize] or
+    /// [serde::Deserialize] of a type.
+    Message(String),
+    /// A kind of value that has no representation in Kind.
+    Unsupported(&'static str),
+    /// A number that does not fit in a `U60` or in the Rust type.
+    OutOfRange(String),
+    /// Something else was found where the text was expected at the
+    /// position.
+    Expected(&'static str, usize),
+    /// There is text after the value at the position.
+    TrailingCharacters(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Message(message) => write!(f, "{}", message),
+            Error::Unsupported(kind) => write!(f, "cannot represent {} in Kind", kind),
+            Error::OutOfRange(num) => write!(f, "the number {} is out of range", num),
+            Error::Expected(text, pos) => write!(f, "expected {} at position {}", text, pos),
+            Error::TrailingCharacters(pos) => write!(f, "unexpected characters at position {}", pos),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Writes a value in the format of `#derive[serialize]`.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut serializer = Serializer {
+        output: String::new(),
+    };
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+/// Reads a value in the format of `#derive[serialize]`. The whole
+/// string must be a single value.
+pub fn from_str<'de, T: de::Deserialize<'de>>(input: &'de str) -> Result<T, Error> {
+    let mut deserializer = Deserializer { input, pos: 0 };
+    let value = T::deserialize(&mut deserializer)?;
+    if deserializer.pos == input.len() {
+        Ok(value)
+    } else {
+        Err(Error::TrailingCharacters(deserializer.pos))
+    }
+}
+
+pub struct Serializer {
+    output: String,
+}
+
+impl Serializer {
+    fn number<N: TryInto<u64> + Display + Copy>(&mut self, num: N) -> Result<(), Error> {
+        match num.try_into() {
+            Ok(value) if value <= U60_MAX => {
+                self.output.push_str(&value.to_string());
+                Ok(())
+            }
+            _ => Err(Error::OutOfRange(num.to_string())),
+        }
+    }
+
+    /// Starts a value with the constructor `name`. It must be closed
+    /// with `]` after the fields.
+    fn open(&mut self, name: &str) {
+        self.output.push_str("[\"");
+        self.output.push_str(name);
+        self.output.push('"');
+    }
+
+    fn constructor(&mut self, name: &str) {
+        self.open(name);
+        self.output.push(']');
+    }
+
+    fn list(&mut self, items: impl Iterator<Item = u64>) -> Result<(), Error> {
+        let mut depth = 0;
+        for item in items {
+            self.open("cons");
+            self.output.push(',');
+            self.number(item)?;
+            self.output.push(',');
+            depth += 1;
+        }
+        self.constructor("nil");
+        self.output.push_str(&"]".repeat(depth));
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = List<'a>;
+    type SerializeTuple = Fields<'a>;
+    type SerializeTupleStruct = Fields<'a>;
+    type SerializeTupleVariant = Fields<'a>;
+    type SerializeMap = ser::Impossible<(), Error>;
+    type SerializeStruct = Fields<'a>;
+    type SerializeStructVariant = Fields<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.constructor(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.number(v)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), Error> {
+        Err(Error::Unsupported("floats"))
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), Error> {
+        Err(Error::Unsupported("floats"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.number(v as u32)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.list(v.chars().map(|chr| chr as u64))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.list(v.iter().map(|byte| *byte as u64))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.constructor("none");
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.open("some");
+        self.output.push(',');
+        value.serialize(&mut *self)?;
+        self.output.push(']');
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.constructor("new");
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<(), Error> {
+        self.constructor(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.open("new");
+        self.output.push(',');
+        value.serialize(&mut *self)?;
+        self.output.push(']');
+        Ok(())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.open(variant);
+        self.output.push(',');
+        value.serialize(&mut *self)?;
+        self.output.push(']');
+        Ok(())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<List<'a>, Error> {
+        Ok(List { ser: self, depth: 0 })
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Fields<'a>, Error> {
+        self.open("new");
+        Ok(Fields { ser: self })
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Fields<'a>, Error> {
+        self.open("new");
+        Ok(Fields { ser: self })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Fields<'a>, Error> {
+        self.open(variant);
+        Ok(Fields { ser: self })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Unsupported("maps"))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Fields<'a>, Error> {
+        self.open("new");
+        Ok(Fields { ser: self })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Fields<'a>, Error> {
+        self.open(variant);
+        Ok(Fields { ser: self })
+    }
+}
+
+/// Writes the elements of a sequence as a list, closing all of the
+/// `cons` at the end.
+pub struct List<'a> {
+    ser: &'a mut Serializer,
+    depth: usize,
+}
+
+impl<'a> ser::SerializeSeq for List<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.ser.open("cons");
+        self.ser.output.push(',');
+        value.serialize(&mut *self.ser)?;
+        self.ser.output.push(',');
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.constructor("nil");
+        self.ser.output.push_str(&"]".repeat(self.depth));
+        Ok(())
+    }
+}
+
+/// Writes the fields of a constructor that was opened already.
+pub struct Fields<'a> {
+    ser: &'a mut Serializer,
+}
+
+impl<'a> Fields<'a> {
+    fn field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.ser.output.push(',');
+        value.serialize(&mut *self.ser)
+    }
+
+    fn close(self) -> Result<(), Error> {
+        self.ser.output.push(']');
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTuple for Fields<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for Fields<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for Fields<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<'a> ser::SerializeStruct for Fields<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for Fields<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+pub struct Deserializer<'de> {
+    input: &'de str,
+    pos: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    fn rest(&self) -> &'de str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self, text: &str) -> bool {
+        self.rest().starts_with(text)
+    }
+
+    fn expect(&mut self, text: &'static str) -> Result<(), Error> {
+        if self.peek(text) {
+            self.pos += text.len();
+            Ok(())
+        } else {
+            Err(Error::Expected(text, self.pos))
+        }
+    }
+
+    /// Reads the name of a constructor after its `["`.
+    fn name(&mut self) -> Result<&'de str, Error> {
+        self.expect("[\"")?;
+        let rest = self.rest();
+        match rest.find('"') {
+            Some(end) => {
+                self.pos += end + 1;
+                Ok(&rest[..end])
+            }
+            None => Err(Error::Expected("\"", self.input.len())),
+        }
+    }
+
+    fn number(&mut self) -> Result<u64, Error> {
+        let rest = self.rest();
+        let end = rest.find(|chr: char| !chr.is_ascii_digit()).unwrap_or(rest.len());
+        if end == 0 {
+            return Err(Error::Expected("a number", self.pos));
+        }
+        self.pos += end;
+        match rest[..end].parse() {
+            Ok(num) if num <= U60_MAX => Ok(num),
+            _ => Err(Error::OutOfRange(rest[..end].to_string())),
+        }
+    }
+
+    fn integer<N: TryFrom<u64>>(&mut self) -> Result<N, Error> {
+        let num = self.number()?;
+        N::try_from(num).map_err(|_| Error::OutOfRange(num.to_string()))
+    }
+
+    fn list(&mut self) -> Result<Vec<u64>, Error> {
+        let mut items = Vec::new();
+        while !self.peek("[\"nil\"]") {
+            self.expect("[\"cons\",")?;
+            items.push(self.number()?);
+            self.expect(",")?;
+        }
+        self.expect("[\"nil\"]")?;
+        for _ in 0..items.len() {
+            self.expect("]")?;
+        }
+        Ok(items)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+        self.list()?
+            .into_iter()
+            .map(|code| u32::try_from(code).ok().and_then(char::from_u32))
+            .collect::<Option<String>>()
+            .ok_or(Error::Expected("a list of characters", start))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("values without a type"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let start = self.pos;
+        let value = match self.name()? {
+            "true" => true,
+            "false" => false,
+            _ => return Err(Error::Expected("a boolean", start)),
+        };
+        self.expect("]")?;
+        visitor.visit_bool(value)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i8(self.integer()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i16(self.integer()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.integer()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.integer()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u8(self.integer()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u16(self.integer()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(self.integer()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(self.number()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("floats"))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("floats"))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let start = self.pos;
+        let code = self.integer::<u32>()?;
+        match char::from_u32(code) {
+            Some(chr) => visitor.visit_char(chr),
+            None => Err(Error::Expected("a character", start)),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let start = self.pos;
+        let bytes = self
+            .list()?
+            .into_iter()
+            .map(|byte| u8::try_from(byte).ok())
+            .collect::<Option<Vec<u8>>>()
+            .ok_or(Error::Expected("a list of bytes", start))?;
+        visitor.visit_byte_buf(bytes)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.peek("[\"none\"]") {
+            self.expect("[\"none\"]")?;
+            visitor.visit_none()
+        } else {
+            self.expect("[\"some\",")?;
+            let value = visitor.visit_some(&mut *self)?;
+            self.expect("]")?;
+            Ok(value)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.expect("[\"new\"]")?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.expect("[\"new\",")?;
+        let value = visitor.visit_newtype_struct(&mut *self)?;
+        self.expect("]")?;
+        Ok(value)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(ListAccess { de: self, depth: 0 })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.expect("[\"new\"")?;
+        let value = visitor.visit_seq(FieldsAccess { de: self, len })?;
+        self.expect("]")?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("maps"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("identifiers outside of enums"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported("values without a type"))
+    }
+}
+
+/// Reads the elements of a list and the `]` of all of its `cons` at
+/// the end.
+struct ListAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    depth: usize,
+}
+
+impl<'de, 'a> SeqAccess<'de> for ListAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if self.de.peek("[\"nil\"]") {
+            self.de.expect("[\"nil\"]")?;
+            for _ in 0..self.depth {
+                self.de.expect("]")?;
+            }
+            return Ok(None);
+        }
+        self.de.expect("[\"cons\",")?;
+        let value = seed.deserialize(&mut *self.de)?;
+        self.de.expect(",")?;
+        self.depth += 1;
+        Ok(Some(value))
+    }
+}
+
+/// Reads `len` fields of a constructor, each one after a `,`.
+struct FieldsAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+}
+
+impl<'de, 'a> SeqAccess<'de> for FieldsAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        self.de.expect(",")?;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let name = self.name()?;
+        let variant = seed.deserialize(name.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        self.expect("]")
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        self.expect(",")?;
+        let value = seed.deserialize(&mut *self)?;
+        self.expect("]")?;
+        Ok(value)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let value = visitor.visit_seq(FieldsAccess { de: self, len })?;
+        self.expect("]")?;
+        Ok(value)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.tuple_variant(fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{from_str, to_string, Error, U60_MAX};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Unit;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Meters(u64);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: u64,
+        y: u64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Shape {
+        Empty,
+        Circle(u64),
+        Line(Point, Point),
+        Square { side: u64 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Tree {
+        Leaf,
+        Node(Color, Vec<Tree>),
+    }
+
+    /// Checks that `value` is written as `text` and read back from it.
+    fn round_trip<T>(value: T, text: &str)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+    {
+        assert_eq!(to_string(&value), Ok(text.to_string()));
+        assert_eq!(from_str::<T>(text), Ok(value));
+    }
+
+    #[test]
+    fn bool() {
+        round_trip(true, "[\"true\"]");
+        round_trip(false, "[\"false\"]");
+    }
+
+    #[test]
+    fn u60_bounds() {
+        round_trip(0u64, "0");
+        round_trip(U60_MAX, "1152921504606846975");
+        round_trip(255u8, "255");
+
+        let above = (U60_MAX + 1).to_string();
+        assert_eq!(to_string(&(U60_MAX + 1)), Err(Error::OutOfRange(above.clone())));
+        assert_eq!(from_str::<u64>(&above), Err(Error::OutOfRange(above)));
+        assert_eq!(from_str::<u8>("256"), Err(Error::OutOfRange("256".to_string())));
+        assert_eq!(to_string(&-1i64), Err(Error::OutOfRange("-1".to_string())));
+        assert_eq!(to_string(&1.5f64), Err(Error::Unsupported("floats")));
+    }
+
+    #[test]
+    fn char() {
+        round_trip('a', "97");
+        round_trip('λ', "955");
+    }
+
+    #[test]
+    fn string_and_list() {
+        round_trip("hi".to_string(), "[\"cons\",104,[\"cons\",105,[\"nil\"]]]");
+        round_trip(String::new(), "[\"nil\"]");
+        round_trip(vec![1u64, 22], "[\"cons\",1,[\"cons\",22,[\"nil\"]]]");
+        round_trip(
+            vec![Some(true)],
+            "[\"cons\",[\"some\",[\"true\"]],[\"nil\"]]",
+        );
+    }
+
+    #[test]
+    fn option() {
+        round_trip(Some(7u64), "[\"some\",7]");
+        round_trip(None::<u64>, "[\"none\"]");
+    }
+
+    #[test]
+    fn unit_tuple_and_struct() {
+        round_trip((), "[\"new\"]");
+        round_trip(Unit, "[\"new\"]");
+        round_trip((7u8, ()), "[\"new\",7,[\"new\"]]");
+        round_trip(Meters(5), "[\"new\",5]");
+        round_trip(Point { x: 1, y: 20 }, "[\"new\",1,20]");
+    }
+
+    #[test]
+    fn enum_variants() {
+        round_trip(Shape::Empty, "[\"empty\"]");
+        round_trip(Shape::Circle(3), "[\"circle\",3]");
+        round_trip(
+            Shape::Line(Point { x: 0, y: 1 }, Point { x: 2, y: 3 }),
+            "[\"line\",[\"new\",0,1],[\"new\",2,3]]",
+        );
+        round_trip(Shape::Square { side: 4 }, "[\"square\",4]");
+    }
+
+    /// The values of `suite/run/Serialize.kind2` in the tests, so they
+    /// are written in the same way in Rust and in Kind.
+    #[test]
+    fn same_as_kind() {
+        round_trip(Color::Green, "[\"green\"]");
+        round_trip(
+            vec![Point { x: 1, y: 20 }, Point { x: 300, y: 4 }],
+            "[\"cons\",[\"new\",1,20],[\"cons\",[\"new\",300,4],[\"nil\"]]]",
+        );
+        round_trip(
+            Tree::Node(Color::Red, vec![Tree::Leaf]),
+            "[\"node\",[\"red\"],[\"cons\",[\"leaf\"],[\"nil\"]]]",
+        );
+        round_trip(vec![1u64, 22], "[\"cons\",1,[\"cons\",22,[\"nil\"]]]");
+    }
+
+    #[test]
+    fn expected() {
+        assert_eq!(from_str::<Point>("[\"new\",1]"), Err(Error::Expected(",", 8)));
+        assert_eq!(from_str::<bool>("true"), Err(Error::Expected("[\"", 0)));
+        assert_eq!(from_str::<u64>("[\"nil\"]"), Err(Error::Expected("a number", 0)));
+    }
+
+    #[test]
+    fn trailing_characters() {
+        assert_eq!(from_str::<bool>("[\"true\"],"), Err(Error::TrailingCharacters(8)));
+        assert_eq!(from_str::<u64>("12 "), Err(Error::TrailingCharacters(2)));
+    }
+}
@@ -0,0 +1,216 @@
+//! Walks a definition keeping track of the variables in scope. It's
+//! shared by the lints that look at the variables and not at what
+//! they're bound to.
+
+use kind_tree::concrete::expr::{Case, CaseBinding, Destruct, Expr, ExprKind, Match, Sttm, SttmKind};
+use kind_tree::concrete::pat::PatIdent;
+use kind_tree::concrete::visitor::{walk_expr, Visitor};
+use kind_tree::concrete::{Argument, Attribute, Entry, Rule, TopLevel};
+use kind_tree::symbol::Ident;
+
+struct Binders<F: FnMut(&Ident, &[Ident])> {
+    scope: Vec<Ident>,
+    on_bind: F,
+}
+
+impl<F: FnMut(&Ident, &[Ident])> Binders<F> {
+    fn bind(&mut self, ident: &Ident) {
+        if !ident.generated {
+            (self.on_bind)(ident, &self.scope);
+        }
+        self.scope.push(ident.clone())
+    }
+}
+
+impl<F: FnMut(&Ident, &[Ident])> Visitor for Binders<F> {
+    fn visit_attr(&mut self, _: &mut Attribute) {}
+
+    fn visit_pat_ident(&mut self, ident: &mut PatIdent) {
+        self.bind(&ident.0)
+    }
+
+    fn visit_argument(&mut self, argument: &mut Argument) {
+        if let Some(typ) = &mut argument.typ {
+            self.visit_expr(typ)
+        }
+        self.bind(&argument.name)
+    }
+
+    fn visit_rule(&mut self, rule: &mut Rule) {
+        let len = self.scope.len();
+        for pat in &mut rule.pats {
+            self.visit_pat(pat);
+        }
+        self.visit_expr(&mut rule.body);
+        self.scope.truncate(len);
+    }
+
+    fn visit_entry(&mut self, entry: &mut Entry) {
+        let len = self.scope.len();
+
+        for arg in entry.args.iter_mut() {
+            self.visit_argument(arg)
+        }
+
+        self.visit_expr(&mut entry.typ);
+        self.scope.truncate(len);
+
+        for rule in &mut entry.rules {
+            self.visit_rule(rule)
+        }
+    }
+
+    fn visit_top_level(&mut self, toplevel: &mut TopLevel) {
+        match toplevel {
+            TopLevel::SumType(sum) => {
+                for arg in sum.parameters.iter_mut() {
+                    self.visit_argument(arg)
+                }
+
+                // The indices are not in scope inside of the constructors.
+                let len = self.scope.len();
+
+                for arg in sum.indices.iter_mut() {
+                    self.visit_argument(arg)
+                }
+
+                self.scope.truncate(len);
+
+                for cons in &mut sum.constructors {
+                    for arg in cons.args.iter_mut() {
+                        self.visit_argument(arg)
+                    }
+                    if let Some(typ) = &mut cons.typ {
+                        self.visit_expr(typ)
+                    }
+                    self.scope.truncate(len);
+                }
+            }
+            TopLevel::RecordType(rec) => {
+                for arg in rec.parameters.iter_mut() {
+                    self.visit_argument(arg)
+                }
+
+                for (name, _, typ) in &mut rec.fields {
+                    self.visit_expr(typ);
+                    self.bind(name)
+                }
+            }
+            TopLevel::Entry(entry) => self.visit_entry(entry),
+        }
+    }
+
+    fn visit_destruct(&mut self, destruct: &mut Destruct) {
+        match destruct {
+            Destruct::Destruct(_, _, bindings, _) => {
+                for bind in bindings {
+                    self.visit_case_binding(bind)
+                }
+            }
+            Destruct::Ident(ident) => self.bind(ident),
+        }
+    }
+
+    fn visit_case_binding(&mut self, case_binding: &mut CaseBinding) {
+        match case_binding {
+            CaseBinding::Field(ident) | CaseBinding::Renamed(_, ident) => self.bind(ident),
+        }
+    }
+
+    fn visit_sttm(&mut self, sttm: &mut Sttm) {
+        match &mut sttm.data {
+            SttmKind::Ask(name, val, next) | SttmKind::Let(name, val, next) => {
+                self.visit_expr(val);
+                let len = self.scope.len();
+                self.visit_destruct(name);
+                self.visit_sttm(next);
+                self.scope.truncate(len);
+            }
+            SttmKind::Expr(expr, next) => {
+                self.visit_expr(expr);
+                self.visit_sttm(next);
+            }
+            SttmKind::Return(expr) | SttmKind::RetExpr(expr) => self.visit_expr(expr),
+        }
+    }
+
+    fn visit_case(&mut self, case: &mut Case) {
+        let len = self.scope.len();
+        for binding in &mut case.bindings {
+            self.visit_case_binding(binding)
+        }
+        self.visit_expr(&mut case.value);
+        self.scope.truncate(len);
+    }
+
+    fn visit_match(&mut self, matcher: &mut Match) {
+        for (_, typ) in &mut matcher.with_vars {
+            if let Some(typ) = typ {
+                self.visit_expr(typ)
+            }
+        }
+
+        let len = self.scope.len();
+
+        if let Some(value) = &mut matcher.value {
+            self.visit_expr(value);
+            self.bind(&matcher.scrutinee)
+        }
+
+        for case in &mut matcher.cases {
+            self.visit_case(case)
+        }
+
+        if let Some(motive) = &mut matcher.motive {
+            self.visit_expr(motive)
+        }
+
+        self.scope.truncate(len);
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        let len = self.scope.len();
+
+        match &mut expr.data {
+            ExprKind::All { param, typ, body, .. } => {
+                self.visit_expr(typ);
+                if let Some(param) = param {
+                    self.bind(param)
+                }
+                self.visit_expr(body);
+            }
+            ExprKind::Sigma { param, fst, snd } => {
+                self.visit_expr(fst);
+                if let Some(param) = param {
+                    self.bind(param)
+                }
+                self.visit_expr(snd);
+            }
+            ExprKind::Lambda { param, typ, body, .. } => {
+                if let Some(typ) = typ {
+                    self.visit_expr(typ)
+                }
+                self.bind(param);
+                self.visit_expr(body);
+            }
+            ExprKind::Let { name, val, next } => {
+                self.visit_expr(val);
+                self.visit_destruct(name);
+                self.visit_expr(next);
+            }
+            _ => walk_expr(self, expr),
+        }
+
+        self.scope.truncate(len);
+    }
+}
+
+/// Calls `on_bind` for each variable that is bound by the definition
+/// with the variables that are already in scope.
+pub(crate) fn for_each_binder(toplevel: &mut TopLevel, on_bind: impl FnMut(&Ident, &[Ident])) {
+    let mut binders = Binders {
+        scope: Vec::new(),
+        on_bind,
+    };
+    binders.visit_top_level(toplevel)
+}
@@ -0,0 +1,64 @@
+//! Warns about `if` expressions whose condition is a constant
+//! so one of the branches is never taken.
+
+use kind_report::codes;
+use kind_tree::concrete::expr::{Expr, ExprKind};
+use kind_tree::concrete::visitor::{walk_expr, Visitor};
+use kind_tree::concrete::{Attribute, TopLevel};
+
+use crate::{Finding, Lint, Usage};
+
+pub struct ConstantCondition;
+
+struct Collector<'a> {
+    found: &'a mut Vec<Finding>,
+}
+
+/// The value of a condition that is a constant.
+fn constant_value(cond: &Expr) -> Option<bool> {
+    match &cond.data {
+        ExprKind::Constr { name, args } if args.is_empty() => match name.to_string().as_str() {
+            "Bool.true" => Some(true),
+            "Bool.false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl<'a> Visitor for Collector<'a> {
+    fn visit_attr(&mut self, _: &mut Attribute) {}
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        if let ExprKind::If { cond, .. } = &expr.data {
+            if let Some(value) = constant_value(cond) {
+                let branch = if value { "then" } else { "else" };
+                self.found.push(Finding {
+                    range: cond.range,
+                    message: format!("The condition is always {}.", value),
+                    hints: vec![format!(
+                        "Replace the 'if' expression with its '{}' branch.",
+                        branch
+                    )],
+                    related: vec![],
+                    suggestions: vec![],
+                })
+            }
+        }
+        walk_expr(self, expr)
+    }
+}
+
+impl Lint for ConstantCondition {
+    fn name(&self) -> &'static str {
+        "constant_condition"
+    }
+
+    fn code(&self) -> u32 {
+        codes::CONSTANT_CONDITION
+    }
+
+    fn check(&self, toplevel: &mut TopLevel, _: &Usage, found: &mut Vec<Finding>) {
+        Collector { found }.visit_top_level(toplevel)
+    }
+}
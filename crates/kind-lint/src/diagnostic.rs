@@ -0,0 +1,90 @@
+use std::{error::Error, fmt::Display};
+
+use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity};
+use kind_span::SyntaxCtxIndex;
+
+use crate::{Finding, Level};
+
+#[derive(Debug)]
+pub struct GenericLintError;
+
+impl Display for GenericLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "denied lint")
+    }
+}
+
+impl Error for GenericLintError {}
+
+/// A problem found by one of the lints. Its severity
+/// comes from the level of the lint.
+pub struct LintDiagnostic {
+    pub name: &'static str,
+    pub code: u32,
+    pub level: Level,
+    pub finding: Finding,
+    /// If the problem is inside of a definition, so its
+    /// level can be changed by an attribute.
+    pub in_definition: bool,
+}
+
+impl Diagnostic for LintDiagnostic {
+    fn get_syntax_ctx(&self) -> Option<SyntaxCtxIndex> {
+        Some(self.finding.range.ctx)
+    }
+
+    fn to_diagnostic_frame(&self) -> DiagnosticFrame {
+        let (color, level) = match self.level {
+            Level::Deny => (Color::Fst, "deny"),
+            _ => (Color::For, "warn"),
+        };
+
+        let mut positions = vec![Marker {
+            position: self.finding.range,
+            color,
+            text: "Here!".to_string(),
+            no_code: false,
+            main: true,
+        }];
+
+        for (range, text) in &self.finding.related {
+            positions.push(Marker {
+                position: *range,
+                color: Color::Snd,
+                text: text.clone(),
+                no_code: false,
+                main: false,
+            })
+        }
+
+        let mut hints = self.finding.hints.clone();
+        if self.in_definition {
+            hints.push(format!(
+                "The lint '{}' is set to '{}', it can be changed with '#allow[{}]'.",
+                self.name, level, self.name
+            ));
+        } else {
+            hints.push(format!(
+                "The lint '{}' is set to '{}', it can be changed with '--allow {}'.",
+                self.name, level, self.name
+            ));
+        }
+
+        DiagnosticFrame {
+            code: self.code,
+            severity: self.get_severity(),
+            title: self.finding.message.clone(),
+            subtitles: vec![],
+            hints,
+            suggestions: self.finding.suggestions.clone(),
+            positions,
+        }
+    }
+
+    fn get_severity(&self) -> Severity {
+        match self.level {
+            Level::Deny => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Lints that run over the concrete tree of the file that is being
+//! compiled. None of them change the meaning of the program and each
+//! one of them has a [Level] that can be changed with the `#allow`,
+//! `#warn` and `#deny` attributes or with the command line.
+//! * [motive][motive]         - Match motives that do not depend on the matched value
+//! * [shadowing][shadowing]   - Bindings that shadow another variable
+//! * [snake_case][snake_case] - Variables that are not in snake case
+//! * [condition][condition]   - `if` expressions with a constant condition
+//! * [unused][unused]         - Variables, private definitions and aliases that are never used
+
+use std::sync::mpsc::Sender;
+
+use diagnostic::{GenericLintError, LintDiagnostic};
+use fxhash::{FxHashMap, FxHashSet};
+use kind_report::data::{Diagnostic, Suggestion};
+use kind_span::{Range, SyntaxCtxIndex};
+use kind_tree::concrete::{AttributeStyle, Book, TopLevel};
+
+mod binders;
+pub mod condition;
+pub mod diagnostic;
+pub mod motive;
+pub mod shadowing;
+pub mod snake_case;
+pub mod unused;
+
+/// How the problems found by a lint are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl Level {
+    /// Names of the attributes that change the level of a lint.
+    pub const ATTRIBUTES: &'static [&'static str] = &["allow", "warn", "deny"];
+
+    pub fn from_attribute(name: &str) -> Option<Level> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// A problem that a lint found in a definition.
+pub struct Finding {
+    pub range: Range,
+    pub message: String,
+    pub hints: Vec<String>,
+    /// Other places that are related to the problem.
+    pub related: Vec<(Range, String)>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// What the resolution found out about the whole book. The lints
+/// only see one definition at a time, so the ones that look for
+/// unused names need it.
+#[derive(Default)]
+pub struct Usage {
+    /// Ranges of the binders of the variables that are used.
+    pub used_vars: FxHashSet<Range>,
+    /// Names that are referenced by a definition other than
+    /// the one that declares them.
+    pub references: FxHashSet<String>,
+    /// Aliases of the file that are never used.
+    pub unused_aliases: Vec<(String, Range)>,
+}
+
+pub trait Lint {
+    /// Name used by the attributes and by the command line.
+    fn name(&self) -> &'static str;
+
+    /// Code of the diagnostics emitted by the lint.
+    fn code(&self) -> u32;
+
+    fn default_level(&self) -> Level {
+        Level::Warn
+    }
+
+    /// Looks for problems in a definition and pushes them to `found`.
+    fn check(&self, _toplevel: &mut TopLevel, _usage: &Usage, _found: &mut Vec<Finding>) {}
+
+    /// Looks for problems that are outside of the definitions, like
+    /// the aliases of the file. Their level only comes from the
+    /// command line because there's no definition to put an attribute.
+    fn check_file(&self, _usage: &Usage, _found: &mut Vec<Finding>) {}
+}
+
+/// All of the lints that are available to the compiler.
+pub fn lints() -> Vec<Box<dyn Lint>> {
+    vec![
+        Box::new(motive::RedundantMotive),
+        Box::new(shadowing::ShadowedBinding),
+        Box::new(snake_case::NonSnakeCase),
+        Box::new(condition::ConstantCondition),
+        Box::new(unused::UnusedVariables),
+        Box::new(unused::UnusedDefinitions),
+        Box::new(unused::UnusedAliases),
+    ]
+}
+
+pub fn lint_names() -> Vec<&'static str> {
+    lints().iter().map(|lint| lint.name()).collect()
+}
+
+pub fn find_lint(name: &str) -> Option<Box<dyn Lint>> {
+    lints().into_iter().find(|lint| lint.name() == name)
+}
+
+/// Reads the levels set by the `#allow`, `#warn` and `#deny` attributes
+/// of a definition. Invalid arguments are reported by the desugarer.
+fn attribute_levels(toplevel: &TopLevel) -> FxHashMap<String, Level> {
+    let mut levels = FxHashMap::default();
+
+    for attr in toplevel.get_attrs() {
+        if let Some(level) = Level::from_attribute(attr.name.to_str()) {
+            for arg in &attr.args {
+                if let AttributeStyle::Ident(_, name) = arg {
+                    levels.insert(name.to_string(), level);
+                }
+            }
+        }
+    }
+
+    levels
+}
+
+/// Sends the problems found by a lint with its level and
+/// returns if any of them fails the compilation.
+fn report(
+    errors: &Sender<Box<dyn Diagnostic>>,
+    lint: &dyn Lint,
+    level: Level,
+    found: Vec<Finding>,
+    in_definition: bool,
+) -> bool {
+    let mut failed = false;
+
+    for finding in found {
+        failed |= level == Level::Deny;
+        errors
+            .send(Box::new(LintDiagnostic {
+                name: lint.name(),
+                code: lint.code(),
+                level,
+                finding,
+                in_definition,
+            }))
+            .unwrap()
+    }
+
+    failed
+}
+
+/// If the problems of a lint with this level are looked for.
+fn is_enabled(level: Level, emit_warnings: bool) -> bool {
+    match level {
+        Level::Allow => false,
+        Level::Warn => emit_warnings,
+        Level::Deny => true,
+    }
+}
+
+/// Runs all of the lints over the file `ctx` and its definitions. The
+/// `levels` given by the command line replace the default level of each
+/// lint and the attributes of each definition replace both of them. The
+/// warnings are only reported if `emit_warnings` is set and it fails if any
+/// denied lint found something.
+pub fn lint_book(
+    errors: Sender<Box<dyn Diagnostic>>,
+    book: &mut Book,
+    ctx: SyntaxCtxIndex,
+    levels: &FxHashMap<String, Level>,
+    usage: &Usage,
+    emit_warnings: bool,
+) -> anyhow::Result<()> {
+    let lints = lints();
+    let mut failed = false;
+
+    for lint in &lints {
+        let level = levels.get(lint.name()).copied().unwrap_or_else(|| lint.default_level());
+
+        if is_enabled(level, emit_warnings) {
+            let mut found = Vec::new();
+            lint.check_file(usage, &mut found);
+            failed |= report(&errors, lint.as_ref(), level, found, false);
+        }
+    }
+
+    for (name, ident) in &book.names {
+        let toplevel = match book.entries.get_mut(name) {
+            Some(toplevel) => toplevel,
+            None => continue,
+        };
+
+        let generated = matches!(toplevel, TopLevel::Entry(entry) if entry.generated_by.is_some());
+
+        if ident.range.ctx != ctx || ident.generated || generated {
+            continue;
+        }
+
+        let overrides = attribute_levels(toplevel);
+
+        for lint in &lints {
+            let level = overrides
+                .get(lint.name())
+                .or_else(|| levels.get(lint.name()))
+                .copied()
+                .unwrap_or_else(|| lint.default_level());
+
+            if is_enabled(level, emit_warnings) {
+                let mut found = Vec::new();
+                lint.check(toplevel, usage, &mut found);
+                failed |= report(&errors, lint.as_ref(), level, found, true);
+            }
+        }
+    }
+
+    if failed {
+        Err(GenericLintError.into())
+    } else {
+        Ok(())
+    }
+}
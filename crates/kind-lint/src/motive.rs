@@ -0,0 +1,75 @@
+//! Warns about `match` motives that do not depend on the matched
+//! value nor on the variables given with `with`. These motives are
+//! just the type of the expression, that the checker can infer.
+
+use kind_report::codes;
+use kind_tree::concrete::expr::Match;
+use kind_tree::concrete::visitor::{walk_match, Visitor};
+use kind_tree::concrete::{Attribute, TopLevel};
+use kind_tree::symbol::Ident;
+
+use crate::{Finding, Lint, Usage};
+
+pub struct RedundantMotive;
+
+/// Finds if any of the `names` is used by an expression. It does not
+/// care about shadowing, so it never says that a dependent motive
+/// is redundant.
+struct Uses<'a> {
+    names: &'a [String],
+    used: bool,
+}
+
+impl<'a> Visitor for Uses<'a> {
+    fn visit_ident(&mut self, ident: &mut Ident) {
+        self.used |= self.names.iter().any(|name| name == ident.to_str());
+    }
+}
+
+struct Collector<'a> {
+    found: &'a mut Vec<Finding>,
+}
+
+impl<'a> Visitor for Collector<'a> {
+    fn visit_attr(&mut self, _: &mut Attribute) {}
+
+    fn visit_match(&mut self, matcher: &mut Match) {
+        if let Some(motive) = &mut matcher.motive {
+            let names = std::iter::once(&matcher.scrutinee)
+                .chain(matcher.with_vars.iter().map(|(name, _)| name))
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+
+            let mut uses = Uses { names: &names, used: false };
+            uses.visit_expr(motive);
+
+            if !uses.used {
+                self.found.push(Finding {
+                    range: motive.range,
+                    message: format!(
+                        "The motive does not depend on the matched value '{}'.",
+                        matcher.scrutinee
+                    ),
+                    hints: vec!["Remove the motive, the type of the match can be inferred.".to_string()],
+                    related: vec![],
+                    suggestions: vec![],
+                })
+            }
+        }
+        walk_match(self, matcher)
+    }
+}
+
+impl Lint for RedundantMotive {
+    fn name(&self) -> &'static str {
+        "redundant_motive"
+    }
+
+    fn code(&self) -> u32 {
+        codes::REDUNDANT_MOTIVE
+    }
+
+    fn check(&self, toplevel: &mut TopLevel, _: &Usage, found: &mut Vec<Finding>) {
+        Collector { found }.visit_top_level(toplevel)
+    }
+}
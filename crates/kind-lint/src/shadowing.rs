@@ -0,0 +1,41 @@
+//! Warns about bindings that have the same name of a variable
+//! that is already in scope. It's allowed by default because
+//! rebinding a name with `let` is a common pattern.
+
+use kind_report::codes;
+use kind_tree::concrete::TopLevel;
+
+use crate::binders::for_each_binder;
+use crate::{Finding, Level, Lint, Usage};
+
+pub struct ShadowedBinding;
+
+impl Lint for ShadowedBinding {
+    fn name(&self) -> &'static str {
+        "shadowed_binding"
+    }
+
+    fn code(&self) -> u32 {
+        codes::SHADOWED_BINDING
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Allow
+    }
+
+    fn check(&self, toplevel: &mut TopLevel, _: &Usage, found: &mut Vec<Finding>) {
+        for_each_binder(toplevel, |ident, scope| {
+            let name = ident.to_str();
+            let previous = scope.iter().rev().find(|var| var.to_str() == name && !var.generated);
+            if let Some(previous) = previous {
+                found.push(Finding {
+                    range: ident.range,
+                    message: format!("The binding '{}' shadows another variable.", name),
+                    hints: vec![],
+                    related: vec![(previous.range, "The variable that is shadowed".to_string())],
+                    suggestions: vec![],
+                })
+            }
+        })
+    }
+}
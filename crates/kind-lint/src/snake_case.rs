@@ -0,0 +1,56 @@
+//! Warns about variables whose name is not in snake case,
+//! like `fooBar` instead of `foo_bar`.
+
+use fxhash::FxHashSet;
+use kind_report::codes;
+use kind_tree::concrete::TopLevel;
+
+use crate::binders::for_each_binder;
+use crate::{Finding, Lint, Usage};
+
+pub struct NonSnakeCase;
+
+/// Converts a name like `fooBar` to `foo_bar`.
+fn to_snake_case(name: &str) -> String {
+    let mut res = String::new();
+    for (i, chr) in name.chars().enumerate() {
+        if chr.is_ascii_uppercase() {
+            if i != 0 && !res.ends_with('_') {
+                res.push('_');
+            }
+            res.push(chr.to_ascii_lowercase());
+        } else {
+            res.push(chr);
+        }
+    }
+    res
+}
+
+impl Lint for NonSnakeCase {
+    fn name(&self) -> &'static str {
+        "non_snake_case"
+    }
+
+    fn code(&self) -> u32 {
+        codes::NOT_SNAKE_CASE
+    }
+
+    fn check(&self, toplevel: &mut TopLevel, _: &Usage, found: &mut Vec<Finding>) {
+        // The arguments and the rules usually bind the same names
+        // so each name is only reported once per definition.
+        let mut reported = FxHashSet::default();
+
+        for_each_binder(toplevel, |ident, _| {
+            let name = ident.to_str();
+            if name.chars().any(|chr| chr.is_ascii_uppercase()) && reported.insert(name.to_string()) {
+                found.push(Finding {
+                    range: ident.range,
+                    message: format!("The variable '{}' is not in snake case.", name),
+                    hints: vec![format!("Rename it to '{}'.", to_snake_case(name))],
+                    related: vec![],
+                    suggestions: vec![],
+                })
+            }
+        })
+    }
+}
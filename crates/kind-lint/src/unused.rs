@@ -0,0 +1,171 @@
+//! Warns about the rule variables, `let` bindings, private definitions
+//! and aliases that are never used. The uses are found by the resolution
+//! and come in the [Usage] of the book.
+
+use kind_report::codes;
+use kind_report::data::Suggestion;
+use kind_span::Range;
+use kind_tree::concrete::expr::{Destruct, Expr, ExprKind, Sttm, SttmKind};
+use kind_tree::concrete::pat::PatIdent;
+use kind_tree::concrete::visitor::{walk_entry, walk_expr, walk_sttm, Visitor};
+use kind_tree::concrete::{Attribute, TopLevel};
+use kind_tree::symbol::Ident;
+
+use crate::{Finding, Lint, Usage};
+
+pub struct UnusedVariables;
+
+pub struct UnusedDefinitions;
+
+pub struct UnusedAliases;
+
+/// Collects the rule variables and the `let` bindings written by
+/// hand. The boolean is true for the rule variables.
+#[derive(Default)]
+struct Binders {
+    binders: Vec<(Ident, bool)>,
+}
+
+impl Binders {
+    fn bind(&mut self, ident: &Ident, is_pattern: bool) {
+        if !ident.generated {
+            self.binders.push((ident.clone(), is_pattern))
+        }
+    }
+}
+
+impl Visitor for Binders {
+    fn visit_attr(&mut self, _: &mut Attribute) {}
+
+    fn visit_top_level(&mut self, toplevel: &mut TopLevel) {
+        if let TopLevel::Entry(entry) = toplevel {
+            walk_entry(self, entry)
+        }
+    }
+
+    fn visit_pat_ident(&mut self, ident: &mut PatIdent) {
+        self.bind(&ident.0, true)
+    }
+
+    fn visit_sttm(&mut self, sttm: &mut Sttm) {
+        if let SttmKind::Let(Destruct::Ident(ident), _, _) = &sttm.data {
+            self.bind(ident, false)
+        }
+        walk_sttm(self, sttm)
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        if let ExprKind::Let { name: Destruct::Ident(ident), .. } = &expr.data {
+            self.bind(ident, false)
+        }
+        walk_expr(self, expr)
+    }
+}
+
+/// If the name is the definition itself or one of its helpers
+/// like constructors and derived functions.
+fn is_part_of(name: &str, def: &str) -> bool {
+    name.strip_prefix(def)
+        .map(|rest| rest.is_empty() || rest.starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// A definition is private when its last name starts with an
+/// underscore, like `Nat._double` (see `guide/naming.md`).
+fn is_private(name: &str) -> bool {
+    name.rsplit('.').next().map(|x| x.starts_with('_')).unwrap_or(false)
+}
+
+impl Lint for UnusedVariables {
+    fn name(&self) -> &'static str {
+        "unused_variables"
+    }
+
+    fn code(&self) -> u32 {
+        codes::UNUSED_VARIABLE
+    }
+
+    fn check(&self, toplevel: &mut TopLevel, usage: &Usage, found: &mut Vec<Finding>) {
+        let mut binders = Binders::default();
+        binders.visit_top_level(toplevel);
+
+        let mut binders = binders.binders;
+        binders.sort_by_key(|(ident, _)| ident.range.start);
+
+        for (ident, is_pattern) in binders {
+            if usage.used_vars.contains(&ident.range) {
+                continue;
+            }
+
+            let (hint, suggestions) = if is_pattern {
+                let suggestion = Suggestion {
+                    position: Range::new(ident.range.start, ident.range.start, ident.range.ctx),
+                    replacement: "_".to_string(),
+                };
+                ("Start its name with an underscore to make it a hole.", vec![suggestion])
+            } else {
+                ("Remove the binding if it's not needed.", vec![])
+            };
+
+            found.push(Finding {
+                range: ident.range,
+                message: format!("The variable '{}' is never used.", ident),
+                hints: vec![hint.to_string()],
+                related: vec![],
+                suggestions,
+            })
+        }
+    }
+}
+
+impl Lint for UnusedDefinitions {
+    fn name(&self) -> &'static str {
+        "unused_definitions"
+    }
+
+    fn code(&self) -> u32 {
+        codes::UNUSED_DEFINITION
+    }
+
+    fn check(&self, toplevel: &mut TopLevel, usage: &Usage, found: &mut Vec<Finding>) {
+        let name = match &*toplevel {
+            TopLevel::SumType(sum) => &sum.name,
+            TopLevel::RecordType(rec) => &rec.name,
+            TopLevel::Entry(entry) => &entry.name,
+        };
+
+        let kept = toplevel.get_attrs().iter().any(|attr| attr.name.to_str() == "keep");
+
+        if is_private(name.to_str()) && !kept && !usage.references.iter().any(|x| is_part_of(x, name.to_str())) {
+            found.push(Finding {
+                range: name.range,
+                message: format!("The private definition '{}' is never used.", name),
+                hints: vec!["Definitions whose last name starts with an underscore are private.".to_string()],
+                related: vec![],
+                suggestions: vec![],
+            })
+        }
+    }
+}
+
+impl Lint for UnusedAliases {
+    fn name(&self) -> &'static str {
+        "unused_aliases"
+    }
+
+    fn code(&self) -> u32 {
+        codes::UNUSED_ALIAS
+    }
+
+    fn check_file(&self, usage: &Usage, found: &mut Vec<Finding>) {
+        for (alias, range) in &usage.unused_aliases {
+            found.push(Finding {
+                range: *range,
+                message: format!("The alias '{}' is never used.", alias),
+                hints: vec![],
+                related: vec![],
+                suggestions: vec![],
+            })
+        }
+    }
+}
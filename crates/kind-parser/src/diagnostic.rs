@@ -1,6 +1,7 @@
 //! All of the sintatic erros both from the
 //! lexer and the parser.
 
+use kind_report::codes;
 use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity};
 use kind_span::{Range, SyntaxCtxIndex};
 
@@ -70,11 +71,12 @@ impl Diagnostic for SyntaxDiagnostic {
     fn to_diagnostic_frame(&self) -> DiagnosticFrame {
         match self {
             SyntaxDiagnostic::UnfinishedString(range) => DiagnosticFrame {
-                code: 1,
+                code: codes::UNFINISHED_STRING,
                 severity: Severity::Error,
                 title: "Unfinished String".to_string(),
                 subtitles: vec![],
                 hints: vec!["You need to close the string with another quote, take a look at the beggining".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -84,11 +86,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::IgnoreRestShouldBeOnTheEnd(range) => DiagnosticFrame {
-                code: 2,
+                code: codes::IGNORE_REST_SHOULD_BE_ON_THE_END,
                 severity: Severity::Error,
                 title: "Invalid position of the '..' operator".to_string(),
                 subtitles: vec![],
                 hints: vec!["Put it on the end of the clause or remove it.".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -98,11 +101,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::UnusedDocString(range) => DiagnosticFrame {
-                code: 3,
+                code: codes::UNUSED_DOC_STRING,
                 severity: Severity::Warning,
                 title: "This entire documentation comment is in a invalid position".to_string(),
                 subtitles: vec![],
                 hints: vec!["Take a look at the rules for doc comments at https://github.com/Kindelia/Kind2/blob/master/guide/doc_strings.md".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::For,
@@ -112,11 +116,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::UnfinishedChar(range) => DiagnosticFrame {
-                code: 4,
+                code: codes::UNFINISHED_CHAR,
                 severity: Severity::Error,
                 title: "Unfinished Char".to_string(),
                 subtitles: vec![],
                 hints: vec!["You need to close the character with another quote, take a look at the beginning".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -126,7 +131,7 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::LowerCasedDefinition(name, range) => DiagnosticFrame {
-                code: 5,
+                code: codes::LOWER_CASED_DEFINITION,
                 severity: Severity::Error,
                 title: "The definition name must be capitalized.".to_string(),
                 subtitles: vec![],
@@ -135,6 +140,7 @@ impl Diagnostic for SyntaxDiagnostic {
                     let fst = c.next().unwrap().to_uppercase();
                     format!("Change it to '{}{}'", fst, c.as_str())
                 }],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -144,11 +150,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::NotAClauseOfDef(fst, snd) => DiagnosticFrame {
-                code: 6,
+                code: codes::NOT_A_CLAUSE_OF_DEF,
                 severity: Severity::Error,
                 title: "Unexpected capitalized name that does not refer to the definition".to_string(),
                 subtitles: vec![],
                 hints: vec!["If you indend to make another clause, just replace the name in red.".to_string()],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *snd,
@@ -167,11 +174,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 ],
             },
             SyntaxDiagnostic::UnfinishedComment(range) => DiagnosticFrame {
-                code: 7,
+                code: codes::UNFINISHED_COMMENT,
                 severity: Severity::Error,
                 title: "Unfinished Comment".to_string(),
                 subtitles: vec![],
                 hints: vec!["You need to close the string with '*/', take a look at the beggining".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -181,11 +189,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::InvalidEscapeSequence(kind, range) => DiagnosticFrame {
-                code: 8,
+                code: codes::INVALID_ESCAPE_SEQUENCE,
                 severity: Severity::Error,
                 title: format!("The {} character sequence is invalid!", encode_name(kind.clone())),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -195,11 +204,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::InvalidNumberRepresentation(repr, range) => DiagnosticFrame {
-                code: 9,
+                code: codes::INVALID_NUMBER_REPRESENTATION,
                 severity: Severity::Error,
                 title: format!("The {} number sequence is invalid!", encode_name(repr.clone())),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -209,11 +219,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::UnexpectedChar(chr, range) => DiagnosticFrame {
-                code: 10,
+                code: codes::UNEXPECTED_CHAR,
                 severity: Severity::Error,
                 title: format!("The char '{}' is invalid", chr),
                 subtitles: vec![],
                 hints: vec!["Try to remove it!".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -223,11 +234,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::UnexpectedToken(Token::Eof, range, _expect) => DiagnosticFrame {
-                code: 11,
+                code: codes::UNEXPECTED_EOF,
                 severity: Severity::Error,
                 title: "Unexpected end of file.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -237,11 +249,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::UnexpectedToken(Token::Comment(_, _), range, _expect) => DiagnosticFrame {
-                code: 12,
+                code: codes::UNEXPECTED_COMMENT,
                 severity: Severity::Error,
                 title: "Unexpected documentation comment.".to_string(),
                 subtitles: vec![],
                 hints: vec!["Remove this documentation comment or place it in a correct place.".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -251,11 +264,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::UnexpectedToken(token, range, _expect) => DiagnosticFrame {
-                code: 13,
+                code: codes::UNEXPECTED_TOKEN,
                 severity: Severity::Error,
                 title: format!("Unexpected token '{}'.", token),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -265,11 +279,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::Unclosed(range) => DiagnosticFrame {
-                code: 14,
+                code: codes::UNCLOSED,
                 severity: Severity::Error,
                 title: "Unclosed parenthesis.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -279,11 +294,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::CannotUseUse(range) => DiagnosticFrame {
-                code: 15,
+                code: codes::CANNOT_USE_USE,
                 severity: Severity::Error,
                 title: "Can only use the 'use' statement in the beggining of the file".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -293,11 +309,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::ImportsCannotHaveAlias(range) => DiagnosticFrame {
-                code: 16,
+                code: codes::IMPORTS_CANNOT_HAVE_ALIAS,
                 severity: Severity::Error,
                 title: "The upper cased name cannot have an alias".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -307,11 +324,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::InvalidNumberType(type_, range) => DiagnosticFrame {
-                code: 17,
+                code: codes::INVALID_NUMBER_TYPE,
                 severity: Severity::Error,
                 title: format!("The {} number type is invalid", type_),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -321,11 +339,12 @@ impl Diagnostic for SyntaxDiagnostic {
                 }],
             },
             SyntaxDiagnostic::MatchScrutineeShouldBeAName(range) => DiagnosticFrame {
-                code: 18,
+                code: codes::MATCH_SCRUTINEE_SHOULD_BE_A_NAME,
                 severity: Severity::Error,
                 title: "Match scrutinee should be a identifier!".to_string(),
                 subtitles: vec![],
                 hints: vec!["Use the '=' inside the scrutinee! More details on <website>".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
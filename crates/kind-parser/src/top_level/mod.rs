@@ -1,4 +1,5 @@
 use fxhash::FxHashMap;
+use kind_span::Range;
 use kind_tree::concrete::expr::Expr;
 use kind_tree::concrete::pat::{Pat, PatIdent, PatKind};
 
@@ -258,7 +259,7 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_use(&mut self) -> Result<(String, String), SyntaxDiagnostic> {
+    fn parse_use(&mut self) -> Result<(String, QualifiedIdent), SyntaxDiagnostic> {
         self.eat_id("use")?;
         let origin = self.parse_upper_id()?;
         self.eat_id("as")?;
@@ -269,18 +270,18 @@ impl<'a> Parser<'a> {
         } else if alias.get_aux().is_some() {
             Err(SyntaxDiagnostic::ImportsCannotHaveAlias(alias.range))
         } else {
-            Ok((origin.to_string(), alias.to_string()))
+            Ok((origin.to_string(), alias))
         }
     }
 
     pub fn parse_module(&mut self) -> Module {
         let mut entries: Vec<TopLevel> = Vec::new();
-        let mut uses: FxHashMap<String, String> = Default::default();
+        let mut uses: FxHashMap<String, (String, Range)> = Default::default();
 
         while self.check_actual_id("use") {
             match self.parse_use() {
                 Ok((origin, alias)) => {
-                    uses.insert(alias, origin);
+                    uses.insert(alias.to_string(), (origin, alias.range));
                 }
                 Err(err) => {
                     self.send_dignostic(err);
@@ -1,11 +1,43 @@
+use kind_lint::{find_lint, lint_names};
 use kind_span::Locatable;
 use kind_tree::concrete::{self, Attribute, AttributeStyle};
 use kind_tree::Attributes;
+use strsim::jaro;
 
 use crate::diagnostic::PassDiagnostic;
 
 use super::DesugarState;
 
+/// Names of all of the attributes that the compiler understands.
+const ATTRIBUTES: &[&str] = &[
+    "derive",
+    "derivation",
+    "inline",
+    "keep",
+    "kdl_run",
+    "partial",
+    "axiom",
+    "kdl_erase",
+    "kdl_name",
+    "kdl_state",
+    "kdl_contract",
+    "kdl_sign",
+    "strict",
+    "trace",
+    "allow",
+    "warn",
+    "deny",
+];
+
+fn most_similar(names: &[&str], name: &str) -> Option<String> {
+    names
+        .iter()
+        .map(|attr| (jaro(attr, name), attr))
+        .filter(|(similarity, _)| *similarity > 0.8)
+        .max_by(|x, y| x.0.total_cmp(&y.0))
+        .map(|(_, attr)| attr.to_string())
+}
+
 impl<'a> DesugarState<'a> {
     fn args_should_be_empty(&mut self, attr: &Attribute) {
         if !attr.args.is_empty() {
@@ -39,6 +71,12 @@ impl<'a> DesugarState<'a> {
                 // The derive attribute is treated by the expand
                 // pass so here we just ignore it.
                 "derive" => (),
+                // It marks the definitions that implement a derivation
+                // and it's only read by the driver.
+                "derivation" => {
+                    self.args_should_be_empty(attr);
+                    self.attr_without_value(attr);
+                }
                 "inline" => {
                     self.args_should_be_empty(attr);
                     self.attr_without_value(attr);
@@ -89,6 +127,35 @@ impl<'a> DesugarState<'a> {
                         None => self.attr_expects_a_value(attr),
                     }
                 }
+                "kdl_contract" => {
+                    self.args_should_be_empty(attr);
+                    match &attr.value {
+                        Some(AttributeStyle::Ident(_, ident)) => {
+                            attributes.kdl_contract = Some(ident.clone());
+                        }
+                        Some(_) => self.attr_invalid_argument(attr),
+                        None => self.attr_expects_a_value(attr),
+                    }
+                }
+                "kdl_sign" => {
+                    self.args_should_be_empty(attr);
+                    self.attr_without_value(attr);
+                    attributes.kdl_sign = true;
+                }
+                "strict" => {
+                    self.attr_without_value(attr);
+                    if attr.args.is_empty() {
+                        self.send_err(PassDiagnostic::AttributeExpectsAValue(attr.range))
+                    }
+                    for arg in &attr.args {
+                        match arg {
+                            AttributeStyle::Ident(_, ident) => attributes.strict.push(ident.clone()),
+                            other => {
+                                self.send_err(PassDiagnostic::InvalidAttributeArgument(other.locate()))
+                            }
+                        }
+                    }
+                }
                 "trace" => {
                     self.args_should_be_empty(attr);
                     match &attr.value {
@@ -106,7 +173,31 @@ impl<'a> DesugarState<'a> {
                         }
                     }
                 }
-                _ => self.send_err(PassDiagnostic::AttributeDoesNotExists(attr.range)),
+                // The levels of the lints are read by the lint pass so
+                // here we just check that the lints exist.
+                "allow" | "warn" | "deny" => {
+                    self.attr_without_value(attr);
+                    if attr.args.is_empty() {
+                        self.send_err(PassDiagnostic::AttributeExpectsAValue(attr.range))
+                    }
+                    for arg in &attr.args {
+                        match arg {
+                            AttributeStyle::Ident(range, ident) if find_lint(ident.to_str()).is_none() => {
+                                let similar = most_similar(&lint_names(), ident.to_str());
+                                self.send_err(PassDiagnostic::UnknownLint(*range, ident.to_string(), similar))
+                            }
+                            AttributeStyle::Ident(_, _) => (),
+                            other => {
+                                self.send_err(PassDiagnostic::InvalidAttributeArgument(other.locate()))
+                            }
+                        }
+                    }
+                }
+                name => self.send_err(PassDiagnostic::AttributeDoesNotExists(
+                    attr.range,
+                    attr.name.clone(),
+                    most_similar(ATTRIBUTES, name),
+                )),
             }
         }
 
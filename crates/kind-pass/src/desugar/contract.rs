@@ -0,0 +1,159 @@
+//! Generates the entry-point of Kindelia contracts. A contract
+//! is an entry without rules marked with `#kdl_contract = Actions`
+//! where `Actions` is a sum type. For each constructor `Actions.c`
+//! we generate a rule that dispatches the action to the handler
+//! `Contract.c` with the same fields.
+//!
+//! When the contract also has a `#kdl_state = init`, it receives
+//! the current state before the action, and each handler receives
+//! the state before the fields of the action:
+//!
+//! ```kind2
+//! #kdl_contract = Counter.Action
+//! #kdl_state = Counter.init
+//! Counter (state: Counter.State) (action: Counter.Action) : Counter.State
+//!
+//! Counter.add (state: Counter.State) (amount: U60) : Counter.State
+//! ```
+
+use kind_tree::concrete::expr::Expr;
+use kind_tree::concrete::pat::{Pat, PatIdent, PatKind};
+use kind_tree::concrete::{self, Binding, ExprKind, TopLevel};
+use kind_tree::symbol::Ident;
+
+use crate::diagnostic::PassDiagnostic;
+
+use super::DesugarState;
+
+impl<'a> DesugarState<'a> {
+    /// Checks that the state of a contract is a record, so the
+    /// layout of the state is described by its fields, and that
+    /// the contract receives a state of the same type.
+    fn check_contract_state(&mut self, state: &Ident, arg: &concrete::Argument) {
+        let entry = self.old_book.entries.get(state.to_str());
+
+        let record = match entry {
+            Some(TopLevel::Entry(entry)) => match &entry.typ.data {
+                ExprKind::Constr { name, .. } => match self.old_book.entries.get(name.to_str()) {
+                    Some(TopLevel::RecordType(_)) => name,
+                    _ => return self.send_err(PassDiagnostic::ContractStateIsNotARecord(state.range)),
+                },
+                _ => return self.send_err(PassDiagnostic::ContractStateIsNotARecord(state.range)),
+            },
+            // The erasure and the Kindelia compiler already
+            // complains about the missing init entry.
+            None => return,
+            _ => return self.send_err(PassDiagnostic::ContractStateIsNotARecord(state.range)),
+        };
+
+        let same_type = matches!(&arg.typ, Some(typ) if matches!(
+            &typ.data,
+            ExprKind::Constr { name, args } if name.to_str() == record.to_str() && args.is_empty()
+        ));
+
+        if !same_type {
+            let range = arg.typ.as_ref().map_or(arg.range, |typ| typ.range);
+            self.send_err(PassDiagnostic::ContractStateTypeMismatch(range, record.to_string()))
+        }
+    }
+
+    pub fn desugar_contract_rules(
+        &mut self,
+        entry: &concrete::Entry,
+        actions: &Ident,
+        state: Option<&Ident>,
+    ) -> Vec<Box<concrete::Rule>> {
+        if !entry.rules.is_empty() {
+            self.send_err(PassDiagnostic::ContractShouldNotHaveRules(entry.rules[0].range));
+            return Vec::new();
+        }
+
+        let arity = if state.is_some() { 2 } else { 1 };
+
+        if entry.args.len() != arity || entry.args.iter().any(|arg| arg.hidden) {
+            self.send_err(PassDiagnostic::ContractShouldReceiveTheAction(
+                entry.name.range,
+                state.is_some(),
+            ));
+            return Vec::new();
+        }
+
+        if let Some(state) = state {
+            self.check_contract_state(state, &entry.args[0])
+        }
+
+        let sum = match self.old_book.entries.get(actions.to_str()) {
+            Some(TopLevel::SumType(sum)) => sum,
+            _ => {
+                self.send_err(PassDiagnostic::ContractActionsIsNotASumType(actions.range));
+                return Vec::new();
+            }
+        };
+
+        let range = entry.range;
+        let state_var = state.map(|_| self.gen_name(range));
+        let mut rules = Vec::new();
+
+        for cons in &sum.constructors {
+            let handler = entry.name.add_segment(cons.name.to_str());
+
+            if !self.old_book.entries.contains_key(handler.to_str()) {
+                self.send_err(PassDiagnostic::ContractMissingHandler(
+                    cons.name.range,
+                    handler.to_string(),
+                ));
+                continue;
+            }
+
+            let fields = cons
+                .args
+                .iter()
+                .filter(|arg| !arg.hidden)
+                .map(|arg| arg.name.clone())
+                .collect::<Vec<Ident>>();
+
+            let pat = Box::new(Pat {
+                data: PatKind::App(
+                    sum.name.add_segment(cons.name.to_str()),
+                    fields
+                        .iter()
+                        .map(|field| {
+                            Box::new(Pat {
+                                data: PatKind::Var(PatIdent(field.clone())),
+                                range,
+                            })
+                        })
+                        .collect(),
+                ),
+                range,
+            });
+
+            let body = Expr::cons(
+                handler,
+                state_var
+                    .iter()
+                    .cloned()
+                    .chain(fields)
+                    .map(|field| Binding::Positional(Expr::var(field)))
+                    .collect(),
+                range,
+            );
+
+            let state_pat = state_var.iter().map(|var| {
+                Box::new(Pat {
+                    data: PatKind::Var(PatIdent(var.clone())),
+                    range,
+                })
+            });
+
+            rules.push(Box::new(concrete::Rule {
+                name: entry.name.clone(),
+                pats: state_pat.chain(std::iter::once(pat)).collect(),
+                body,
+                range,
+            }))
+        }
+
+        rules
+    }
+}
@@ -257,7 +257,11 @@ impl<'a> DesugarState<'a> {
         }
 
         if !unbound.is_empty() {
-            self.send_err(PassDiagnostic::NoCoverage(range, unbound));
+            let insert_at = matcher
+                .cases
+                .last()
+                .map(|case| Range::new(case.value.range.end, case.value.range.end, range.ctx));
+            self.send_err(PassDiagnostic::NoCoverage(range, unbound, insert_at));
             return desugared::Expr::err(range);
         }
 
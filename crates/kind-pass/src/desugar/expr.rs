@@ -84,7 +84,7 @@ impl<'a> DesugarState<'a> {
         let typ = self.desugar_expr(&sub.typ);
 
         let mut value = vec![];
-        self.desugar_record_field_sequence(range, &mut value, typ, &sub.fields);
+        self.desugar_record_field_sequence(&mut value, typ, &sub.fields);
 
         if self.failed {
             return Expr::err(range)
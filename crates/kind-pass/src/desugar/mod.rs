@@ -8,6 +8,7 @@
 
 use std::sync::mpsc::Sender;
 
+use fxhash::FxHashSet;
 use kind_report::data::Diagnostic;
 use kind_span::Range;
 use kind_tree::{
@@ -20,6 +21,7 @@ use crate::diagnostic::{PassDiagnostic, GenericPassError};
 
 pub mod app;
 pub mod attributes;
+pub mod contract;
 pub mod destruct;
 pub mod expr;
 pub mod top_level;
@@ -31,27 +33,45 @@ pub struct DesugarState<'a> {
     pub new_book: desugared::Book,
     pub name_count: u64,
     pub failed: bool,
+    /// Names of the definitions that had some error.
+    pub broken: FxHashSet<String>,
 }
 
 pub fn desugar_book(
     errors: Sender<Box<dyn Diagnostic>>,
     book: &concrete::Book,
 ) -> anyhow::Result<desugared::Book> {
+    let (new_book, broken) = desugar_book_partial(errors, book, Default::default());
+
+    if broken.is_empty() {
+        Ok(new_book)
+    } else {
+        Err(GenericPassError.into())
+    }
+}
+
+/// Desugars all of the definitions even if some of them fail. The
+/// parts that cannot be desugared are replaced by
+/// [desugared::ExprKind::Err] and the names of the definitions that
+/// failed are returned with the book. The definitions in `broken`
+/// failed in a previous stage, so they're not desugared.
+pub fn desugar_book_partial(
+    errors: Sender<Box<dyn Diagnostic>>,
+    book: &concrete::Book,
+    broken: FxHashSet<String>,
+) -> (desugared::Book, FxHashSet<String>) {
     let mut state = DesugarState {
         errors,
         old_book: book,
         new_book: Default::default(),
         name_count: 0,
         failed: false,
+        broken,
     };
 
     state.desugar_book(book);
 
-    if state.failed {
-        Err(GenericPassError.into())
-    } else {
-        Ok(state.new_book)
-    }
+    (state.new_book, state.broken)
 }
 
 impl<'a> DesugarState<'a> {
@@ -75,8 +95,32 @@ impl<'a> DesugarState<'a> {
     }
 
     pub fn desugar_book(&mut self, book: &concrete::Book) {
-        for top_level in book.entries.values() {
-            self.desugar_top_level(top_level)
+        for (name, top_level) in &book.entries {
+            if self.broken.contains(name) {
+                self.broken.extend(top_level_names(top_level));
+                continue;
+            }
+
+            self.failed = false;
+            self.desugar_top_level(top_level);
+
+            if self.failed {
+                self.broken.extend(top_level_names(top_level))
+            }
         }
     }
 }
+
+/// Names of all the entries that are created by a top level definition.
+fn top_level_names(top_level: &concrete::TopLevel) -> Vec<String> {
+    match top_level {
+        concrete::TopLevel::SumType(sum) => std::iter::once(sum.name.to_string())
+            .chain(sum.constructors.iter().map(|cons| sum.name.add_segment(cons.name.to_str()).to_string()))
+            .collect(),
+        concrete::TopLevel::RecordType(rec) => vec![
+            rec.name.to_string(),
+            rec.name.add_segment(rec.constructor.to_str()).to_string(),
+        ],
+        concrete::TopLevel::Entry(entry) => vec![entry.name.to_string()],
+    }
+}
@@ -1,5 +1,4 @@
 use fxhash::FxHashMap;
-use kind_span::Range;
 use kind_tree::{
     concrete::{self, TopLevel},
     desugared::{self, Expr},
@@ -7,6 +6,8 @@ use kind_tree::{
     telescope::Telescope,
 };
 
+use kind_derive::fields::shared_fields;
+
 use crate::{subst::subst_on_expr, diagnostic::PassDiagnostic};
 
 use super::DesugarState;
@@ -21,35 +22,82 @@ impl<'a> DesugarState<'a> {
         Telescope<concrete::Argument>,
         Vec<Box<Expr>>,
     )> {
+        let typ_range = typ.range;
         match typ.data {
-            desugared::ExprKind::Ctr { name, args } => {
-                let Some(TopLevel::RecordType(record)) = self.old_book.entries.get(name.to_str()) else { return None };
-                let entry_constructor = self.old_book.meta.get(
-                    record
-                        .name
-                        .add_segment(record.constructor.to_str())
-                        .to_str(),
-                )?;
-                Some((
-                    name,
-                    &record.parameters,
-                    entry_constructor
-                        .arguments
-                        .clone()
-                        .drop(record.parameters.len()),
-                    args,
-                ))
-            }
+            desugared::ExprKind::Ctr { name, args } => match self.old_book.entries.get(name.to_str())? {
+                TopLevel::RecordType(record) => {
+                    let entry_constructor = self.old_book.meta.get(
+                        record
+                            .name
+                            .add_segment(record.constructor.to_str())
+                            .to_str(),
+                    )?;
+                    Some((
+                        name,
+                        &record.parameters,
+                        entry_constructor
+                            .arguments
+                            .clone()
+                            .drop(record.parameters.len()),
+                        args,
+                    ))
+                }
+                // Sum types only have the fields that all of their
+                // constructors share.
+                TopLevel::SumType(sum) if sum.indices.is_empty() => {
+                    let fields = shared_fields(sum)
+                        .into_iter()
+                        .map(|(field, typ)| concrete::Argument::new_explicit(field, typ, typ_range))
+                        .collect();
+                    Some((name, &sum.parameters, Telescope::new(fields), args))
+                }
+                _ => None,
+            },
             _ => None,
         }
     }
 
+    /// The constructors of the sum type `name` that do not have the
+    /// `field` with the type that it has in the first constructor with
+    /// it. It's `None` if none of them has the field.
+    fn constructors_without_field(&self, name: &QualifiedIdent, field: &Ident) -> Option<Vec<String>> {
+        let Some(TopLevel::SumType(sum)) = self.old_book.entries.get(name.to_str()) else { return None };
+
+        let field_type = |cons: &concrete::Constructor| {
+            cons.args
+                .iter()
+                .find(|arg| !arg.hidden && arg.name.to_str() == field.to_str())
+                .map(|arg| arg.typ.as_ref().map(|typ| typ.to_string()))
+        };
+
+        let typ = sum.constructors.iter().find_map(field_type)?;
+
+        let missing = sum
+            .constructors
+            .iter()
+            .filter(|cons| field_type(cons).as_ref() != Some(&typ))
+            .map(|cons| sum.name.add_segment(cons.name.to_str()).to_string());
+
+        Some(missing.collect())
+    }
+
     pub fn desugar_record_field_sequence(
         &mut self,
-        range: Range,
         res: &mut Vec<(QualifiedIdent, Ident)>,
         typ: Box<desugared::Expr>,
         fields: &[Ident],
+    ) -> bool {
+        self.desugar_field_path(res, typ, fields, None)
+    }
+
+    /// Finds the types of each field of the path. The `parent` is the
+    /// field that has the type `typ`, if it's not the start of the path.
+    fn desugar_field_path(
+        &mut self,
+        res: &mut Vec<(QualifiedIdent, Ident)>,
+        typ: Box<desugared::Expr>,
+        fields: &[Ident],
+        parent: Option<&Ident>,
     ) -> bool {
         if fields.is_empty() {
             return true;
@@ -77,14 +125,25 @@ impl<'a> DesugarState<'a> {
 
                 subst_on_expr(&mut val, pair);
                 res.push((name, key));
-                self.desugar_record_field_sequence(range, res, val, &fields[1..]);
-                return true;
+                return self.desugar_field_path(res, val, &fields[1..], Some(&fields[0]));
+            } else if let Some(constructors) = self.constructors_without_field(&name, &fields[0]) {
+                self.send_err(PassDiagnostic::FieldNotInEveryConstructor(
+                    fields[0].range,
+                    fields[0].to_string(),
+                    constructors,
+                ));
             } else {
                 self.send_err(PassDiagnostic::CannotFindTheField(
                     fields[0].range,
                     fields[0].to_string()
                 ));
             }
+        } else if let Some(parent) = parent {
+            self.send_err(PassDiagnostic::PathLeavesRecord(
+                fields[0].range,
+                parent.to_string(),
+                typ.to_string(),
+            ));
         } else {
             self.send_err(PassDiagnostic::CannotAccessType(
                 fields[0].range,
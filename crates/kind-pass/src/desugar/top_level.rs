@@ -374,9 +374,38 @@ impl<'a> DesugarState<'a> {
     pub fn desugar_entry(&mut self, entry: &concrete::Entry) {
         self.name_count = 0;
 
+        // The derived `show` joins the strings with the `String.concat`
+        // of the library, that is not checked by the resolution.
+        if let Some(typ) = &entry.generated_by {
+            if entry.name.to_string() == format!("{}.show", typ)
+                && !self.check_implementation("String.concat", entry.range, Sugar::Show(typ.clone()))
+            {
+                return;
+            }
+        }
+
+        let attrs = self.desugar_attributes(&entry.attrs);
+
+        for strict in &attrs.strict {
+            let is_relevant = entry
+                .args
+                .iter()
+                .any(|arg| arg.name.to_str() == strict.to_str() && !arg.erased);
+
+            if !is_relevant {
+                self.send_err(PassDiagnostic::InvalidStrictArgument(strict.range, strict.to_string()))
+            }
+        }
+
+        let contract_rules = match &attrs.kdl_contract {
+            Some(actions) => self.desugar_contract_rules(entry, actions, attrs.kdl_state.as_ref()),
+            None => Vec::new(),
+        };
+
         let rules = entry
             .rules
             .iter()
+            .chain(contract_rules.iter())
             .map(|x| self.desugar_rule(&entry.args, x))
             .collect();
 
@@ -385,7 +414,7 @@ impl<'a> DesugarState<'a> {
             args: entry.args.map(|x| self.desugar_argument(x)).to_vec(),
             typ: self.desugar_expr(&entry.typ),
             range: entry.range,
-            attrs: self.desugar_attributes(&entry.attrs),
+            attrs,
             rules,
         };
 
@@ -1,6 +1,7 @@
 use std::{fmt::Display, error::Error};
 
-use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity};
+use kind_report::codes;
+use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity, Suggestion};
 use kind_span::{Range, SyntaxCtxIndex};
 use kind_tree::symbol::Ident;
 
@@ -15,6 +16,23 @@ impl Display for GenericPassError {
 
 impl Error for GenericPassError { }
 
+/// Fills the missing arguments with holes. It's only done when the
+/// function has no hidden arguments, because otherwise we cannot know
+/// which of the arguments are missing. Extra arguments are not removed,
+/// since we cannot know which ones are wrong.
+fn arity_suggestion(head: Range, got: &[Range], expected: usize, hidden: usize) -> Option<Suggestion> {
+    if hidden != 0 || got.len() >= expected {
+        return None;
+    }
+
+    let end = got.last().map_or(head.end, |arg| arg.end);
+
+    Some(Suggestion {
+        position: Range::new(end, end, head.ctx),
+        replacement: " _".repeat(expected - got.len()),
+    })
+}
+
 pub enum Sugar {
     DoNotation,
     List,
@@ -26,6 +44,7 @@ pub enum Sugar {
     Getter(String),
     Mutter(String),
     Match(String),
+    Show(String),
 }
 
 /// Describes all of the possible errors inside each
@@ -36,7 +55,7 @@ pub enum PassDiagnostic {
     DuplicatedNamed(Range, Range),
     LetDestructOnlyForRecord(Range),
     LetDestructOnlyForSum(Range),
-    NoCoverage(Range, Vec<String>),
+    NoCoverage(Range, Vec<String>, Option<Range>),
     CannotFindField(Range, Range, String),
     CannotFindConstructor(Range, Range, String),
     NeedToImplementMethods(Range, Sugar),
@@ -55,10 +74,22 @@ pub enum PassDiagnostic {
     AttributeExpectsAValue(Range),
     DuplicatedAttributeArgument(Range, Range),
     CannotDerive(String, Range),
-    AttributeDoesNotExists(Range),
+    AttributeDoesNotExists(Range, Ident, Option<String>),
     NeedsAField(Range),
     CannotFindTheField(Range, String),
     CannotAccessType(Range, String),
+    ContractShouldNotHaveRules(Range),
+    /// The flag says if the contract has a state.
+    ContractShouldReceiveTheAction(Range, bool),
+    ContractActionsIsNotASumType(Range),
+    ContractMissingHandler(Range, String),
+    ContractStateIsNotARecord(Range),
+    ContractStateTypeMismatch(Range, String),
+    InvalidStrictArgument(Range, String),
+    /// The name of the lint and the most similar known lint, if any.
+    UnknownLint(Range, String, Option<String>),
+    FieldNotInEveryConstructor(Range, String, Vec<String>),
+    PathLeavesRecord(Range, String, String),
 }
 
 // TODO: A way to build an error message with methods
@@ -70,7 +101,7 @@ impl Diagnostic for PassDiagnostic {
             PassDiagnostic::DuplicatedNamed(range, _) => Some(range.ctx),
             PassDiagnostic::LetDestructOnlyForRecord(range) => Some(range.ctx),
             PassDiagnostic::LetDestructOnlyForSum(range) => Some(range.ctx),
-            PassDiagnostic::NoCoverage(range, _) => Some(range.ctx),
+            PassDiagnostic::NoCoverage(range, _, _) => Some(range.ctx),
             PassDiagnostic::CannotFindField(range, _, _) => Some(range.ctx),
             PassDiagnostic::CannotFindConstructor(range, _, _) => Some(range.ctx),
             PassDiagnostic::NeedToImplementMethods(range, _) => Some(range.ctx),
@@ -89,17 +120,27 @@ impl Diagnostic for PassDiagnostic {
             PassDiagnostic::AttributeExpectsAValue(range) => Some(range.ctx),
             PassDiagnostic::DuplicatedAttributeArgument(range, _) => Some(range.ctx),
             PassDiagnostic::CannotDerive(_, range) => Some(range.ctx),
-            PassDiagnostic::AttributeDoesNotExists(range) => Some(range.ctx),
+            PassDiagnostic::AttributeDoesNotExists(range, _, _) => Some(range.ctx),
             PassDiagnostic::NeedsAField(range) => Some(range.ctx),
             PassDiagnostic::CannotFindTheField(range, _) => Some(range.ctx),
             PassDiagnostic::CannotAccessType(range, _) => Some(range.ctx),
+            PassDiagnostic::ContractShouldNotHaveRules(range) => Some(range.ctx),
+            PassDiagnostic::ContractShouldReceiveTheAction(range, _) => Some(range.ctx),
+            PassDiagnostic::ContractActionsIsNotASumType(range) => Some(range.ctx),
+            PassDiagnostic::ContractMissingHandler(range, _) => Some(range.ctx),
+            PassDiagnostic::ContractStateIsNotARecord(range) => Some(range.ctx),
+            PassDiagnostic::ContractStateTypeMismatch(range, _) => Some(range.ctx),
+            PassDiagnostic::InvalidStrictArgument(range, _) => Some(range.ctx),
+            PassDiagnostic::UnknownLint(range, _, _) => Some(range.ctx),
+            PassDiagnostic::FieldNotInEveryConstructor(range, _, _) => Some(range.ctx),
+            PassDiagnostic::PathLeavesRecord(range, _, _) => Some(range.ctx),
         }
     }
 
     fn to_diagnostic_frame(&self) -> DiagnosticFrame {
         match self {
             PassDiagnostic::UnboundVariable(idents, suggestions) => DiagnosticFrame {
-                code: 100,
+                code: codes::UNBOUND_VARIABLE,
                 severity: Severity::Error,
                 title: format!("Cannot find the definition '{}'.", idents[0].to_str()),
                 subtitles: vec![],
@@ -111,6 +152,7 @@ impl Diagnostic for PassDiagnostic {
                 } else {
                     "Take a look at naming rules at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()
                 }],
+                suggestions: vec![],
                 positions: idents
                     .iter()
                     .map(|ident| Marker {
@@ -152,20 +194,22 @@ impl Diagnostic for PassDiagnostic {
                 }
 
                 DiagnosticFrame {
-                    code: 200,
+                    code: codes::CANNOT_USE_IRRELEVANT,
                     severity: Severity::Error,
                     title: "This irrelevant parameter should not be used in a relevant position.".to_string(),
                     subtitles: vec![],
                     hints: vec![],
+                    suggestions: vec![],
                     positions,
                 }
             }
             PassDiagnostic::LetDestructOnlyForRecord(place) => DiagnosticFrame {
-                code: 200,
+                code: codes::LET_DESTRUCT_ONLY_FOR_RECORD,
                 severity: Severity::Error,
                 title: "Can only destruct record types.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -175,11 +219,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::RulesWithInconsistentArity(arities) => DiagnosticFrame {
-                code: 201,
+                code: codes::RULES_WITH_INCONSISTENT_ARITY,
                 severity: Severity::Error,
                 title: "All of the rules of a entry should have the same number of patterns.".to_string(),
                 subtitles: vec![],
                 hints: vec!["Check if you're trying to use a function that manipulats erased variables.".to_string()],
+                suggestions: vec![],
                 positions: arities
                     .iter()
                     .map(|(range, size)| Marker {
@@ -192,7 +237,7 @@ impl Diagnostic for PassDiagnostic {
                     .collect(),
             },
             PassDiagnostic::RuleWithIncorrectArity(place, _got, expected, hidden) => DiagnosticFrame {
-                code: 203,
+                code: codes::RULE_WITH_INCORRECT_ARITY,
                 severity: Severity::Error,
                 title: "This rule is with the incorrect arity.".to_string(),
                 subtitles: vec![],
@@ -203,6 +248,7 @@ impl Diagnostic for PassDiagnostic {
                 } else {
                     format!("This rule expects {} arguments or {} (without hidden ones)", expected, expected - hidden)
                 }],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -212,7 +258,7 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::NeedToImplementMethods(expr_place, sugar) => DiagnosticFrame {
-                code: 204,
+                code: codes::NEED_TO_IMPLEMENT_METHODS,
                 severity: Severity::Error,
                 title: "Required functions are not implemented for this type.".to_string(),
                 subtitles: vec![],
@@ -226,8 +272,10 @@ impl Diagnostic for PassDiagnostic {
                     Sugar::U120 => "You must implement 'U120.new' in order to use the u120 notation.".to_string(),
                     Sugar::Match(_) => "You must implement 'match' in order to use the match notation (or derive match with #derive[match]).".to_string(),
                     Sugar::Mutter(typ) => format!("You must derive 'mutters' for '{}' in order to use this syntax", typ),
-                    Sugar::Getter(typ) => format!("You must derive 'getters' for '{}' in order to use this syntax", typ)
+                    Sugar::Getter(typ) => format!("You must derive 'getters' for '{}' in order to use this syntax", typ),
+                    Sugar::Show(typ) => format!("You must implement 'String.concat' in order to derive 'show' for '{}'", typ),
                 }],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *expr_place,
                     color: Color::Fst,
@@ -237,11 +285,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::LetDestructOnlyForSum(place) => DiagnosticFrame {
-                code: 206,
+                code: codes::LET_DESTRUCT_ONLY_FOR_SUM,
                 severity: Severity::Error,
                 title: "Can only use match on sum types.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -251,11 +300,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::CannotFindField(place, def_name, ty) => DiagnosticFrame {
-                code: 207,
+                code: codes::CANNOT_FIND_FIELD,
                 severity: Severity::Error,
                 title: format!("Cannot find this field in the definition '{}'.", ty),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *place,
@@ -274,11 +324,12 @@ impl Diagnostic for PassDiagnostic {
                 ],
             },
             PassDiagnostic::CannotFindConstructor(place, def_name, ty) => DiagnosticFrame {
-                code: 208,
+                code: codes::CANNOT_FIND_CONSTRUCTOR,
                 severity: Severity::Error,
                 title: format!("Cannot find this constructor in the type definition '{}'.", ty),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *place,
@@ -296,12 +347,19 @@ impl Diagnostic for PassDiagnostic {
                     },
                 ],
             },
-            PassDiagnostic::NoCoverage(place, other) => DiagnosticFrame {
-                code: 209,
+            PassDiagnostic::NoCoverage(place, other, insert_at) => DiagnosticFrame {
+                code: codes::NO_COVERAGE,
                 severity: Severity::Error,
                 title: "The match is not covering all of the possibilities!".to_string(),
                 subtitles: vec![],
                 hints: vec![format!("Need a case for {}", other.iter().map(|x| format!("'{}'", x)).collect::<Vec<String>>().join(", "))],
+                suggestions: insert_at
+                    .iter()
+                    .map(|position| Suggestion {
+                        position: *position,
+                        replacement: other.iter().map(|case| format!("; {} => _", case)).collect(),
+                    })
+                    .collect(),
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -320,7 +378,7 @@ impl Diagnostic for PassDiagnostic {
                 }];
 
                 DiagnosticFrame {
-                    code: 210,
+                    code: codes::INCORRECT_ARITY,
                     severity: Severity::Error,
                     title: "Incorrect arity.".to_string(),
                     subtitles: vec![],
@@ -336,17 +394,19 @@ impl Diagnostic for PassDiagnostic {
                             got.len()
                         )
                     }],
+                    suggestions: arity_suggestion(*head_range, got, *expected, *hidden).into_iter().collect(),
                     positions,
                 }
             }
             PassDiagnostic::SugarIsBadlyImplemented(head_range, place_range, expected) => DiagnosticFrame {
-                code: 211,
+                code: codes::SUGAR_IS_BADLY_IMPLEMENTED,
                 severity: Severity::Error,
                 title: "Incorrect arity in the sugar definition".to_string(),
                 subtitles: vec![],
                 hints: vec![format!(
                     "Take a look at how sugar functions should be implemented at https://github.com/Kindelia/Kind2/blob/master/guide/sugars.md"
                 )],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *head_range,
@@ -369,11 +429,12 @@ impl Diagnostic for PassDiagnostic {
                 ],
             },
             PassDiagnostic::DuplicatedNamed(first_decl, last_decl) => DiagnosticFrame {
-                code: 212,
+                code: codes::DUPLICATED_NAMED,
                 severity: Severity::Error,
                 title: "Repeated named variable".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *last_decl,
@@ -392,11 +453,12 @@ impl Diagnostic for PassDiagnostic {
                 ],
             },
             PassDiagnostic::RepeatedVariable(first_decl, last_decl) => DiagnosticFrame {
-                code: 214,
+                code: codes::REPEATED_VARIABLE,
                 severity: Severity::Error,
                 title: "Repeated name".to_string(),
                 subtitles: vec![],
                 hints: vec!["Rename one of the occurences".to_string()],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *last_decl,
@@ -415,11 +477,12 @@ impl Diagnostic for PassDiagnostic {
                 ],
             },
             PassDiagnostic::CannotFindAlias(name, range) => DiagnosticFrame {
-                code: 214,
+                code: codes::CANNOT_FIND_ALIAS,
                 severity: Severity::Error,
                 title: "Cannot find alias".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -450,20 +513,22 @@ impl Diagnostic for PassDiagnostic {
                 });
 
                 DiagnosticFrame {
-                    code: 214,
+                    code: codes::SHOULD_BE_A_PARAMETER,
                     severity: Severity::Error,
                     title: "The expression is not the parameter declared in the type constructor".to_string(),
                     subtitles: vec![],
                     hints: vec![],
+                    suggestions: vec![],
                     positions,
                 }
             }
             PassDiagnostic::NotATypeConstructor(error_range, declaration_range) => DiagnosticFrame {
-                code: 214,
+                code: codes::NOT_A_TYPE_CONSTRUCTOR,
                 severity: Severity::Error,
                 title: "This is not the type that is being declared.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: *error_range,
@@ -482,7 +547,7 @@ impl Diagnostic for PassDiagnostic {
                 ],
             },
             PassDiagnostic::NoFieldCoverage(place, other) => DiagnosticFrame {
-                code: 209,
+                code: codes::NO_FIELD_COVERAGE,
                 severity: Severity::Error,
                 title: "The case is not covering all the values inside of it!".to_string(),
                 subtitles: vec![],
@@ -490,6 +555,7 @@ impl Diagnostic for PassDiagnostic {
                     "Need variables for {}",
                     other.iter().map(|x| format!("'{}'", x)).collect::<Vec<String>>().join(", ")
                 )],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -499,11 +565,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::AttributeDoesNotExpectEqual(place) => DiagnosticFrame {
-                code: 209,
+                code: codes::ATTRIBUTE_DOES_NOT_EXPECT_EQUAL,
                 severity: Severity::Error,
                 title: "This attribute does not support values!".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -513,11 +580,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::AttributeDoesNotExpectArgs(place) => DiagnosticFrame {
-                code: 209,
+                code: codes::ATTRIBUTE_DOES_NOT_EXPECT_ARGS,
                 severity: Severity::Error,
                 title: "This attribute does not expect arguments".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -527,11 +595,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::InvalidAttributeArgument(place) => DiagnosticFrame {
-                code: 209,
+                code: codes::INVALID_ATTRIBUTE_ARGUMENT,
                 severity: Severity::Error,
                 title: "Invalid attribute argument".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -541,11 +610,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::CannotDerive(name, place) => DiagnosticFrame {
-                code: 209,
+                code: codes::CANNOT_DERIVE,
                 severity: Severity::Error,
                 title: format!("Cannot derive '{}' for this definition", name),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -556,11 +626,12 @@ impl Diagnostic for PassDiagnostic {
             },
 
             PassDiagnostic::AttributeExpectsAValue(place) => DiagnosticFrame {
-                code: 209,
+                code: codes::ATTRIBUTE_EXPECTS_A_VALUE,
                 severity: Severity::Error,
                 title: "This attribute expects a value".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -569,12 +640,22 @@ impl Diagnostic for PassDiagnostic {
                     main: true,
                 }],
             },
-            PassDiagnostic::AttributeDoesNotExists(place) => DiagnosticFrame {
-                code: 209,
+            PassDiagnostic::AttributeDoesNotExists(place, name, similar) => DiagnosticFrame {
+                code: codes::ATTRIBUTE_DOES_NOT_EXIST,
                 severity: Severity::Error,
                 title: "This attribute does not exists".to_string(),
                 subtitles: vec![],
-                hints: vec![],
+                hints: similar
+                    .iter()
+                    .map(|similar| format!("Maybe you're looking for '#{}'", similar))
+                    .collect(),
+                suggestions: similar
+                    .iter()
+                    .map(|similar| Suggestion {
+                        position: name.range,
+                        replacement: similar.clone(),
+                    })
+                    .collect(),
                 positions: vec![Marker {
                     position: *place,
                     color: Color::Fst,
@@ -584,11 +665,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::DuplicatedAttributeArgument(first, sec) => DiagnosticFrame {
-                code: 210,
+                code: codes::DUPLICATED_ATTRIBUTE_ARGUMENT,
                 severity: Severity::Warning,
                 title: "Duplicated attribute argument".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *sec,
                     color: Color::For,
@@ -604,11 +686,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::NeedsAField(range) => DiagnosticFrame {
-                code: 211,
+                code: codes::NEEDS_A_FIELD,
                 severity: Severity::Error,
                 title: "This expression does not access any field.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -618,11 +701,12 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::CannotFindTheField(range, _) => DiagnosticFrame {
-                code: 212,
+                code: codes::CANNOT_FIND_THE_FIELD,
                 severity: Severity::Error,
                 title: "Cannot find the field".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -632,11 +716,106 @@ impl Diagnostic for PassDiagnostic {
                 }],
             },
             PassDiagnostic::CannotAccessType(range, _) => DiagnosticFrame {
-                code: 213,
+                code: codes::CANNOT_ACCESS_TYPE,
                 severity: Severity::Error,
                 title: "Cannot access the type fields.".to_string(),
                 subtitles: vec![],
                 hints: vec!["This syntax only access some types, it does not make complete type directed syntax.".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::ContractShouldNotHaveRules(range) => DiagnosticFrame {
+                code: codes::CONTRACT_SHOULD_NOT_HAVE_RULES,
+                severity: Severity::Error,
+                title: "A contract should not have rules.".to_string(),
+                subtitles: vec![],
+                hints: vec!["The rules of a contract are generated from the constructors of its actions.".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::ContractShouldReceiveTheAction(range, has_state) => DiagnosticFrame {
+                code: codes::CONTRACT_SHOULD_RECEIVE_THE_ACTION,
+                severity: Severity::Error,
+                title: if *has_state {
+                    "A contract with a state should receive only the state and the action as explicit arguments.".to_string()
+                } else {
+                    "A contract should receive only the action as an explicit argument.".to_string()
+                },
+                subtitles: vec![],
+                hints: vec![],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::ContractActionsIsNotASumType(range) => DiagnosticFrame {
+                code: codes::CONTRACT_ACTIONS_IS_NOT_A_SUM_TYPE,
+                severity: Severity::Error,
+                title: "The actions of a contract should be a sum type.".to_string(),
+                subtitles: vec![],
+                hints: vec![],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::ContractMissingHandler(range, name) => DiagnosticFrame {
+                code: codes::CONTRACT_MISSING_HANDLER,
+                severity: Severity::Error,
+                title: format!("Cannot find the handler '{}' for this action.", name),
+                subtitles: vec![],
+                hints: vec![],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::ContractStateIsNotARecord(range) => DiagnosticFrame {
+                code: codes::CONTRACT_STATE_IS_NOT_A_RECORD,
+                severity: Severity::Error,
+                title: "The state of a contract should be a record.".to_string(),
+                subtitles: vec![],
+                hints: vec![],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::ContractStateTypeMismatch(range, record) => DiagnosticFrame {
+                code: codes::CONTRACT_STATE_TYPE_MISMATCH,
+                severity: Severity::Error,
+                title: format!("The state that the contract receives should have the type '{}'.", record),
+                subtitles: vec![],
+                hints: vec!["It is the type of the initial state given by '#kdl_state'.".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -645,6 +824,78 @@ impl Diagnostic for PassDiagnostic {
                     main: true,
                 }],
             },
+            PassDiagnostic::InvalidStrictArgument(range, name) => DiagnosticFrame {
+                code: codes::INVALID_STRICT_ARGUMENT,
+                severity: Severity::Error,
+                title: format!("'{}' is not a relevant argument of this function.", name),
+                subtitles: vec![],
+                hints: vec!["Only arguments that are not erased can be strict.".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::UnknownLint(range, name, similar) => DiagnosticFrame {
+                code: codes::UNKNOWN_LINT,
+                severity: Severity::Error,
+                title: format!("The lint '{}' does not exists.", name),
+                subtitles: vec![],
+                hints: similar
+                    .iter()
+                    .map(|similar| format!("Maybe you're looking for '{}'", similar))
+                    .collect(),
+                suggestions: similar
+                    .iter()
+                    .map(|similar| Suggestion {
+                        position: *range,
+                        replacement: similar.clone(),
+                    })
+                    .collect(),
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::FieldNotInEveryConstructor(range, name, constructors) => DiagnosticFrame {
+                code: codes::FIELD_NOT_IN_EVERY_CONSTRUCTOR,
+                severity: Severity::Error,
+                title: format!("The field '{}' is not in every constructor of the type.", name),
+                subtitles: vec![],
+                hints: vec![format!(
+                    "It's missing or it has another type in {}",
+                    constructors.iter().map(|x| format!("'{}'", x)).collect::<Vec<_>>().join(", ")
+                )],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            PassDiagnostic::PathLeavesRecord(range, parent, typ) => DiagnosticFrame {
+                code: codes::PATH_LEAVES_RECORD,
+                severity: Severity::Error,
+                title: format!("The field '{}' does not have fields.", parent),
+                subtitles: vec![],
+                hints: vec![format!("Its type is '{}', that is not a record or a sum type", typ)],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "This field cannot be accessed".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
         }
     }
 
@@ -656,7 +907,7 @@ impl Diagnostic for PassDiagnostic {
             | DuplicatedNamed(_, _)
             | LetDestructOnlyForRecord(_)
             | LetDestructOnlyForSum(_)
-            | NoCoverage(_, _)
+            | NoCoverage(_, _, _)
             | CannotFindField(_, _, _)
             | CannotFindConstructor(_, _, _)
             | NeedToImplementMethods(_, _)
@@ -678,7 +929,17 @@ impl Diagnostic for PassDiagnostic {
             | NeedsAField(_)
             | CannotFindTheField(_, _)
             | CannotAccessType(_, _)
-            | AttributeDoesNotExists(_) => Severity::Error,
+            | ContractShouldNotHaveRules(_)
+            | ContractShouldReceiveTheAction(_, _)
+            | ContractActionsIsNotASumType(_)
+            | ContractMissingHandler(_, _)
+            | ContractStateIsNotARecord(_)
+            | ContractStateTypeMismatch(_, _)
+            | InvalidStrictArgument(_, _)
+            | AttributeDoesNotExists(_, _, _)
+            | UnknownLint(_, _, _)
+            | FieldNotInEveryConstructor(_, _, _)
+            | PathLeavesRecord(_, _, _) => Severity::Error,
         }
     }
 }
@@ -122,7 +122,7 @@ impl<'a> ErasureState<'a> {
                 }
             }
 
-            if entr.attrs.kdl_run || entr.attrs.keep {
+            if entr.attrs.kdl_run || entr.attrs.keep || entr.attrs.kdl_contract.is_some() {
                 let id = self.get_edge_or_create(&entr.name);
                 self.set_relevance(id, Relevance::Relevant, entr.name.range);
                 entrypoints.push(id);
@@ -207,7 +207,8 @@ impl<'a> ErasureState<'a> {
             self.erase_expr(Ambient::Irrelevant, id, &arg.typ);
             self.ctx.insert(arg.name.to_string(), Relevance::Irrelevant);
             if !arg.erased {
-                args.push((arg.name.to_string(), arg.range, false))
+                let strict = entry.attrs.strict.iter().any(|name| name.to_str() == arg.name.to_str());
+                args.push((arg.name.to_string(), arg.range, strict))
             }
         }
 
@@ -6,11 +6,21 @@ use std::fmt::Display;
 use std::sync::mpsc::Sender;
 
 use fxhash::FxHashMap;
+use kind_derive::confusion::derive_no_confusion;
+use kind_derive::enumeration::derive_enum;
+use kind_derive::equal::{derive_eq, derive_eq_rec};
+use kind_derive::fields::{derive_getters_sum, derive_mutters_sum, derive_setters_sum};
+use kind_derive::functor::{derive_fold, derive_functor};
 use kind_derive::getters::derive_getters;
+use kind_derive::hash::{derive_hash, derive_hash_rec};
+use kind_derive::induction::derive_induction;
 use kind_derive::matching::derive_match;
 use kind_derive::mutters::derive_mutters;
 use kind_derive::open::derive_match_rec;
+use kind_derive::order::{derive_ord, derive_ord_rec};
+use kind_derive::serialize::{derive_serialize, derive_serialize_rec};
 use kind_derive::setters::derive_setters;
+use kind_derive::show::{derive_show, derive_show_rec};
 use kind_report::data::Diagnostic;
 use kind_span::Locatable;
 use kind_span::Range;
@@ -19,7 +29,8 @@ use kind_tree::concrete::EntryMeta;
 use kind_tree::concrete::Module;
 use kind_tree::concrete::RecordDecl;
 use kind_tree::concrete::SumTypeDecl;
-use kind_tree::concrete::{Attribute, TopLevel};
+use kind_tree::concrete::{Attribute, AttributeStyle, TopLevel};
+use kind_tree::symbol::Ident;
 
 use crate::diagnostic::PassDiagnostic;
 
@@ -37,7 +48,17 @@ pub enum Derive {
     Match,
     Getters,
     Setters,
-    Mutters
+    Mutters,
+    Eq,
+    Show,
+    Induction,
+    NoConfusion,
+    Ord,
+    Hash,
+    Functor,
+    Fold,
+    Serialize,
+    Enum,
 }
 
 impl Display for Derive {
@@ -47,6 +68,16 @@ impl Display for Derive {
             Derive::Getters => write!(f, "getters"),
             Derive::Setters => write!(f, "setters"),
             Derive::Mutters => write!(f, "mutters"),
+            Derive::Eq => write!(f, "eq"),
+            Derive::Show => write!(f, "show"),
+            Derive::Induction => write!(f, "induction"),
+            Derive::NoConfusion => write!(f, "no_confusion"),
+            Derive::Ord => write!(f, "ord"),
+            Derive::Hash => write!(f, "hash"),
+            Derive::Functor => write!(f, "functor"),
+            Derive::Fold => write!(f, "fold"),
+            Derive::Serialize => write!(f, "serialize"),
+            Derive::Enum => write!(f, "enum"),
         }
     }
 }
@@ -69,10 +100,42 @@ fn string_to_derive(name: &str) -> Option<Derive> {
         "getters" => Some(Derive::Getters),
         "setters" => Some(Derive::Setters),
         "mutters" => Some(Derive::Mutters),
+        "eq" => Some(Derive::Eq),
+        "show" => Some(Derive::Show),
+        "induction" => Some(Derive::Induction),
+        "no_confusion" => Some(Derive::NoConfusion),
+        "ord" => Some(Derive::Ord),
+        "hash" => Some(Derive::Hash),
+        "functor" => Some(Derive::Functor),
+        "fold" => Some(Derive::Fold),
+        "serialize" => Some(Derive::Serialize),
+        "enum" => Some(Derive::Enum),
         _ => None,
     }
 }
 
+/// The derivations that are written in Kind are named by the
+/// definitions that implement them, so they start with an upper case
+/// letter like every other top level name.
+fn is_user_derivation(name: &str) -> bool {
+    name.starts_with(|chr: char| chr.is_ascii_uppercase())
+}
+
+/// Gets the names of the derivations of a declaration that are not
+/// built into the compiler. They are run by the driver after all of
+/// the definitions that they need are loaded.
+pub fn user_derivations(attrs: &[Attribute]) -> Vec<Ident> {
+    attrs
+        .iter()
+        .filter(|attr| attr.name.to_str() == "derive")
+        .flat_map(|attr| &attr.args)
+        .filter_map(|arg| match arg {
+            AttributeStyle::Ident(_, ident) if is_user_derivation(ident.to_str()) => Some(ident.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn expand_derive(error_channel: Channel, attrs: &[Attribute]) -> Option<Derivations> {
     use kind_tree::concrete::AttributeStyle::*;
 
@@ -97,6 +160,7 @@ pub fn expand_derive(error_channel: Channel, attrs: &[Attribute]) -> Option<Deri
                     let key = string_to_derive(ident.to_str()).unwrap();
                     insert_or_report(error_channel.clone(), &mut defs, key, *range)
                 }
+                Ident(_, ident) if is_user_derivation(ident.to_str()) => (),
                 other => {
                     let err = Box::new(PassDiagnostic::InvalidAttributeArgument(other.locate()));
                     error_channel.send(err).unwrap();
@@ -113,6 +177,34 @@ pub fn expand_derive(error_channel: Channel, attrs: &[Attribute]) -> Option<Deri
     }
 }
 
+/// The entries that a derivation generates and its errors.
+type Derived = (Vec<Entry>, Vec<Box<dyn Diagnostic>>);
+
+/// Runs the derivation `derive` of a type, adds the entries that it
+/// generates and reports its errors. Returns if it failed.
+fn run_derivation<T>(
+    error_channel: &Channel,
+    entries: &mut FxHashMap<String, (Entry, EntryMeta)>,
+    range: Range,
+    decl: &T,
+    derive: &dyn Fn(Range, &T) -> Derived,
+) -> bool {
+    let (res, errs) = derive(range, decl);
+
+    for res in res {
+        let info = res.extract_book_info();
+        entries.insert(res.name.to_string(), (res, info));
+    }
+
+    let failed = !errs.is_empty();
+
+    for err in errs {
+        error_channel.send(err).unwrap();
+    }
+
+    failed
+}
+
 pub fn expand_sum_type(
     error_channel: Channel,
     entries: &mut FxHashMap<String, (Entry, EntryMeta)>,
@@ -120,66 +212,90 @@ pub fn expand_sum_type(
     derivations: Derivations,
 ) -> bool {
     let mut failed = false;
+    let range = sum.name.range;
 
     for (key, val) in derivations {
-        match key {
-            Derive::Match => {
-                let (res, errs) = derive_match(sum.name.range, sum);
-                let info = res.extract_book_info();
-                entries.insert(res.name.to_string(), (res, info));
-                for err in errs {
-                    error_channel.send(err).unwrap();
-                    failed = true;
-                }
-            }
-            other => {
-                error_channel
-                    .send(Box::new(PassDiagnostic::CannotDerive(
-                        other.to_string(),
-                        val,
-                    )))
-                    .unwrap();
-                failed = true;
+        let mut derive = |derive: &dyn Fn(Range, &SumTypeDecl) -> Derived| {
+            run_derivation(&error_channel, entries, range, sum, derive)
+        };
+
+        failed |= match key {
+            Derive::Match => derive(&|range, sum| {
+                let (res, errs) = derive_match(range, sum);
+                (vec![res], errs)
+            }),
+            Derive::Induction => derive(&|range, sum| {
+                let (res, errs) = derive_induction(range, sum);
+                (vec![res], errs)
+            }),
+            Derive::Eq => derive(&derive_eq),
+            Derive::Show => derive(&derive_show),
+            Derive::NoConfusion => derive(&derive_no_confusion),
+            Derive::Ord => derive(&derive_ord),
+            Derive::Serialize => derive(&derive_serialize),
+            Derive::Enum => derive(&derive_enum),
+            Derive::Hash => derive(&derive_hash),
+            Derive::Functor => derive(&derive_functor),
+            Derive::Fold => derive(&derive_fold),
+            Derive::Getters | Derive::Setters | Derive::Mutters => {
+                let fields = match key {
+                    Derive::Getters => derive_getters_sum,
+                    Derive::Setters => derive_setters_sum,
+                    _ => derive_mutters_sum,
+                };
+                derive(&|range, sum| {
+                    let (res, mut errs) = fields(range, sum);
+                    // Without a field that every constructor has there is
+                    // nothing to access.
+                    if res.is_empty() && errs.is_empty() {
+                        errs.push(Box::new(PassDiagnostic::CannotDerive(key.to_string(), val)));
+                    }
+                    (res, errs)
+                })
             }
-        }
+        };
     }
 
     failed
 }
 
 pub fn expand_record_type(
-    _error_channel: Channel,
+    error_channel: Channel,
     entries: &mut FxHashMap<String, (Entry, EntryMeta)>,
     rec: &RecordDecl,
     derivations: Derivations,
-) {
-    for (key, _) in derivations {
-        match key {
-            Derive::Match => {
-                let res = derive_match_rec(rec.name.range, rec);
-                let info = res.extract_book_info();
-                entries.insert(res.name.to_string(), (res, info));
-            }
-            Derive::Getters => {
-                for res in derive_getters(rec.name.range, rec) {
-                    let info = res.extract_book_info();
-                    entries.insert(res.name.to_string(), (res, info));
-                }
-            }
-            Derive::Setters => {
-                for res in derive_setters(rec.name.range, rec) {
-                    let info = res.extract_book_info();
-                    entries.insert(res.name.to_string(), (res, info));
-                }
-            }
-            Derive::Mutters => {
-                for res in derive_mutters(rec.name.range, rec) {
-                    let info = res.extract_book_info();
-                    entries.insert(res.name.to_string(), (res, info));
-                }
+) -> bool {
+    let mut failed = false;
+    let range = rec.name.range;
+
+    for (key, val) in derivations {
+        let mut derive = |derive: &dyn Fn(Range, &RecordDecl) -> Derived| {
+            run_derivation(&error_channel, entries, range, rec, derive)
+        };
+
+        failed |= match key {
+            Derive::Match => derive(&|range, rec| (vec![derive_match_rec(range, rec)], Vec::new())),
+            Derive::Getters => derive(&|range, rec| (derive_getters(range, rec), Vec::new())),
+            Derive::Setters => derive(&|range, rec| (derive_setters(range, rec), Vec::new())),
+            Derive::Mutters => derive(&|range, rec| (derive_mutters(range, rec), Vec::new())),
+            Derive::Eq => derive(&derive_eq_rec),
+            Derive::Show => derive(&derive_show_rec),
+            Derive::Ord => derive(&derive_ord_rec),
+            Derive::Serialize => derive(&derive_serialize_rec),
+            Derive::Hash => derive(&derive_hash_rec),
+            other => {
+                error_channel
+                    .send(Box::new(PassDiagnostic::CannotDerive(
+                        other.to_string(),
+                        val,
+                    )))
+                    .unwrap();
+                true
             }
-        }
+        };
     }
+
+    failed
 }
 
 pub fn expand_module(error_channel: Channel, module: &mut Module) -> bool {
@@ -198,7 +314,7 @@ pub fn expand_module(error_channel: Channel, module: &mut Module) -> bool {
             }
             TopLevel::RecordType(rec) => {
                 if let Some(derive) = expand_derive(error_channel.clone(), &rec.attrs) {
-                    expand_record_type(error_channel.clone(), &mut entries, rec, derive)
+                    failed |= expand_record_type(error_channel.clone(), &mut entries, rec, derive)
                 } else {
                     failed = true;
                 }
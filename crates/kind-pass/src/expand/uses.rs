@@ -1,5 +1,6 @@
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use kind_report::data::Diagnostic;
+use kind_span::Range;
 use kind_tree::concrete::{visitor::Visitor, Module};
 /// Expands sum type and record definitions to a lot of
 /// helper definitions like eliminators and replace qualified identifiers
@@ -9,7 +10,8 @@ use std::sync::mpsc::Sender;
 use crate::diagnostic::PassDiagnostic;
 
 pub struct Expand {
-    pub names: FxHashMap<String, String>,
+    pub names: FxHashMap<String, (String, Range)>,
+    pub used: FxHashSet<String>,
     pub errors: Sender<Box<dyn Diagnostic>>,
     pub failed: bool,
 }
@@ -20,7 +22,7 @@ impl Visitor for Expand {
             return;
         }
         let alias = match self.names.get(&ident.get_root()) {
-            Some(path) => path,
+            Some((path, _)) => path,
             None => {
                 self.errors
                     .send(Box::new(PassDiagnostic::CannotFindAlias(
@@ -32,6 +34,7 @@ impl Visitor for Expand {
                 return;
             }
         };
+        self.used.insert(ident.get_root());
         match &ident.get_aux() {
             Some(post) => {
                 ident.change_root(format!("{}.{}", alias, post));
@@ -42,14 +45,32 @@ impl Visitor for Expand {
     }
 }
 
-pub fn expand_uses(module: &mut Module, errors: Sender<Box<dyn Diagnostic>>) -> bool {
+/// Replaces the aliases of the module by their paths and returns the
+/// ones that are never used, in the order that they were declared. It
+/// returns `None` if the module uses an alias that does not exist.
+pub fn expand_uses(module: &mut Module, errors: Sender<Box<dyn Diagnostic>>) -> Option<Vec<(String, Range)>> {
     let mut session = Expand {
         names: module.uses.clone(),
+        used: FxHashSet::default(),
         errors,
         failed: false,
     };
     for entry in module.entries.iter_mut() {
         session.visit_top_level(entry)
     }
-    session.failed
+
+    if session.failed {
+        return None;
+    }
+
+    let mut unused = session
+        .names
+        .into_iter()
+        .filter(|(alias, _)| !session.used.contains(alias))
+        .map(|(alias, (_, range))| (alias, range))
+        .collect::<Vec<_>>();
+
+    unused.sort_by_key(|(_, range)| range.start.index);
+
+    Some(unused)
 }
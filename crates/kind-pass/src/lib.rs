@@ -4,11 +4,15 @@
 //! * [expand][expand]   - Expand some attributes and derivations of each construction.
 //! * [unbound][unbound] - Collects all of the unbound definitions and check the linearity of them.
 //! * [inline][inline]   - Inlines expressions
+//! * [strictness][strictness] - Infers which arguments should be evaluated eagerly
+//! * [unused][unused] - Warns about variables, definitions and aliases that are never used
 
 pub mod desugar;
 pub mod erasure;
 mod diagnostic;
 pub mod expand;
 pub mod inline;
+pub mod strictness;
 pub mod unbound;
+pub mod unused;
 pub mod subst;
\ No newline at end of file
@@ -0,0 +1,106 @@
+//! Infers the strictness of arguments that are numeric accumulators.
+//! An argument is an accumulator if it's bound to a variable in a rule
+//! and the same function is called recursively with a binary operation
+//! over this variable in the same position, e.g.
+//!
+//! ```text
+//! Sum (List.cons x xs) acc = Sum xs (+ acc x)
+//! ```
+//!
+//! If these arguments are lazy, each recursive call builds a bigger
+//! thunk that is only reduced at the end, so we evaluate them eagerly.
+//!
+//! Evaluating an argument that would never be used can change the
+//! termination of the program, so an accumulator is only strict when
+//! every rule already forces it, either by matching it or by using it
+//! in a position that is always evaluated, e.g. `Sum List.nil acc = acc`.
+
+use kind_tree::untyped::{self, Expr, ExprKind};
+
+fn mentions(expr: &Expr, var: &str) -> bool {
+    use ExprKind::*;
+    match &expr.data {
+        Var { name } => name.to_str() == var,
+        Lambda { param, body, .. } => param.to_str() != var && mentions(body, var),
+        App { fun, args } => mentions(fun, var) || args.iter().any(|arg| mentions(arg, var)),
+        Fun { args, .. } | Ctr { args, .. } => args.iter().any(|arg| mentions(arg, var)),
+        Let { name, val, next } => {
+            mentions(val, var) || (name.to_str() != var && mentions(next, var))
+        }
+        Binary { left, right, .. } => mentions(left, var) || mentions(right, var),
+        U60 { .. } | F60 { .. } | Str { .. } | Err => false,
+    }
+}
+
+/// Checks if `expr` calls the function `fun` with a binary
+/// operation that uses `var` as the argument at `idx`.
+fn accumulates(expr: &Expr, fun: &str, idx: usize, var: &str) -> bool {
+    use ExprKind::*;
+    match &expr.data {
+        Fun { name, args } if name.to_str() == fun => {
+            let accumulated = args
+                .get(idx)
+                .map(|arg| matches!(arg.data, Binary { .. }) && mentions(arg, var))
+                .unwrap_or(false);
+            accumulated || args.iter().any(|arg| accumulates(arg, fun, idx, var))
+        }
+        Fun { args, .. } | Ctr { args, .. } => {
+            args.iter().any(|arg| accumulates(arg, fun, idx, var))
+        }
+        Lambda { param, body, .. } => param.to_str() != var && accumulates(body, fun, idx, var),
+        App { fun: head, args } => {
+            accumulates(head, fun, idx, var) || args.iter().any(|arg| accumulates(arg, fun, idx, var))
+        }
+        Let { name, val, next } => {
+            accumulates(val, fun, idx, var)
+                || (name.to_str() != var && accumulates(next, fun, idx, var))
+        }
+        Binary { left, right, .. } => {
+            accumulates(left, fun, idx, var) || accumulates(right, fun, idx, var)
+        }
+        Var { .. } | U60 { .. } | F60 { .. } | Str { .. } | Err => false,
+    }
+}
+
+/// Checks if reducing `expr` always evaluates the variable `var`,
+/// assuming that the argument `idx` of the function `fun` is strict.
+fn forces(expr: &Expr, fun: &str, idx: usize, var: &str) -> bool {
+    use ExprKind::*;
+    match &expr.data {
+        Var { name } => name.to_str() == var,
+        Binary { left, right, .. } => forces(left, fun, idx, var) || forces(right, fun, idx, var),
+        Let { name, next, .. } => name.to_str() != var && forces(next, fun, idx, var),
+        Fun { name, args } if name.to_str() == fun => {
+            args.get(idx).is_some_and(|arg| forces(arg, fun, idx, var))
+        }
+        _ => false,
+    }
+}
+
+fn is_accumulator(entry: &untyped::Entry, idx: usize) -> bool {
+    let fun = entry.name.to_str();
+
+    let accumulates_somewhere = entry.rules.iter().any(|rule| match rule.pats.get(idx).map(|pat| &pat.data) {
+        Some(ExprKind::Var { name }) => accumulates(&rule.body, fun, idx, name.to_str()),
+        _ => false,
+    });
+
+    let forced_everywhere = entry.rules.iter().all(|rule| match rule.pats.get(idx).map(|pat| &pat.data) {
+        Some(ExprKind::Var { name }) => forces(&rule.body, fun, idx, name.to_str()),
+        // Any other pattern is evaluated to be matched.
+        Some(_) => true,
+        None => false,
+    });
+
+    accumulates_somewhere && forced_everywhere
+}
+
+pub fn infer_strictness(book: &mut untyped::Book) {
+    for (_, entry) in book.entrs.iter_mut() {
+        for idx in 0..entry.args.len() {
+            if !entry.args[idx].2 && is_accumulator(entry, idx) {
+                entry.args[idx].2 = true;
+            }
+        }
+    }
+}
@@ -38,6 +38,15 @@ pub struct UnboundCollector {
 
     pub unbound: FxHashMap<String, Vec<Ident>>,
     pub emit_errs: bool,
+
+    // Top level definitions that use a name that is not bound.
+    pub with_unbound: FxHashSet<String>,
+
+    // Keep track of the variables and definitions that are used, so the
+    // unused ones can be reported by the [unused][crate::unused] lints.
+    pub used_vars: FxHashSet<Range>,
+    pub current_top_level: String,
+    pub references: FxHashMap<String, FxHashSet<String>>,
 }
 
 impl UnboundCollector {
@@ -54,6 +63,10 @@ impl UnboundCollector {
             record_defs: Default::default(),
             type_defs: Default::default(),
             emit_errs,
+            with_unbound: Default::default(),
+            used_vars: Default::default(),
+            current_top_level: Default::default(),
+            references: Default::default(),
         }
     }
 }
@@ -88,13 +101,10 @@ pub fn get_book_unbound(
     diagnostic_sender: Sender<Box<dyn Diagnostic>>,
     book: &mut Book,
     emit_errs: bool,
-) -> (
-    FxHashMap<String, Vec<Ident>>,
-    FxHashMap<String, FxHashSet<QualifiedIdent>>,
-) {
+) -> UnboundCollector {
     let mut state = UnboundCollector::new(diagnostic_sender, emit_errs);
     state.visit_book(book);
-    (state.unbound, state.unbound_top_level)
+    state
 }
 
 impl UnboundCollector {
@@ -159,20 +169,30 @@ impl Visitor for UnboundCollector {
 
     fn visit_ident(&mut self, ident: &mut Ident) {
         let name = ident.to_str();
-        if self.context_vars.iter().all(|x| x.1 != name) {
+        if let Some(var) = self.context_vars.iter().rev().find(|x| x.1 == name) {
+            self.used_vars.insert(var.0);
+        } else {
             let entry = self
                 .unbound
                 .entry(name.to_string())
                 .or_insert_with(Vec::new);
             entry.push(ident.clone());
+            self.with_unbound.insert(self.current_top_level.clone());
         }
     }
 
     fn visit_qualified_ident(&mut self, ident: &mut QualifiedIdent) {
         debug_assert!(ident.get_aux().is_none());
+        self.references
+            .entry(self.current_top_level.clone())
+            .or_default()
+            .insert(ident.to_string());
         if !self.top_level_defs.contains_key(&ident.get_root()) {
             let entry = self.unbound_top_level.entry(ident.get_root()).or_default();
             entry.insert(ident.clone());
+            if !ident.generated {
+                self.with_unbound.insert(self.current_top_level.clone());
+            }
         }
     }
 
@@ -247,6 +267,12 @@ impl Visitor for UnboundCollector {
     }
 
     fn visit_top_level(&mut self, toplevel: &mut TopLevel) {
+        self.current_top_level = match toplevel {
+            TopLevel::SumType(sum) => sum.name.to_string(),
+            TopLevel::RecordType(rec) => rec.name.to_string(),
+            TopLevel::Entry(entry) => entry.name.to_string(),
+        };
+
         match toplevel {
             TopLevel::SumType(entr) => {
                 let mut repeated_names = FxHashMap::<String, Range>::default();
@@ -612,6 +638,7 @@ impl Visitor for UnboundCollector {
                 next,
             } => {
                 self.visit_qualified_ident(type_name);
+                self.visit_ident(var_name);
 
                 if let Some(motive) = motive {
                     self.visit_expr(motive)
@@ -0,0 +1,55 @@
+//! Gathers the [Usage] that the unused lints of `kind_lint` need from
+//! the [UnboundCollector] that resolved the names of the book.
+
+use fxhash::FxHashSet;
+use kind_lint::Usage;
+use kind_span::Range;
+use kind_tree::concrete::{Book, TopLevel};
+
+use crate::unbound::UnboundCollector;
+
+/// If the name is the definition itself or one of its helpers
+/// like constructors and derived functions.
+fn is_part_of(name: &str, def: &str) -> bool {
+    name.strip_prefix(def)
+        .map(|rest| rest.is_empty() || rest.starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// The names referenced by each definition, without the references to
+/// itself. The types and the definitions that a type derives can also
+/// refer to the constructors and the other helpers of the type.
+fn external_references(book: &Book, unbound: &UnboundCollector) -> FxHashSet<String> {
+    let mut references = FxHashSet::default();
+
+    for (referrer, names) in &unbound.references {
+        let (owner, owns_helpers) = match book.entries.get(referrer) {
+            Some(TopLevel::Entry(entry)) => match &entry.generated_by {
+                Some(typ) => (typ.as_str(), true),
+                None => (referrer.as_str(), false),
+            },
+            Some(_) => (referrer.as_str(), true),
+            None => (referrer.as_str(), false),
+        };
+
+        for name in names {
+            let is_owner = if owns_helpers { is_part_of(name, owner) } else { name == owner };
+            if !is_owner {
+                references.insert(name.clone());
+            }
+        }
+    }
+
+    references
+}
+
+/// The uses that `unbound` found in the book. `unused_aliases` are the
+/// aliases of the file that [expand_uses][crate::expand::uses::expand_uses]
+/// found that are never used.
+pub fn book_usage(book: &Book, unbound: &UnboundCollector, unused_aliases: Vec<(String, Range)>) -> Usage {
+    Usage {
+        used_vars: unbound.used_vars.clone(),
+        references: external_references(book, unbound),
+        unused_aliases,
+    }
+}
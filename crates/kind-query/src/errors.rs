@@ -3,6 +3,7 @@
 
 use std::path::PathBuf;
 
+use kind_report::codes;
 use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity, Subtitle, Word};
 use kind_tree::symbol::{Ident, QualifiedIdent};
 
@@ -26,7 +27,7 @@ impl Diagnostic for DriverError {
     fn to_diagnostic_frame(&self) -> DiagnosticFrame {
         match self {
             DriverError::UnboundVariable(idents, suggestions) => DiagnosticFrame {
-                code: 100,
+                code: codes::UNBOUND_VARIABLE,
                 severity: Severity::Error,
                 title: format!("Cannot find the definition '{}'.", idents[0].to_str()),
                 subtitles: vec![],
@@ -38,6 +39,7 @@ impl Diagnostic for DriverError {
                 } else {
                     "Take a look at the rules for name searching at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()
                 }],
+                suggestions: vec![],
                 positions: idents
                     .iter()
                     .map(|ident| Marker {
@@ -50,7 +52,7 @@ impl Diagnostic for DriverError {
                     .collect(),
             },
             DriverError::MultiplePaths(ident, paths) => DiagnosticFrame {
-                code: 101,
+                code: codes::MULTIPLE_PATHS,
                 severity: Severity::Error,
                 title: "Multiple definitions for the same name".to_string(),
                 subtitles: paths
@@ -58,6 +60,7 @@ impl Diagnostic for DriverError {
                     .map(|path| Subtitle::Phrase(Color::Fst, vec![Word::White(path.display().to_string())]))
                     .collect(),
                 hints: vec!["Take a look at the rules for name searching at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: ident.range,
                     color: Color::Fst,
@@ -67,11 +70,12 @@ impl Diagnostic for DriverError {
                 }],
             },
             DriverError::DefinedMultipleTimes(fst, snd) => DiagnosticFrame {
-                code: 102,
+                code: codes::DEFINED_MULTIPLE_TIMES,
                 severity: Severity::Error,
                 title: "Defined multiple times for the same name".to_string(),
                 subtitles: vec![],
                 hints: vec!["Rename one of the definitions or remove and look at how names work in Kind at https://github.com/Kindelia/Kind2/blob/master/guide/naming.md".to_string()],
+                suggestions: vec![],
                 positions: vec![
                     Marker {
                         position: fst.range,
@@ -0,0 +1,125 @@
+//! Registry of every diagnostic code. The diagnostics and their
+//! [explanations][crate::explain] refer to the codes by these names,
+//! so a code cannot be changed in one place and not in the other.
+
+macro_rules! codes {
+    ($($name:ident = $code:literal,)*) => {
+        $(pub const $name: u32 = $code;)*
+
+        /// Every code that a diagnostic can have.
+        pub const ALL: &[u32] = &[$($name),*];
+    };
+}
+
+codes! {
+    // Syntax
+    UNFINISHED_STRING = 1,
+    IGNORE_REST_SHOULD_BE_ON_THE_END = 2,
+    UNUSED_DOC_STRING = 3,
+    UNFINISHED_CHAR = 4,
+    LOWER_CASED_DEFINITION = 5,
+    NOT_A_CLAUSE_OF_DEF = 6,
+    UNFINISHED_COMMENT = 7,
+    INVALID_ESCAPE_SEQUENCE = 8,
+    INVALID_NUMBER_REPRESENTATION = 9,
+    UNEXPECTED_CHAR = 10,
+    UNEXPECTED_EOF = 11,
+    UNEXPECTED_COMMENT = 12,
+    UNEXPECTED_TOKEN = 13,
+    UNCLOSED = 14,
+    CANNOT_USE_USE = 15,
+    IMPORTS_CANNOT_HAVE_ALIAS = 16,
+    INVALID_NUMBER_TYPE = 17,
+    MATCH_SCRUTINEE_SHOULD_BE_A_NAME = 18,
+
+    // Driver
+    UNBOUND_VARIABLE = 100,
+    MULTIPLE_PATHS = 101,
+    DEFINED_MULTIPLE_TIMES = 102,
+    REWRITE_LIMIT_EXCEEDED = 104,
+    HEAP_LIMIT_EXCEEDED = 105,
+    INVALID_SIGNATURES = 106,
+    CANNOT_FIND_FILE = 107,
+    THERE_ISNT_A_MAIN = 108,
+    NOT_A_DERIVATION = 109,
+    DERIVATION_DID_NOT_RETURN_STRING = 110,
+    TOO_MANY_DERIVATION_ROUNDS = 111,
+
+    // Passes
+    CANNOT_USE_IRRELEVANT = 200,
+    RULES_WITH_INCONSISTENT_ARITY = 201,
+    SHOULD_BE_A_PARAMETER = 202,
+    RULE_WITH_INCORRECT_ARITY = 203,
+    NEED_TO_IMPLEMENT_METHODS = 204,
+    LET_DESTRUCT_ONLY_FOR_RECORD = 205,
+    LET_DESTRUCT_ONLY_FOR_SUM = 206,
+    CANNOT_FIND_FIELD = 207,
+    CANNOT_FIND_CONSTRUCTOR = 208,
+    NO_COVERAGE = 209,
+    INCORRECT_ARITY = 210,
+    SUGAR_IS_BADLY_IMPLEMENTED = 211,
+    DUPLICATED_NAMED = 212,
+    CANNOT_ACCESS_TYPE = 213,
+    REPEATED_VARIABLE = 214,
+    CONTRACT_SHOULD_NOT_HAVE_RULES = 215,
+    CONTRACT_SHOULD_RECEIVE_THE_ACTION = 216,
+    CONTRACT_ACTIONS_IS_NOT_A_SUM_TYPE = 217,
+    CONTRACT_MISSING_HANDLER = 218,
+    CONTRACT_STATE_IS_NOT_A_RECORD = 219,
+    INVALID_STRICT_ARGUMENT = 220,
+    NO_FIELD_COVERAGE = 221,
+    ATTRIBUTE_DOES_NOT_EXPECT_EQUAL = 222,
+    ATTRIBUTE_DOES_NOT_EXPECT_ARGS = 223,
+    INVALID_ATTRIBUTE_ARGUMENT = 224,
+    CANNOT_DERIVE = 225,
+    ATTRIBUTE_EXPECTS_A_VALUE = 226,
+    ATTRIBUTE_DOES_NOT_EXIST = 227,
+    NEEDS_A_FIELD = 228,
+    CANNOT_FIND_THE_FIELD = 229,
+    CANNOT_FIND_ALIAS = 230,
+    NOT_A_TYPE_CONSTRUCTOR = 231,
+    DUPLICATED_ATTRIBUTE_ARGUMENT = 232,
+    UNUSED_VARIABLE = 233,
+    UNUSED_DEFINITION = 234,
+    UNUSED_ALIAS = 235,
+    UNKNOWN_LINT = 236,
+    FIELD_NOT_IN_EVERY_CONSTRUCTOR = 237,
+    PATH_LEAVES_RECORD = 238,
+    CONTRACT_STATE_TYPE_MISMATCH = 239,
+
+    // Type checker
+    TYPE_MISMATCH = 300,
+    INSPECTION = 301,
+    IMPOSSIBLE_CASE = 302,
+    CANT_INFER_HOLE = 303,
+    INVALID_CALL = 304,
+    UNCOVERED_PATTERN = 305,
+    CANT_INFER_LAMBDA = 306,
+    TOO_MANY_ARGUMENTS = 307,
+    UNBOUND_VARIABLE_IN_CHECKER = 308,
+
+    // Derivations
+    CANNOT_USE_NAMED_VARIABLE = 400,
+    CANNOT_USE_ALL = 401,
+    INVALID_RETURN_TYPE = 402,
+    UNSUPPORTED_FIELD = 403,
+    NO_TYPE_PARAMETER = 404,
+    UNSUPPORTED_OCCURRENCE = 405,
+    INDEXED_TYPE = 406,
+    CONSTRUCTOR_WITH_FIELDS = 407,
+
+    // Kindelia
+    INVALID_VAR_NAME = 600,
+    SHOULD_NOT_HAVE_ARGUMENTS = 601,
+    SHOULD_HAVE_ONLY_ONE_RULE = 603,
+    NO_INIT_ENTRY = 604,
+    FLOAT_USED = 605,
+    MISSING_SIGNATURE = 606,
+    CANNOT_FORCE_ARGUMENT = 607,
+
+    // Lints
+    REDUNDANT_MOTIVE = 700,
+    SHADOWED_BINDING = 701,
+    NOT_SNAKE_CASE = 702,
+    CONSTANT_CONDITION = 703,
+}
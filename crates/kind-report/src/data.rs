@@ -1,7 +1,10 @@
 use std::time::Duration;
 
+use fxhash::FxHashSet;
 use kind_span::{Range, SyntaxCtxIndex};
 
+use crate::report::FileCache;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Severity {
     Error,
@@ -43,6 +46,15 @@ pub struct Marker {
     pub main: bool,
 }
 
+/// A change in the source code that fixes a diagnostic without
+/// the need of any human intervention. It replaces the text inside
+/// of the `position` with the `replacement`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub position: Range,
+    pub replacement: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct DiagnosticFrame {
     pub code: u32,
@@ -50,17 +62,74 @@ pub struct DiagnosticFrame {
     pub title: String,
     pub subtitles: Vec<Subtitle>,
     pub hints: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
     pub positions: Vec<Marker>,
 }
+
+impl DiagnosticFrame {
+    /// The files that the markers point to. The file of the main marker
+    /// comes first and the others follow in the order that they appear.
+    pub fn syntax_ctxs(&self) -> Vec<SyntaxCtxIndex> {
+        let main = self.positions.iter().filter(|marker| marker.main);
+        let rest = self.positions.iter().filter(|marker| !marker.main);
+
+        let mut ctxs = Vec::new();
+        for marker in main.chain(rest) {
+            if !ctxs.contains(&marker.position.ctx) {
+                ctxs.push(marker.position.ctx)
+            }
+        }
+        ctxs
+    }
+}
+
 pub enum Log {
     Checking(String),
     Checked(Duration),
     Compiled(Duration),
     Rewrites(u64),
     Failed(Duration),
+    Summary { errors: usize, warnings: usize },
 }
 pub trait Diagnostic {
     fn get_syntax_ctx(&self) -> Option<SyntaxCtxIndex>;
     fn get_severity(&self) -> Severity;
     fn to_diagnostic_frame(&self) -> DiagnosticFrame;
 }
+
+/// Removes the diagnostics that were reported more than once (the ones
+/// with the same code and main position) and sorts the rest by file
+/// path, read from the `cache`, and position, so the order does not
+/// depend on the order of the passes or of the loaded files. The
+/// diagnostics without any position come first.
+pub fn collect_diagnostics<I>(diagnostics: I, cache: &dyn FileCache) -> Vec<Box<dyn Diagnostic>>
+where
+    I: IntoIterator<Item = Box<dyn Diagnostic>>,
+{
+    let mut seen = FxHashSet::default();
+    let mut collected = Vec::new();
+
+    for diagnostic in diagnostics {
+        let frame = diagnostic.to_diagnostic_frame();
+
+        let main = frame
+            .positions
+            .iter()
+            .find(|marker| marker.main)
+            .or_else(|| frame.positions.first())
+            .map(|marker| marker.position);
+
+        let title = if main.is_none() { frame.title } else { String::new() };
+
+        if seen.insert((frame.code, main, title)) {
+            let position = main.map(|range| {
+                let path = cache.fetch(range.ctx).map(|(path, _)| path);
+                (path, range.start, range.end)
+            });
+            collected.push((position, frame.code, diagnostic));
+        }
+    }
+
+    collected.sort_by(|(position, code, _), (other, other_code, _)| (position, code).cmp(&(other, other_code)));
+    collected.into_iter().map(|(_, _, diagnostic)| diagnostic).collect()
+}
@@ -0,0 +1,2157 @@
+//! Long explanations of each one of the diagnostic codes. They're
+//! shown by `kind2 explain` and each one of them comes with an
+//! example of a source that triggers the diagnostic and the same
+//! source after fixing it.
+
+use std::fmt::Display;
+
+use crate::codes;
+
+pub struct Explanation {
+    pub code: u32,
+    pub title: &'static str,
+    pub text: &'static str,
+    /// A Kind source that triggers the diagnostic. Some diagnostics
+    /// depend on the files around or on the runtime, so they do not
+    /// have any example.
+    pub bad: Option<&'static str>,
+    pub fixed: Option<&'static str>,
+}
+
+/// Renders a code like `E0209`.
+pub fn code_name(code: u32) -> String {
+    format!("E{:04}", code)
+}
+
+/// Parses codes like `E0209`, `e209` or `209`.
+pub fn parse_code(code: &str) -> Option<u32> {
+    let code = code.strip_prefix(['E', 'e']).unwrap_or(code);
+    code.parse().ok()
+}
+
+pub fn explain(code: u32) -> Option<&'static Explanation> {
+    EXPLANATIONS.iter().find(|explanation| explanation.code == code)
+}
+
+fn write_example(f: &mut std::fmt::Formatter<'_>, code: &str) -> std::fmt::Result {
+    for line in code.lines() {
+        if line.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, "    {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: {}", code_name(self.code), self.title)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.text)?;
+
+        if let Some(bad) = self.bad {
+            writeln!(f)?;
+            writeln!(f, "Erroneous code example:")?;
+            writeln!(f)?;
+            write_example(f, bad)?;
+        }
+
+        if let Some(fixed) = self.fixed {
+            writeln!(f)?;
+            writeln!(f, "Fixed:")?;
+            writeln!(f)?;
+            write_example(f, fixed)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub const EXPLANATIONS: &[Explanation] = &[
+    // Syntax
+    Explanation {
+        code: codes::UNFINISHED_STRING,
+        title: "Unfinished string",
+        text: "A string literal was opened with `\"` but the file ended before \
+               the closing `\"`. Strings can span multiple lines, so the error \
+               usually points to the last string of the file even if the \
+               missing quote is in a previous line.",
+        bad: Some(r#"type String {
+  cons (head: U60) (tail: String)
+  nil
+}
+
+Greeting : String
+Greeting = "Hello"#),
+        fixed: Some(r#"type String {
+  cons (head: U60) (tail: String)
+  nil
+}
+
+Greeting : String
+Greeting = "Hello""#),
+    },
+    Explanation {
+        code: codes::IGNORE_REST_SHOULD_BE_ON_THE_END,
+        title: "Invalid position of the '..' operator",
+        text: "When destructuring a constructor with `let` or `open`, the `..` \
+               operator ignores all of the fields that were not bound. It only \
+               makes sense at the end of the bindings.",
+        bad: Some(r#"#derive[match]
+record User {
+  name : U60
+  age : U60
+}
+
+Age (user: User) : U60
+Age user =
+  let User.new .. age = user
+  age"#),
+        fixed: Some(r#"#derive[match]
+record User {
+  name : U60
+  age : U60
+}
+
+Age (user: User) : U60
+Age user =
+  let User.new age .. = user
+  age"#),
+    },
+    Explanation {
+        code: codes::UNUSED_DOC_STRING,
+        title: "Unused documentation comment",
+        text: "Documentation comments (`//!`) document the definition that comes \
+               right after them. Inside of an expression there's nothing to \
+               document, so the comment is ignored. Use a normal comment (`//`) \
+               or move it to the top of the definition.",
+        bad: Some(r#"Answer : U60
+Answer =
+  //! The answer to everything
+  42"#),
+        fixed: Some(r#"//! The answer to everything
+Answer : U60
+Answer = 42"#),
+    },
+    Explanation {
+        code: codes::UNFINISHED_CHAR,
+        title: "Unfinished char",
+        text: "A char literal must contain exactly one character (or one escape \
+               sequence) between single quotes.",
+        bad: Some(r#"Letter : U60
+Letter = 'ab'"#),
+        fixed: Some(r#"Letter : U60
+Letter = 'a'"#),
+    },
+    Explanation {
+        code: codes::LOWER_CASED_DEFINITION,
+        title: "Lower cased definition",
+        text: "The names of top level definitions must start with an upper case \
+               letter. Lower cased names are reserved for variables.",
+        bad: Some(r#"double (n: U60) : U60
+double n = (* n 2)"#),
+        fixed: Some(r#"Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::NOT_A_CLAUSE_OF_DEF,
+        title: "Rule with a different name from the definition",
+        text: "The rules of a definition must start with the name of the \
+               definition. This error usually happens when a definition is \
+               renamed but one of its rules is not, or because of a typo.",
+        bad: Some(r#"Double (n: U60) : U60
+Doble n = (* n 2)"#),
+        fixed: Some(r#"Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::UNFINISHED_COMMENT,
+        title: "Unfinished comment",
+        text: "A multi line comment was opened with `/*` but it's never closed \
+               with `*/`. Multi line comments can be nested, so each `/*` needs \
+               its own `*/`.",
+        bad: Some(r#"/* Doubles a number
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"/* Doubles a number */
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::INVALID_ESCAPE_SEQUENCE,
+        title: "Invalid escape sequence",
+        text: "The `\\x` escape sequence expects exactly two hexadecimal digits \
+               and `\\u` expects exactly four of them, and they must form a \
+               valid unicode character.",
+        bad: Some(r#"Letter : U60
+Letter = '\xZZ'"#),
+        fixed: Some(r#"Letter : U60
+Letter = '\x41'"#),
+    },
+    Explanation {
+        code: codes::INVALID_NUMBER_REPRESENTATION,
+        title: "Invalid number representation",
+        text: "The digits of a number literal must be valid in its base. Binary \
+               numbers (`0b`) only accept `0` and `1`, octal numbers (`0o`) \
+               only accept digits from `0` to `7` and hexadecimal numbers (`0x`) \
+               accept digits and letters from `a` to `f`. The number must also \
+               fit in its type.",
+        bad: Some(r#"Big : U60
+Big = 99999999999999999999999"#),
+        fixed: Some(r#"Big : U60
+Big = 999999999999999999"#),
+    },
+    Explanation {
+        code: codes::UNEXPECTED_CHAR,
+        title: "Invalid character",
+        text: "The file contains a character that is not part of the syntax of \
+               Kind outside of strings, chars and comments.",
+        bad: Some(r#"Price : U60
+Price = 10€"#),
+        fixed: Some(r#"// Price in €
+Price : U60
+Price = 10"#),
+    },
+    Explanation {
+        code: codes::UNEXPECTED_EOF,
+        title: "Unexpected end of file",
+        text: "The file ended in the middle of a definition, so the parser was \
+               still waiting for something like the body of a rule or a closing \
+               parenthesis.",
+        bad: Some(r#"Answer : U60
+Answer ="#),
+        fixed: Some(r#"Answer : U60
+Answer = 42"#),
+    },
+    Explanation {
+        code: codes::UNEXPECTED_COMMENT,
+        title: "Unexpected documentation comment",
+        text: "Documentation comments (`//!`) can only appear before top level \
+               definitions and before expressions. Use a normal comment (`//`) \
+               to document the arguments of a definition.",
+        bad: Some(r#"Add (a: U60) //! The first number
+    (b: U60) : U60
+Add a b = (+ a b)"#),
+        fixed: Some(r#"Add (a: U60) // The first number
+    (b: U60) : U60
+Add a b = (+ a b)"#),
+    },
+    Explanation {
+        code: codes::UNEXPECTED_TOKEN,
+        title: "Unexpected token",
+        text: "The parser found something that cannot appear in this position. \
+               It's usually a missing or an extra parenthesis, or an operator \
+               that was written in infix form. Binary operators in Kind use the \
+               prefix form like `(+ a b)`.",
+        bad: Some(r#"Add (a: U60) (b: U60) : U60
+Add a b = (a + b))"#),
+        fixed: Some(r#"Add (a: U60) (b: U60) : U60
+Add a b = (+ a b)"#),
+    },
+    Explanation {
+        code: codes::UNCLOSED,
+        title: "Unclosed parenthesis",
+        text: "A parenthesis or a bracket was opened and the file ended before \
+               it was closed.",
+        bad: Some(r#"Answer : U60
+Answer = (+ 40 (* 2 1)"#),
+        fixed: Some(r#"Answer : U60
+Answer = (+ 40 (* 2 1))"#),
+    },
+    Explanation {
+        code: codes::CANNOT_USE_USE,
+        title: "'use' in the middle of the file",
+        text: "The `use` statements create aliases for the whole file, so they \
+               must come before every definition.",
+        bad: Some(r#"type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+use Nat as N
+
+One : Nat
+One = N/succ N/zero"#),
+        fixed: Some(r#"use Nat as N
+
+type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+One : Nat
+One = N/succ N/zero"#),
+    },
+    Explanation {
+        code: codes::IMPORTS_CANNOT_HAVE_ALIAS,
+        title: "Alias of a name with '/'",
+        text: "A `use` statement creates an alias to a namespace, e.g. `use Nat \
+               as N` allows to write `N/succ` instead of `Nat.succ`. Both the \
+               namespace and the alias must be plain names, without `/`.",
+        bad: Some(r#"use Nat/succ as S
+
+type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+One : Nat
+One = Nat.succ Nat.zero"#),
+        fixed: Some(r#"use Nat as N
+
+type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+One : Nat
+One = N/succ N/zero"#),
+    },
+    Explanation {
+        code: codes::INVALID_NUMBER_TYPE,
+        title: "Invalid number type",
+        text: "Number literals can have a suffix with their type, but only `u60` \
+               and `u120` are supported.",
+        bad: Some(r#"Big : U60
+Big = 10u64"#),
+        fixed: Some(r#"Big : U60
+Big = 10u60"#),
+    },
+    Explanation {
+        code: codes::MATCH_SCRUTINEE_SHOULD_BE_A_NAME,
+        title: "Match scrutinee is not a name",
+        text: "The `match` expression binds the fields of the matched value as \
+               `name.field`, so it needs a name. To match on an arbitrary \
+               expression, give it a name with `match Type name = expr { .. }`.",
+        bad: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool (Bool.true) {
+    true  => 1
+    false => 0
+  }"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool x = Bool.true {
+    true  => 1
+    false => 0
+  }"#),
+    },
+    // Resolution
+    Explanation {
+        code: codes::UNBOUND_VARIABLE,
+        title: "Cannot find the definition",
+        text: "The name does not refer to any definition of the file, of the \
+               files that it imports or of the files that follow the naming \
+               conventions. A definition named `Data.List.map` is searched in \
+               `Data/List/map.kind2`, `Data/List/map/_.kind2`, \
+               `Data/List.kind2` and so on. The compiler suggests similar names \
+               when there are any, and `kind2 fix` applies the suggestion.",
+        bad: Some(r#"Main : U60
+Main = Dobule 2
+
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"Main : U60
+Main = Double 2
+
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::MULTIPLE_PATHS,
+        title: "Ambiguous definition location",
+        text: "A definition can be in `Name.kind2` or in `Name/_.kind2`. When \
+               both files exist, the compiler cannot choose one of them. \
+               Remove or rename one of the files.",
+        bad: Some(r#"Main : U60
+Main = Double 21
+
+// File: Double.kind2
+Double (n: U60) : U60
+Double n = (* n 2)
+
+// File: Double/_.kind2
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"Main : U60
+Main = Double 21
+
+// File: Double.kind2
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::DEFINED_MULTIPLE_TIMES,
+        title: "Defined multiple times",
+        text: "Two definitions have the same name. Constructors of types and \
+               fields of records also define names, e.g. `type Nat { zero }` \
+               defines `Nat.zero`, so they cannot be defined again.",
+        bad: Some(r#"type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+Nat.zero : U60
+Nat.zero = 0"#),
+        fixed: Some(r#"type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+Nat.to_u60.zero : U60
+Nat.to_u60.zero = 0"#),
+    },
+    Explanation {
+        code: codes::REWRITE_LIMIT_EXCEEDED,
+        title: "Rewrite limit exceeded",
+        text: "The evaluation of `kind2 run` or `kind2 eval` performed more \
+               rewrites than the limit given by `--max-rewrites`. The program \
+               may not terminate, or it may need a bigger limit.",
+        bad: Some(r#"Loop (n: U60) : U60
+Loop n = Loop (+ n 1)
+
+Main : U60
+Main = Loop 0"#),
+        fixed: Some(r#"Loop (n: U60) : U60
+Loop 0 = 0
+Loop n = Loop (- n 1)
+
+Main : U60
+Main = Loop 10"#),
+    },
+    Explanation {
+        code: codes::HEAP_LIMIT_EXCEEDED,
+        title: "Heap limit exceeded",
+        text: "The evaluation of `kind2 run` or `kind2 eval` needed more memory \
+               than the heap given by `--heap-size`. The memory that is freed \
+               is only reused after the whole heap was used, so the limit \
+               counts every node that the evaluation allocates. The program \
+               may build a value that grows forever, or it may need a bigger \
+               heap.",
+        bad: Some(r#"type List (t: Type) {
+  nil
+  cons (head: t) (tail: List t)
+}
+
+Range (n: U60) : List U60
+Range 0 = List.nil
+Range n = List.cons n (Range (- n 1))
+
+Main : List U60
+Main = Range 100000"#),
+        fixed: Some(r#"type List (t: Type) {
+  nil
+  cons (head: t) (tail: List t)
+}
+
+Range (n: U60) : List U60
+Range 0 = List.nil
+Range n = List.cons n (Range (- n 1))
+
+Main : List U60
+Main = Range 10"#),
+    },
+    Explanation {
+        code: codes::INVALID_SIGNATURES,
+        title: "Invalid signatures file",
+        text: "The file given by `--signatures` must contain one entry per \
+               line, with the name of a definition marked with `#kdl_sign` \
+               followed by the hexadecimal of its 65 bytes signature. Empty \
+               lines and lines starting with `//` are ignored. The compiler \
+               does not sign statements, so the signatures have to be made \
+               with the Kindelia client.",
+        bad: Some(r#"#kdl_sign
+Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21
+
+// File: Main.signatures
+Double 1b00"#),
+        fixed: Some(r#"#kdl_sign
+Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21
+
+// File: Main.signatures
+Double 1b00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"#),
+    },
+    Explanation {
+        code: codes::CANNOT_FIND_FILE,
+        title: "Cannot find file",
+        text: "A file given in the command line, like the file to compile or \
+               the one of `--signatures`, does not exist or cannot be read.",
+        bad: Some(r#"#kdl_sign
+Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21"#),
+        fixed: Some(r#"#kdl_sign
+Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21
+
+// File: Main.signatures
+Double 1b00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"#),
+    },
+    Explanation {
+        code: codes::THERE_ISNT_A_MAIN,
+        title: "Cannot find the 'Main' function",
+        text: "`kind2 run` and `kind2 eval` evaluate the `Main` definition of \
+               the file, so it needs to exist.",
+        bad: Some(r#"Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21"#),
+    },
+    Explanation {
+        code: codes::NOT_A_DERIVATION,
+        title: "Cannot find the derivation",
+        text: "The names in `#derive` that start with an upper case letter \
+               are derivations written in Kind. They are definitions marked \
+               with `#derivation` that receive the description of the type \
+               as a `Kind.SumType` and return the code of the derived \
+               definitions as a `String`. The library of the derivation \
+               defines `Kind.SumType`, `Kind.Constructor` and `Kind.Argument` \
+               like in the fixed example, and the types inside of them are \
+               written in the syntax of Kind.",
+        bad: Some(r#"#derive[Derive.Zero]
+type Color {
+  red
+  green
+}"#),
+        fixed: Some(r#"type String { nil cons (head: U60) (tail: String) }
+type Maybe (t: Type) { some (val: t) none }
+type Bool { true false }
+type List (t: Type) { nil cons (head: t) (tail: List t) }
+
+record Kind.SumType {
+  name         : String
+  parameters   : List Kind.Argument
+  indices      : List Kind.Argument
+  constructors : List Kind.Constructor
+}
+
+record Kind.Constructor {
+  name : String
+  args : List Kind.Argument
+  typ  : Maybe String
+}
+
+record Kind.Argument {
+  name   : String
+  typ    : Maybe String
+  hidden : Bool
+  erased : Bool
+}
+
+String.concat (xs: String) (ys: String) : String
+String.concat String.nil ys = ys
+String.concat (String.cons x xs) ys = String.cons x (String.concat xs ys)
+
+#derivation
+Derive.Zero (decl: Kind.SumType) : String
+Derive.Zero (Kind.SumType.new name params indices conss) =
+  String.concat name (String.concat ".zero : U60\n" (String.concat name ".zero = 0\n"))
+
+#derive[Derive.Zero]
+type Color {
+  red
+  green
+}
+
+Main : U60
+Main = Color.zero"#),
+    },
+    Explanation {
+        code: codes::DERIVATION_DID_NOT_RETURN_STRING,
+        title: "Derivation that does not return a string",
+        text: "A derivation written in Kind is evaluated when the file is \
+               loaded, and the result has to be a `String` with the derived \
+               code. The evaluation got stuck before it built the whole \
+               string, usually because a function has no rule for one of its \
+               arguments.",
+        bad: Some(r#"type String { nil cons (head: U60) (tail: String) }
+type Maybe (t: Type) { some (val: t) none }
+type Bool { true false }
+type List (t: Type) { nil cons (head: t) (tail: List t) }
+
+record Kind.SumType {
+  name         : String
+  parameters   : List Kind.Argument
+  indices      : List Kind.Argument
+  constructors : List Kind.Constructor
+}
+
+record Kind.Constructor {
+  name : String
+  args : List Kind.Argument
+  typ  : Maybe String
+}
+
+record Kind.Argument {
+  name   : String
+  typ    : Maybe String
+  hidden : Bool
+  erased : Bool
+}
+
+String.concat (xs: String) (ys: String) : String
+String.concat (String.cons x xs) ys = String.cons x (String.concat xs ys)
+
+#derivation
+Derive.Zero (decl: Kind.SumType) : String
+Derive.Zero (Kind.SumType.new name params indices conss) =
+  String.concat name (String.concat ".zero : U60\n" (String.concat name ".zero = 0\n"))
+
+#derive[Derive.Zero]
+type Color {
+  red
+  green
+}
+
+Main : U60
+Main = Color.zero"#),
+        fixed: Some(r#"type String { nil cons (head: U60) (tail: String) }
+type Maybe (t: Type) { some (val: t) none }
+type Bool { true false }
+type List (t: Type) { nil cons (head: t) (tail: List t) }
+
+record Kind.SumType {
+  name         : String
+  parameters   : List Kind.Argument
+  indices      : List Kind.Argument
+  constructors : List Kind.Constructor
+}
+
+record Kind.Constructor {
+  name : String
+  args : List Kind.Argument
+  typ  : Maybe String
+}
+
+record Kind.Argument {
+  name   : String
+  typ    : Maybe String
+  hidden : Bool
+  erased : Bool
+}
+
+String.concat (xs: String) (ys: String) : String
+String.concat String.nil ys = ys
+String.concat (String.cons x xs) ys = String.cons x (String.concat xs ys)
+
+#derivation
+Derive.Zero (decl: Kind.SumType) : String
+Derive.Zero (Kind.SumType.new name params indices conss) =
+  String.concat name (String.concat ".zero : U60\n" (String.concat name ".zero = 0\n"))
+
+#derive[Derive.Zero]
+type Color {
+  red
+  green
+}
+
+Main : U60
+Main = Color.zero"#),
+    },
+    Explanation {
+        code: codes::TOO_MANY_DERIVATION_ROUNDS,
+        title: "Too many rounds of derivations",
+        text: "The types in the code returned by a derivation written in Kind \
+               are derived too, in a new round of derivations. A derivation \
+               that returns a type with the same `#derive` creates a new type \
+               in every round and never ends, so the derivations stop after \
+               16 rounds. Remove the `#derive` from the code that the \
+               derivation returns.",
+        bad: Some(r##"type String { nil cons (head: U60) (tail: String) }
+type Maybe (t: Type) { some (val: t) none }
+type Bool { true false }
+type List (t: Type) { nil cons (head: t) (tail: List t) }
+
+record Kind.SumType {
+  name         : String
+  parameters   : List Kind.Argument
+  indices      : List Kind.Argument
+  constructors : List Kind.Constructor
+}
+
+record Kind.Constructor {
+  name : String
+  args : List Kind.Argument
+  typ  : Maybe String
+}
+
+record Kind.Argument {
+  name   : String
+  typ    : Maybe String
+  hidden : Bool
+  erased : Bool
+}
+
+String.concat (xs: String) (ys: String) : String
+String.concat String.nil ys = ys
+String.concat (String.cons x xs) ys = String.cons x (String.concat xs ys)
+
+#derivation
+Derive.Next (decl: Kind.SumType) : String
+Derive.Next (Kind.SumType.new name params indices conss) =
+  String.concat "#derive[Derive.Next]\ntype " (String.concat name ".Next { new }\n")
+
+#derive[Derive.Next]
+type Color {
+  red
+  green
+}
+
+Main : U60
+Main = 0"##),
+        fixed: Some(r##"type String { nil cons (head: U60) (tail: String) }
+type Maybe (t: Type) { some (val: t) none }
+type Bool { true false }
+type List (t: Type) { nil cons (head: t) (tail: List t) }
+
+record Kind.SumType {
+  name         : String
+  parameters   : List Kind.Argument
+  indices      : List Kind.Argument
+  constructors : List Kind.Constructor
+}
+
+record Kind.Constructor {
+  name : String
+  args : List Kind.Argument
+  typ  : Maybe String
+}
+
+record Kind.Argument {
+  name   : String
+  typ    : Maybe String
+  hidden : Bool
+  erased : Bool
+}
+
+String.concat (xs: String) (ys: String) : String
+String.concat String.nil ys = ys
+String.concat (String.cons x xs) ys = String.cons x (String.concat xs ys)
+
+#derivation
+Derive.Next (decl: Kind.SumType) : String
+Derive.Next (Kind.SumType.new name params indices conss) =
+  String.concat "type " (String.concat name ".Next { new }\n")
+
+#derive[Derive.Next]
+type Color {
+  red
+  green
+}
+
+Main : Color.Next
+Main = Color.Next.new"##),
+    },
+    // Desugar and erasure
+    Explanation {
+        code: codes::CANNOT_USE_IRRELEVANT,
+        title: "Irrelevant value in a relevant position",
+        text: "Erased arguments, like the ones declared with `<x>` or `-(x)`, \
+               are removed before the program runs. They can only be used in \
+               types and in other erased positions. To use the value at \
+               runtime, declare the argument with `(x)` or `+<x>`.",
+        bad: Some(r#"Pick <a: U60> (b: U60) : U60
+Pick a b = a
+
+Main : U60
+Main = Pick 1 2"#),
+        fixed: Some(r#"Pick (a: U60) (b: U60) : U60
+Pick a b = a
+
+Main : U60
+Main = Pick 1 2"#),
+    },
+    Explanation {
+        code: codes::RULES_WITH_INCONSISTENT_ARITY,
+        title: "Rules with inconsistent arity",
+        text: "The rules of a definition can either match on all of the \
+               arguments or only on the ones that are not hidden, but all of \
+               the rules must use the same choice.",
+        bad: Some(r#"type List <t: Type> {
+  nil
+  cons (head: t) (tail: List t)
+}
+
+Length <t> (xs: List t) : U60
+Length t List.nil = 0
+Length (List.cons _ tail) = (+ 1 (Length tail))"#),
+        fixed: Some(r#"type List <t: Type> {
+  nil
+  cons (head: t) (tail: List t)
+}
+
+Length <t> (xs: List t) : U60
+Length List.nil = 0
+Length (List.cons _ tail) = (+ 1 (Length tail))"#),
+    },
+    Explanation {
+        code: codes::SHOULD_BE_A_PARAMETER,
+        title: "Expected a parameter of the type",
+        text: "The parameters of a type (the arguments before the `~`) are the \
+               same for all of the constructors. When a constructor declares \
+               its return type, it must use the parameters as they are. Only \
+               the indices (the arguments after the `~`) can change.",
+        bad: Some(r#"type Box <t: Type> {
+  new (value: t) : Box U60
+}"#),
+        fixed: Some(r#"type Box <t: Type> {
+  new (value: t) : Box t
+}"#),
+    },
+    Explanation {
+        code: codes::RULE_WITH_INCORRECT_ARITY,
+        title: "Rule with incorrect arity",
+        text: "Each rule must have one pattern for each argument of the \
+               definition. The patterns of hidden arguments can be omitted.",
+        bad: Some(r#"Add (a: U60) (b: U60) : U60
+Add a = (+ a 1)"#),
+        fixed: Some(r#"Add (a: U60) (b: U60) : U60
+Add a b = (+ a b)"#),
+    },
+    Explanation {
+        code: codes::NEED_TO_IMPLEMENT_METHODS,
+        title: "Required functions are not implemented",
+        text: "Some syntax sugars are translated to functions that must exist. \
+               `match` needs `Type.match`, `open` and destructuring `let` need \
+               `Record.match`, both generated by `#derive[match]`. Lists need \
+               `List`, `List.cons` and `List.nil` and pairs need `Sigma.new`.",
+        bad: Some(r#"type Bool {
+  true
+  false
+}
+
+Not (b: Bool) : Bool
+Not b =
+  match Bool b {
+    true  => Bool.false
+    false => Bool.true
+  }"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+Not (b: Bool) : Bool
+Not b =
+  match Bool b {
+    true  => Bool.false
+    false => Bool.true
+  }"#),
+    },
+    Explanation {
+        code: codes::LET_DESTRUCT_ONLY_FOR_RECORD,
+        title: "Can only destruct records",
+        text: "Destructuring a value with `let` or `open` only works for records, \
+               because they have a single constructor. Use `match` for sum \
+               types.",
+        bad: Some(r#"#derive[match]
+type Pair {
+  new (fst: U60) (snd: U60)
+}
+
+Fst (p: Pair) : U60
+Fst p =
+  let Pair.new fst .. = p
+  fst"#),
+        fixed: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p =
+  let Pair.new fst .. = p
+  fst"#),
+    },
+    Explanation {
+        code: codes::LET_DESTRUCT_ONLY_FOR_SUM,
+        title: "Can only match on types with constructors",
+        text: "The `match` expression needs to know the constructors of the \
+               type, so it only works for types declared with `type` or \
+               `record`, and not for types declared as functions.",
+        bad: Some(r#"Bool : Type
+Bool.true : Bool
+Bool.false : Bool
+Bool.match (b: Bool) : U60
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool b {
+    true  => 1
+    false => 0
+  }"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool b {
+    true  => 1
+    false => 0
+  }"#),
+    },
+    Explanation {
+        code: codes::CANNOT_FIND_FIELD,
+        title: "Cannot find the field",
+        text: "A destructuring or a match case binds a field that the \
+               constructor does not have.",
+        bad: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p =
+  let Pair.new (first = x) .. = p
+  x"#),
+        fixed: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p =
+  let Pair.new (fst = x) .. = p
+  x"#),
+    },
+    Explanation {
+        code: codes::CANNOT_FIND_CONSTRUCTOR,
+        title: "Cannot find the constructor",
+        text: "A case of a `match` refers to a constructor that the matched type \
+               does not have.",
+        bad: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool b {
+    true  => 1
+    fals => 0
+  }"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool b {
+    true  => 1
+    false => 0
+  }"#),
+    },
+    Explanation {
+        code: codes::NO_COVERAGE,
+        title: "The match is not covering all of the possibilities",
+        text: "A `match` needs one case for each constructor of the type. \
+               `kind2 fix` adds the missing cases with holes that can be \
+               filled later.",
+        bad: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool b {
+    true => 1
+  }"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}
+
+ToNum (b: Bool) : U60
+ToNum b =
+  match Bool b {
+    true  => 1
+    false => 0
+  }"#),
+    },
+    Explanation {
+        code: codes::INCORRECT_ARITY,
+        title: "Incorrect arity",
+        text: "Top level definitions and constructors must be called with all \
+               of their explicit arguments, or with all of their arguments, \
+               including the hidden ones. To partially apply a function, wrap \
+               it in a lambda. `kind2 fix` removes the extra arguments or adds \
+               holes for the missing ones.",
+        bad: Some(r#"Add (a: U60) (b: U60) : U60
+Add a b = (+ a b)
+
+Main : U60
+Main = Add 1"#),
+        fixed: Some(r#"Add (a: U60) (b: U60) : U60
+Add a b = (+ a b)
+
+Main : U60
+Main = Add 1 2"#),
+    },
+    Explanation {
+        code: codes::SUGAR_IS_BADLY_IMPLEMENTED,
+        title: "Incorrect arity in the sugar definition",
+        text: "A syntax sugar was translated to a function whose arguments are \
+               not the expected ones. List literals expect `List.cons` to \
+               receive the head and the tail, and pairs expect `Sigma.new` to \
+               receive both values.",
+        bad: Some(r#"type List <t: Type> {
+  nil
+  cons (head: t) (tail: List t) (size: U60)
+}
+
+Main : List U60
+Main = [1, 2]"#),
+        fixed: Some(r#"type List <t: Type> {
+  nil
+  cons (head: t) (tail: List t)
+}
+
+Main : List U60
+Main = [1, 2]"#),
+    },
+    Explanation {
+        code: codes::DUPLICATED_NAMED,
+        title: "Repeated named variable",
+        text: "The same field was bound twice in a destructuring or in a match \
+               case.",
+        bad: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Sum (p: Pair) : U60
+Sum p =
+  let Pair.new (fst = a) (fst = b) = p
+  (+ a b)"#),
+        fixed: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Sum (p: Pair) : U60
+Sum p =
+  let Pair.new (fst = a) (snd = b) = p
+  (+ a b)"#),
+    },
+    Explanation {
+        code: codes::CANNOT_ACCESS_TYPE,
+        title: "Cannot access the fields of the type",
+        text: "The `!Type value .field` syntax accesses the fields of records \
+               and the fields that all of the constructors of a sum type \
+               share, but the given type is neither of them.",
+        bad: Some(r#"#derive[match, getters]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: U60) : U60
+Fst p = !U60 p .fst"#),
+        fixed: Some(r#"#derive[match, getters]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p = !Pair p .fst"#),
+    },
+    Explanation {
+        code: codes::REPEATED_VARIABLE,
+        title: "Repeated name",
+        text: "Two variables were bound with the same name in the same \
+               pattern, so one of them would be inaccessible.",
+        bad: Some(r#"Add (a: U60) (b: U60) : U60
+Add a a = (+ a a)"#),
+        fixed: Some(r#"Add (a: U60) (b: U60) : U60
+Add a b = (+ a b)"#),
+    },
+    Explanation {
+        code: codes::CONTRACT_SHOULD_NOT_HAVE_RULES,
+        title: "A contract should not have rules",
+        text: "The rules of a definition marked with `#kdl_contract` are \
+               generated from the actions of the contract, one for each \
+               constructor, so it cannot have its own rules.",
+        bad: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60
+Counter action = 0"#),
+        fixed: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60"#),
+    },
+    Explanation {
+        code: codes::CONTRACT_SHOULD_RECEIVE_THE_ACTION,
+        title: "A contract should receive only the action",
+        text: "A contract receives the action that is going to be dispatched \
+               as its only explicit argument. A contract with a `#kdl_state` \
+               receives the current state before the action.",
+        bad: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) (amount: U60) : U60"#),
+        fixed: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60"#),
+    },
+    Explanation {
+        code: codes::CONTRACT_ACTIONS_IS_NOT_A_SUM_TYPE,
+        title: "The actions of a contract should be a sum type",
+        text: "The value of `#kdl_contract` is the type of the actions, and \
+               each one of its constructors is an action of the contract, so \
+               it must be a type declared with `type`.",
+        bad: Some(r#"Counter.Action : Type
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60"#),
+        fixed: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60"#),
+    },
+    Explanation {
+        code: codes::CONTRACT_MISSING_HANDLER,
+        title: "Missing handler of an action",
+        text: "Each action `Action.name` of a contract `Contract` is dispatched \
+               to a definition named `Contract.name` that receives the fields \
+               of the action, after the state if the contract has one.",
+        bad: Some(r#"type Counter.Action {
+  inc
+  add (amount: U60)
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60"#),
+        fixed: Some(r#"type Counter.Action {
+  inc
+  add (amount: U60)
+}
+
+Counter.inc : U60
+Counter.inc = 1
+
+Counter.add (amount: U60) : U60
+Counter.add amount = amount
+
+#kdl_contract = Counter.Action
+Counter (action: Counter.Action) : U60"#),
+    },
+    Explanation {
+        code: codes::CONTRACT_STATE_IS_NOT_A_RECORD,
+        title: "The state of a contract should be a record",
+        text: "The value of `#kdl_state` in a contract is the initial state, and \
+               its type must be a record, so the layout of the state is \
+               described by the fields of the record.",
+        bad: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc (state: U60) : U60
+Counter.inc state = state
+
+Counter.init : U60
+Counter.init = 0
+
+#kdl_contract = Counter.Action
+#kdl_state = Counter.init
+Counter (state: U60) (action: Counter.Action) : U60"#),
+        fixed: Some(r#"type Counter.Action {
+  inc
+}
+
+Counter.inc (state: Counter.State) : Counter.State
+Counter.inc state = state
+
+record Counter.State {
+  count : U60
+}
+
+Counter.init : Counter.State
+Counter.init = Counter.State.new 0
+
+#kdl_contract = Counter.Action
+#kdl_state = Counter.init
+Counter (state: Counter.State) (action: Counter.Action) : Counter.State"#),
+    },
+    Explanation {
+        code: codes::INVALID_STRICT_ARGUMENT,
+        title: "Strict argument is not relevant",
+        text: "The `#strict` attribute forces the evaluation of arguments before \
+               the function is called. It only accepts names of arguments that \
+               exist at runtime, so erased arguments cannot be strict.",
+        bad: Some(r#"#strict[acc]
+Sum <acc: U60> (n: U60) : U60
+Sum acc n = n"#),
+        fixed: Some(r#"#strict[acc]
+Sum (acc: U60) (n: U60) : U60
+Sum acc n = (+ acc n)"#),
+    },
+    Explanation {
+        code: codes::NO_FIELD_COVERAGE,
+        title: "The case is not covering all of the fields",
+        text: "A destructuring must bind all of the fields of the record, or \
+               end with `..` to ignore the rest of them.",
+        bad: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p =
+  let Pair.new fst = p
+  fst"#),
+        fixed: Some(r#"#derive[match]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p =
+  let Pair.new fst .. = p
+  fst"#),
+    },
+    Explanation {
+        code: codes::ATTRIBUTE_DOES_NOT_EXPECT_EQUAL,
+        title: "This attribute does not support values",
+        text: "Some attributes are flags and do not receive a value with `=`.",
+        bad: Some(r#"#inline = true
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"#inline
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::ATTRIBUTE_DOES_NOT_EXPECT_ARGS,
+        title: "This attribute does not expect arguments",
+        text: "Some attributes do not receive arguments between `[` and `]`.",
+        bad: Some(r#"#inline[n]
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"#inline
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::INVALID_ATTRIBUTE_ARGUMENT,
+        title: "Invalid attribute argument",
+        text: "The attribute received an argument that it does not understand. \
+               `#derive` accepts the derivations of the compiler, like \
+               `match`, `getters`, `setters` and `mutters`, and the names of \
+               the derivations written in Kind, and `#trace` accepts `true` \
+               or `false`.",
+        bad: Some(r#"#derive[matching]
+type Bool {
+  true
+  false
+}"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}"#),
+    },
+    Explanation {
+        code: codes::CANNOT_DERIVE,
+        title: "Cannot derive for this definition",
+        text: "Some derivations only make sense for sum types, like \
+               `induction`, and `getters`, `setters` and `mutters` need a \
+               field that every constructor of the type has.",
+        bad: Some(r#"#derive[getters]
+type Bool {
+  true
+  false
+}"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}"#),
+    },
+    Explanation {
+        code: codes::ATTRIBUTE_EXPECTS_A_VALUE,
+        title: "This attribute expects a value",
+        text: "Some attributes need a value, like `#kdl_name = Name`, or \
+               arguments, like `#strict[x]`.",
+        bad: Some(r#"#kdl_name
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"#kdl_name = Dbl
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::ATTRIBUTE_DOES_NOT_EXIST,
+        title: "This attribute does not exist",
+        text: "The name of the attribute is unknown. The compiler suggests a \
+               similar attribute when there is one, and `kind2 fix` applies \
+               the suggestion.",
+        bad: Some(r#"#inlin
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"#inline
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::NEEDS_A_FIELD,
+        title: "The expression does not access any field",
+        text: "The `!Type value @= f` syntax modifies a field of a record with \
+               a function, so it needs at least one field, like \
+               `!Type value .field @= f`.",
+        bad: Some(r#"#derive[match, mutters]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Update (p: Pair) (f: U60 -> U60) : Pair
+Update p f = !Pair p @= f"#),
+        fixed: Some(r#"#derive[match, mutters]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Update (p: Pair) (f: U60 -> U60) : Pair
+Update p f = !Pair p .fst @= f"#),
+    },
+    Explanation {
+        code: codes::CANNOT_FIND_THE_FIELD,
+        title: "Cannot find the field",
+        text: "The `!Type value .field` syntax accesses a field that the record \
+               does not have.",
+        bad: Some(r#"#derive[match, getters]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p = !Pair p .first"#),
+        fixed: Some(r#"#derive[match, getters]
+record Pair {
+  fst : U60
+  snd : U60
+}
+
+Fst (p: Pair) : U60
+Fst p = !Pair p .fst"#),
+    },
+    Explanation {
+        code: codes::CANNOT_FIND_ALIAS,
+        title: "Cannot find alias",
+        text: "A name like `A/b` refers to the alias `A` that must be created \
+               with `use Namespace as A` at the beginning of the file.",
+        bad: Some(r#"type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+One : Nat
+One = N/succ N/zero"#),
+        fixed: Some(r#"use Nat as N
+
+type Nat {
+  zero
+  succ (pred: Nat)
+}
+
+One : Nat
+One = N/succ N/zero"#),
+    },
+    Explanation {
+        code: codes::NOT_A_TYPE_CONSTRUCTOR,
+        title: "Not the type that is being declared",
+        text: "When a constructor declares its return type, it must be the type \
+               that is being declared applied to its parameters and indices.",
+        bad: Some(r#"type Box <t: Type> {
+  new (value: t) : U60
+}"#),
+        fixed: Some(r#"type Box <t: Type> {
+  new (value: t) : Box t
+}"#),
+    },
+    Explanation {
+        code: codes::DUPLICATED_ATTRIBUTE_ARGUMENT,
+        title: "Duplicated attribute argument",
+        text: "The same argument was given twice to an attribute. The second \
+               one has no effect.",
+        bad: Some(r#"#derive[match, match]
+type Bool {
+  true
+  false
+}"#),
+        fixed: Some(r#"#derive[match]
+type Bool {
+  true
+  false
+}"#),
+    },
+    Explanation {
+        code: codes::UNUSED_VARIABLE,
+        title: "Unused variable",
+        text: "A rule variable or a `let` binding is never used. It's reported \
+               by the lint `unused_variables`, that is a warning by default \
+               and can be changed with `#allow`, `#deny` or the command line. \
+               A rule variable that is not needed can be replaced by a hole \
+               like `_` or `_name`.",
+        bad: Some(r#"Fst (a: U60) (b: U60) : U60
+Fst a b = a"#),
+        fixed: Some(r#"Fst (a: U60) (b: U60) : U60
+Fst a _b = a"#),
+    },
+    Explanation {
+        code: codes::UNUSED_DEFINITION,
+        title: "Unused private definition",
+        text: "A definition whose last name starts with an underscore, like \
+               `Nat._double`, is private to its file and no other definition \
+               refers to it. It's reported by the lint `unused_definitions`, \
+               that is a warning by default, and the definitions marked with \
+               `#keep` are never reported.",
+        bad: Some(r#"Main._two : U60
+Main._two = 2
+
+Main : U60
+Main = 3"#),
+        fixed: Some(r#"Main._two : U60
+Main._two = 2
+
+Main : U60
+Main = Main._two"#),
+    },
+    Explanation {
+        code: codes::UNUSED_ALIAS,
+        title: "Unused alias",
+        text: "An alias created with `use Namespace as A` is never used in the \
+               file. It's reported by the lint `unused_aliases`, that is a \
+               warning by default. Aliases are not part of a definition, so \
+               its level can only be changed with the command line.",
+        bad: Some(r#"use Nat as N
+
+Main : U60
+Main = 3"#),
+        fixed: Some(r#"Main : U60
+Main = 3"#),
+    },
+    Explanation {
+        code: codes::UNKNOWN_LINT,
+        title: "Unknown lint",
+        text: "The attributes `#allow`, `#warn` and `#deny` receive the names \
+               of the lints whose level they change, and one of these names \
+               is not a lint that the compiler knows.",
+        bad: Some(r#"#allow[shadowing]
+Main : U60
+Main = 2"#),
+        fixed: Some(r#"#allow[shadowed_binding]
+Main : U60
+Main = 2"#),
+    },
+    Explanation {
+        code: codes::FIELD_NOT_IN_EVERY_CONSTRUCTOR,
+        title: "Field that is not in every constructor",
+        text: "The `!Type value .field` syntax accesses the fields of a sum \
+               type that all of its constructors have with the same type, \
+               because the value can be built by any of them. Add the field \
+               to the other constructors or match on the value.",
+        bad: Some(r#"#derive[match, getters]
+type Shape {
+  circle (radius: U60) (color: U60)
+  square (side: U60) (color: U60)
+}
+
+Radius (s: Shape) : U60
+Radius s = !Shape s .radius"#),
+        fixed: Some(r#"#derive[match, getters]
+type Shape {
+  circle (radius: U60) (color: U60)
+  square (side: U60) (color: U60)
+}
+
+Color (s: Shape) : U60
+Color s = !Shape s .color"#),
+    },
+    Explanation {
+        code: codes::PATH_LEAVES_RECORD,
+        title: "The path leaves the records",
+        text: "Each field of a path like `!User user .address .city` has to \
+               be a record, or a sum type with shared fields, so the next \
+               field can be accessed inside of it.",
+        bad: Some(r#"#derive[match, getters]
+record User {
+  name : U60
+  age  : U60
+}
+
+Years (u: User) : U60
+Years u = !User u .age .years"#),
+        fixed: Some(r#"#derive[match, getters]
+record User {
+  name : U60
+  age  : U60
+}
+
+Years (u: User) : U60
+Years u = !User u .age"#),
+    },
+    Explanation {
+        code: codes::CONTRACT_STATE_TYPE_MISMATCH,
+        title: "The state of a contract has the wrong type",
+        text: "A contract with a `#kdl_state` receives the current state as its \
+               first argument, so the argument must have the type of the \
+               initial state.",
+        bad: Some(r#"type Counter.Action {
+  inc
+}
+
+record Counter.State {
+  count : U60
+}
+
+Counter.init : Counter.State
+Counter.init = Counter.State.new 0
+
+Counter.inc (state: U60) : U60
+Counter.inc state = state
+
+#kdl_contract = Counter.Action
+#kdl_state = Counter.init
+Counter (state: U60) (action: Counter.Action) : U60"#),
+        fixed: Some(r#"type Counter.Action {
+  inc
+}
+
+record Counter.State {
+  count : U60
+}
+
+Counter.init : Counter.State
+Counter.init = Counter.State.new 0
+
+Counter.inc (state: Counter.State) : Counter.State
+Counter.inc state = state
+
+#kdl_contract = Counter.Action
+#kdl_state = Counter.init
+Counter (state: Counter.State) (action: Counter.Action) : Counter.State"#),
+    },
+    // Type checker
+    Explanation {
+        code: codes::TYPE_MISMATCH,
+        title: "Type mismatch",
+        text: "The type of the expression is not the type that was expected in \
+               its position. The message shows both types and the context of \
+               the variables around the expression.",
+        bad: Some(r#"type Bool {
+  true
+  false
+}
+
+Answer : U60
+Answer = Bool.true"#),
+        fixed: Some(r#"type Bool {
+  true
+  false
+}
+
+Answer : U60
+Answer = 42"#),
+    },
+    Explanation {
+        code: codes::INSPECTION,
+        title: "Inspection",
+        text: "A hole written as `?name` shows the type that is expected in its \
+               position and the variables that can be used there. It's a tool \
+               to write programs and proofs step by step, so it must be \
+               replaced by an expression at the end.",
+        bad: Some(r#"Double (n: U60) : U60
+Double n = ?hole"#),
+        fixed: Some(r#"Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::IMPOSSIBLE_CASE,
+        title: "Impossible case",
+        text: "A pattern of a rule cannot have the type of the argument that it \
+               matches, so the rule would never be used.",
+        bad: Some(r#"type Bool {
+  true
+  false
+}
+
+IsZero (n: U60) : Bool
+IsZero Bool.true = Bool.true"#),
+        fixed: Some(r#"type Bool {
+  true
+  false
+}
+
+IsZero (n: U60) : Bool
+IsZero 0 = Bool.true
+IsZero n = Bool.false"#),
+    },
+    Explanation {
+        code: codes::CANT_INFER_HOLE,
+        title: "Cannot infer hole",
+        text: "The type checker could not find the value of a hole (`_`) or of \
+               a hidden argument, because nothing around it constrains its \
+               value. Write the value explicitly.",
+        bad: Some(r#"Main : U60
+Main =
+  let x = _
+  (+ x 1)"#),
+        fixed: Some(r#"Main : U60
+Main =
+  let x = 41
+  (+ x 1)"#),
+    },
+    Explanation {
+        code: codes::INVALID_CALL,
+        title: "Cannot call this",
+        text: "The expression is applied to arguments but its type is not a \
+               function type.",
+        bad: Some(r#"Main : U60
+Main =
+  let x = 2
+  (x 3)"#),
+        fixed: Some(r#"Main : U60
+Main =
+  let x = 2
+  (* x 3)"#),
+    },
+    Explanation {
+        code: codes::UNCOVERED_PATTERN,
+        title: "The function does not cover all of the possibilities",
+        text: "The rules of the function do not match all of the values of its \
+               arguments, so some calls do not reduce. The message shows one of \
+               the missing cases. This check is enabled with `--coverage`.",
+        bad: Some(r#"type Bool {
+  true
+  false
+}
+
+Or (a: Bool) (b: Bool) : Bool
+Or Bool.true  _          = Bool.true
+Or Bool.false Bool.false = Bool.false"#),
+        fixed: Some(r#"type Bool {
+  true
+  false
+}
+
+Or (a: Bool) (b: Bool) : Bool
+Or Bool.true  _ = Bool.true
+Or Bool.false b = b"#),
+    },
+    Explanation {
+        code: codes::CANT_INFER_LAMBDA,
+        title: "Cannot infer lambda",
+        text: "The type of a lambda can only be checked against an expected \
+               function type, it cannot be inferred. Annotate the lambda with \
+               `::` or move it to a position where its type is known.",
+        bad: Some(r#"Main : U60
+Main =
+  let double = x => (* x 2)
+  (double 21)"#),
+        fixed: Some(r#"Main : U60
+Main =
+  let double = (x => (* x 2)) :: U60 -> U60
+  (double 21)"#),
+    },
+    Explanation {
+        code: codes::TOO_MANY_ARGUMENTS,
+        title: "Too many arguments",
+        text: "A rule has more patterns than the number of arguments in the \
+               type of the definition. The rules written by hand with too \
+               many patterns are reported before as E0203, so it only comes \
+               from generated definitions and has no example.",
+        bad: None,
+        fixed: None,
+    },
+    Explanation {
+        code: codes::UNBOUND_VARIABLE_IN_CHECKER,
+        title: "Unbound variable",
+        text: "The type checker found a variable that is not bound. The \
+               unbound names written by hand are reported before the type \
+               checking, so it comes from a `specialize x into #n` whose \
+               index is not one of the values that `x` has in the context, \
+               or from a generated definition that is inconsistent.",
+        bad: Some(r#"Main : U60
+Main =
+  let n = 2
+  specialize n into #1 in (+ n 1)"#),
+        fixed: Some(r#"Main : U60
+Main =
+  let n = 2
+  specialize n into #0 in (+ n 1)"#),
+    },
+    // Derivations
+    Explanation {
+        code: codes::CANNOT_USE_NAMED_VARIABLE,
+        title: "Named variable in a constructor return type",
+        text: "The derivation of `match` cannot use named arguments like \
+               `(name = value)` in the return type of the constructors.",
+        bad: Some(r#"#derive[match]
+type Vec <t: Type> ~ (len: U60) {
+  nil : Vec t (len = 0)
+}"#),
+        fixed: Some(r#"#derive[match]
+type Vec <t: Type> ~ (len: U60) {
+  nil : Vec t 0
+}"#),
+    },
+    Explanation {
+        code: codes::CANNOT_USE_ALL,
+        title: "Constructor returning a function",
+        text: "The return type of a constructor must be the type that is being \
+               declared, constructors cannot return functions. Use the \
+               arguments of the constructor instead.",
+        bad: Some(r#"#derive[match]
+type Pair {
+  new (fst: U60) : U60 -> Pair
+}"#),
+        fixed: Some(r#"#derive[match]
+type Pair {
+  new (fst: U60) (snd: U60) : Pair
+}"#),
+    },
+    Explanation {
+        code: codes::INVALID_RETURN_TYPE,
+        title: "Constructor returning an invalid type",
+        text: "The return type of a constructor must be the type that is being \
+               declared applied to its parameters and indices.",
+        bad: Some(r#"#derive[match]
+type Box <t: Type> {
+  new (value: t) : t
+}"#),
+        fixed: Some(r#"#derive[match]
+type Box <t: Type> {
+  new (value: t) : Box t
+}"#),
+    },
+    Explanation {
+        code: codes::UNSUPPORTED_FIELD,
+        title: "Field that is not supported by a derivation",
+        text: "Derivations like `eq`, `ord`, `hash` and `show` handle every \
+               field of the constructors with the function of its type, so the \
+               types of the fields must be type parameters, `U60`, `F60` or \
+               applications of types that have the function too. Functions \
+               cannot be compared, hashed or shown, so they have to be replaced \
+               by a value that identifies them. The `serialize` derivation \
+               does not support `F60` nor hidden and erased fields.",
+        bad: Some(r#"type Bool { true false }
+
+#derive[eq]
+record Handler {
+  run : U60 -> U60
+}"#),
+        fixed: Some(r#"type Bool { true false }
+
+U60.equal (a: U60) (b: U60) : Bool
+U60.equal a b = Bool.true
+
+#derive[eq]
+record Handler {
+  id : U60
+}"#),
+    },
+    Explanation {
+        code: codes::NO_TYPE_PARAMETER,
+        title: "Derivation that needs a type parameter",
+        text: "The `functor` derivation generates `map`, that changes the type \
+               of the values of the last type parameter of the type. A type \
+               without type parameters has nothing to map.",
+        bad: Some(r#"#derive[functor]
+type Color {
+  red
+  green
+}"#),
+        fixed: Some(r#"#derive[functor]
+type Box (t: Type) {
+  new (value: t)
+}"#),
+    },
+    Explanation {
+        code: codes::UNSUPPORTED_OCCURRENCE,
+        title: "Occurrence that a derivation cannot traverse",
+        text: "The `functor` and `fold` derivations look for the values of the \
+               mapped type parameter, or of the folded type, inside of the \
+               fields. They can be the fields themselves or be inside of the \
+               last argument of a type that derives `functor`, like \
+               `List (Tree t)`. Functions and the other arguments of types \
+               cannot be traversed.",
+        bad: Some(r#"#derive[functor]
+type Handler (t: Type) {
+  new (run: t -> U60)
+}"#),
+        fixed: Some(r#"#derive[functor]
+type Handler (t: Type) {
+  new (input: t) (output: U60)
+}"#),
+    },
+    Explanation {
+        code: codes::INDEXED_TYPE,
+        title: "Derivation that does not support indices",
+        text: "The `serialize` derivation reads a value back from the names of \
+               its constructors and from its fields. The indices of a type \
+               are not written, so they cannot be recovered when the value is \
+               read. Turn them into parameters, or into fields of the \
+               constructors, to serialize the type.",
+        bad: Some(r#"type Nat { zero succ (pred: Nat) }
+
+#derive[serialize]
+type Vec (t: Type) ~ (n: Nat) {
+  nil : Vec t Nat.zero
+  cons <size: Nat> (x: t) (xs: Vec t size) : Vec t (Nat.succ size)
+}"#),
+        fixed: Some(r#"type String { nil cons (head: U60) (tail: String) }
+type Maybe (t: Type) { some (val: t) none }
+record Pair (a: Type) (b: Type) {
+  fst : a
+  snd : b
+}
+
+String.concat (xs: String) (ys: String) : String
+String.concat String.nil ys = ys
+String.concat (String.cons x xs) ys = String.cons x (String.concat xs ys)
+
+#derive[serialize]
+type List (t: Type) {
+  nil
+  cons (x: t) (xs: List t)
+}"#),
+    },
+    Explanation {
+        code: codes::CONSTRUCTOR_WITH_FIELDS,
+        title: "Derivation that needs constructors without fields",
+        text: "The `enum` derivation numbers the constructors by their \
+               position in the declaration, so `T.from_u60` can only build \
+               them back if they have nothing else to store. Move the fields \
+               to another type, or keep them next to the value of the \
+               enumeration.",
+        bad: Some(r#"#derive[enum]
+type Status {
+  ok
+  error (code: U60)
+}"#),
+        fixed: Some(r#"type List (t: Type) { nil cons (head: t) (tail: List t) }
+type Maybe (t: Type) { some (val: t) none }
+type Equal <t: Type> (a: t) ~ (b: t) {
+  refl : Equal t a a
+}
+
+#derive[enum]
+type Status {
+  ok
+  error
+}
+
+record Response {
+  status : Status
+  code   : U60
+}"#),
+    },
+    // Kindelia
+    Explanation {
+        code: codes::INVALID_VAR_NAME,
+        title: "Invalid name for Kindelia",
+        text: "Names in Kindelia have at most 12 characters from `a-z`, `A-Z`, \
+               `0-9`, `.` and `_`. Long names of definitions are shortened \
+               automatically, but the names chosen with `#kdl_name` must be \
+               valid.",
+        bad: Some(r#"#kdl_name = DoubleTheNumber
+#keep
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+        fixed: Some(r#"#kdl_name = Double
+#keep
+Double (n: U60) : U60
+Double n = (* n 2)"#),
+    },
+    Explanation {
+        code: codes::SHOULD_NOT_HAVE_ARGUMENTS,
+        title: "This entry should not have arguments",
+        text: "Entries marked with `#kdl_run` and the initial states of \
+               `#kdl_state` are evaluated once, so they cannot receive \
+               arguments.",
+        bad: Some(r#"#kdl_run
+Start (n: U60) : U60
+Start n = n"#),
+        fixed: Some(r#"#kdl_run
+Start : U60
+Start = 42"#),
+    },
+    Explanation {
+        code: codes::SHOULD_HAVE_ONLY_ONE_RULE,
+        title: "This entry should have only one rule",
+        text: "Entries marked with `#kdl_run` and the initial states of \
+               `#kdl_state` are evaluated once, so they need exactly one rule.",
+        bad: Some(r#"#kdl_state = Counter.init
+#keep
+Counter : U60 {
+  1
+}
+
+Counter.init : U60"#),
+        fixed: Some(r#"#kdl_state = Counter.init
+#keep
+Counter : U60 {
+  1
+}
+
+Counter.init : U60
+Counter.init = 0"#),
+    },
+    Explanation {
+        code: codes::NO_INIT_ENTRY,
+        title: "Missing initial state",
+        text: "The value of `#kdl_state` must be the name of the definition that \
+               computes the initial state of the function.",
+        bad: Some(r#"#kdl_state = Counter.init
+#keep
+Counter : U60 {
+  1
+}"#),
+        fixed: Some(r#"#kdl_state = Counter.init
+#keep
+Counter : U60 {
+  1
+}
+
+Counter.init : U60
+Counter.init = 0"#),
+    },
+    Explanation {
+        code: codes::FLOAT_USED,
+        title: "Floats are not supported in Kindelia",
+        text: "Kindelia only has unsigned integers, so programs that use `F60` \
+               cannot be compiled to it. The syntax does not have `F60` \
+               literals yet, so it has no example.",
+        bad: None,
+        fixed: None,
+    },
+    Explanation {
+        code: codes::MISSING_SIGNATURE,
+        title: "Missing signature",
+        text: "Statements marked with `#kdl_sign` receive their signatures \
+               from the file given by `--signatures`. The compiler does not \
+               sign statements itself, so a statement without a signature \
+               in the file cannot be generated: sign it with the Kindelia \
+               client and add its signature to the file.",
+        bad: Some(r#"#kdl_sign
+Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21
+
+// File: Main.signatures
+// Signatures made by the Kindelia client"#),
+        fixed: Some(r#"#kdl_sign
+Double (n: U60) : U60
+Double n = (* n 2)
+
+Main : U60
+Main = Double 21
+
+// File: Main.signatures
+// Signatures made by the Kindelia client
+Double 1b00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"#),
+    },
+    Explanation {
+        code: codes::CANNOT_FORCE_ARGUMENT,
+        title: "Argument cannot be strict in Kindelia",
+        text: "Kindelia only evaluates the arguments of a function that are \
+               matched against a constructor or a number in some rule, and it \
+               has no other way to make an argument strict. A `#strict` \
+               argument that is only bound to variables cannot be compiled \
+               to Kindelia.",
+        bad: Some(r#"#strict[n]
+#keep
+Double (n: U60) : U60
+Double n = (+ n n)"#),
+        fixed: Some(r#"type Nums {
+  nil
+  cons (head: U60) (tail: Nums)
+}
+
+#strict[xs]
+#keep
+Length (xs: Nums) : U60
+Length Nums.nil = 0
+Length (Nums.cons x xs) = (+ 1 (Length xs))"#),
+    },
+    // Lints
+    Explanation {
+        code: codes::REDUNDANT_MOTIVE,
+        title: "Redundant match motive",
+        text: "The motive of a `match` does not use the matched value nor the \
+               variables given with `with`, so it's just the type of the \
+               expression and the type checker can infer it. It's a warning of \
+               the lint `redundant_motive`.",
+        bad: Some(r#"#derive[match]
+type Opt {
+  none
+  some (value: U60)
+}
+
+Main : U60
+Main =
+  match Opt x = Opt.some 2 {
+    some => x.value
+    none => 0
+  } : U60"#),
+        fixed: Some(r#"#derive[match]
+type Opt {
+  none
+  some (value: U60)
+}
+
+Main : U60
+Main =
+  match Opt x = Opt.some 2 {
+    some => x.value
+    none => 0
+  }"#),
+    },
+    Explanation {
+        code: codes::SHADOWED_BINDING,
+        title: "Shadowed binding",
+        text: "A variable has the same name of another variable that is in \
+               scope, so the other one cannot be used anymore. The lint \
+               `shadowed_binding` is allowed by default and it can be turned \
+               on with `#warn[shadowed_binding]` or `--warn shadowed_binding`.",
+        bad: Some(r#"#warn[shadowed_binding]
+Main : U60
+Main =
+  let x = 2
+  let x = (+ x 1)
+  x"#),
+        fixed: Some(r#"#warn[shadowed_binding]
+Main : U60
+Main =
+  let x = 2
+  let y = (+ x 1)
+  y"#),
+    },
+    Explanation {
+        code: codes::NOT_SNAKE_CASE,
+        title: "Variable not in snake case",
+        text: "The names of the variables should be in snake case, like \
+               `first_number` instead of `firstNumber`. It's a warning of the \
+               lint `non_snake_case`.",
+        bad: Some(r#"Main : U60
+Main =
+  let someValue = 2
+  someValue"#),
+        fixed: Some(r#"Main : U60
+Main =
+  let some_value = 2
+  some_value"#),
+    },
+    Explanation {
+        code: codes::CONSTANT_CONDITION,
+        title: "Constant condition",
+        text: "The condition of an `if` is a constant, so one of the branches \
+               is never taken and the `if` can be replaced by the other one. \
+               It's a warning of the lint `constant_condition`.",
+        bad: Some(r#"type Bool {
+  true
+  false
+}
+
+Bool.if <r: Type> (b: Bool) (t: r) (f: r) : r
+Bool.if _ Bool.true  t _ = t
+Bool.if _ Bool.false _ f = f
+
+Main : U60
+Main = if Bool.true { 1 } else { 2 }"#),
+        fixed: Some(r#"type Bool {
+  true
+  false
+}
+
+Bool.if <r: Type> (b: Bool) (t: r) (f: r) : r
+Bool.if _ Bool.true  t _ = t
+Bool.if _ Bool.false _ f = f
+
+Main : U60
+Main = 1"#),
+    },
+];
@@ -1,9 +1,15 @@
 use yansi::Paint;
 
+/// Registry of the diagnostic codes
+pub mod codes;
 /// Data structures
 pub mod data;
+/// Explanations of the diagnostic codes
+pub mod explain;
 /// Render
 pub mod report;
+/// SARIF output for code scanning tools
+pub mod sarif;
 
 #[derive(Debug)]
 pub struct Chars {
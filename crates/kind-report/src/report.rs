@@ -14,9 +14,9 @@ use kind_span::{Pos, SyntaxCtxIndex};
 use unicode_width::UnicodeWidthStr;
 use yansi::Paint;
 
-use crate::{data::*, RenderConfig};
+use crate::{data::*, explain::code_name, RenderConfig};
 
-type SortedMarkers = FxHashMap<SyntaxCtxIndex, Vec<Marker>>;
+type SortedMarkers = Vec<(SyntaxCtxIndex, Vec<Marker>)>;
 
 #[derive(Debug, Clone)]
 struct Point {
@@ -34,22 +34,75 @@ impl Display for Point {
     }
 }
 
-fn count_width(str: &str) -> (usize, usize) {
-    (UnicodeWidthStr::width(str), str.chars().filter(|x| *x == '\t').count())
+/// Line and column (both starting at 1 and counting characters) of
+/// a byte index of the code.
+pub fn line_and_column(code: &str, index: usize) -> (usize, usize) {
+    let before = &code[..index.min(code.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|x| x + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count() + 1)
 }
 
-fn group_markers(markers: &[Marker]) -> SortedMarkers {
-    let mut file_group = SortedMarkers::default();
-    for marker in markers {
-        let group = file_group
-            .entry(marker.position.ctx)
-            .or_insert_with(Vec::new);
-        group.push(marker.clone())
+/// Parses a position in the `line:column` format, both starting at 1.
+pub fn parse_position(str: &str) -> Result<(usize, usize), String> {
+    let (line, column) = str
+        .split_once(':')
+        .ok_or_else(|| "expected a position like 'line:column'".to_string())?;
+
+    match (line.parse(), column.parse()) {
+        (Ok(line), Ok(column)) if line > 0 && column > 0 => Ok((line, column)),
+        _ => Err("the line and the column should be numbers starting at 1".to_string()),
     }
-    for group in file_group.values_mut() {
-        group.sort_by(|x, y| x.position.start.cmp(&y.position.end));
+}
+
+/// Byte index of a line and a column, both starting at 1, like the
+/// ones returned by [line_and_column].
+pub fn position_to_index(code: &str, (line, column): (usize, usize)) -> Option<usize> {
+    let line_start = if line == 1 {
+        0
+    } else {
+        code.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+
+    let line_code = code[line_start..].split('\n').next().unwrap_or_default();
+
+    line_code
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(line_code.len()))
+        .nth(column - 1)
+        .map(|index| line_start + index)
+}
+
+fn plural(count: usize, name: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, name)
+    } else {
+        format!("{} {}s", count, name)
     }
-    file_group
+}
+
+fn count_width(str: &str) -> (usize, usize) {
+    (UnicodeWidthStr::width(str), str.chars().filter(|x| *x == '\t').count())
+}
+
+/// Groups the markers by file, in the order of [DiagnosticFrame::syntax_ctxs],
+/// so each file gets its own code block.
+fn group_markers(frame: &DiagnosticFrame) -> SortedMarkers {
+    frame
+        .syntax_ctxs()
+        .into_iter()
+        .map(|ctx| {
+            let mut group: Vec<Marker> = frame
+                .positions
+                .iter()
+                .filter(|marker| marker.position.ctx == ctx)
+                .cloned()
+                .collect();
+            group.sort_by_key(|x| x.position.start);
+            (ctx, group)
+        })
+        .collect()
 }
 
 fn get_code_line_guide(code: &str) -> Vec<usize> {
@@ -417,6 +470,7 @@ impl Report for Box<dyn Diagnostic> {
         let frame = self.to_diagnostic_frame();
 
         render_tag(&frame.severity, fmt)?;
+        write!(fmt, "{} ", Paint::new(format!("[{}]", code_name(frame.code))).dimmed())?;
         writeln!(fmt, "{}", Paint::new(&frame.title).bold())?;
 
         if !frame.subtitles.is_empty() {
@@ -467,7 +521,7 @@ impl Report for Box<dyn Diagnostic> {
             }
         }
 
-        let groups = group_markers(&frame.positions);
+        let groups = group_markers(&frame);
         let is_empty = groups.is_empty();
 
         for (ctx, group) in groups {
@@ -547,6 +601,16 @@ impl Report for Log {
                     u64
                 )
             }
+            Log::Summary { errors, warnings } => {
+                let color = if *errors > 0 { yansi::Color::Red } else { yansi::Color::Yellow };
+                writeln!(
+                    fmt,
+                    "   {} {} and {}",
+                    Paint::new(" SUMMARY ").bg(color).bold(),
+                    plural(*errors, "error"),
+                    plural(*warnings, "warning")
+                )
+            }
         }
     }
 }
@@ -0,0 +1,313 @@
+//! Writes diagnostics in the SARIF 2.1.0 format, that is read by
+//! code scanning tools. Each diagnostic code becomes a rule and the
+//! main marker of a diagnostic becomes the location of its result.
+
+use std::path::PathBuf;
+
+use kind_span::Range;
+use serde::Serialize;
+
+use crate::data::{Diagnostic, DiagnosticFrame, Severity, Subtitle, Word};
+use crate::explain::{code_name, explain};
+use crate::report::{line_and_column, FileCache};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/Kindelia/Kind2";
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+impl Message {
+    fn new(text: impl Into<String>) -> Message {
+        Message { text: text.into() }
+    }
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: Message,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    artifact_changes: Vec<ArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolDriver {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: ToolDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+fn file_uri(path: PathBuf) -> String {
+    let path = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| pathdiff::diff_paths(&path, cwd))
+        .unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+struct Writer<'a, C: FileCache> {
+    cache: &'a C,
+}
+
+impl<'a, C: FileCache> Writer<'a, C> {
+    fn physical_location(&self, range: Range) -> Option<PhysicalLocation> {
+        let (path, code) = self.cache.fetch(range.ctx)?;
+        let (start_line, start_column) = line_and_column(code, range.start.index as usize);
+        let (end_line, end_column) = line_and_column(code, range.end.index as usize);
+
+        Some(PhysicalLocation {
+            artifact_location: ArtifactLocation { uri: file_uri(path) },
+            region: Region {
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+            },
+        })
+    }
+
+    fn location(&self, range: Range) -> Option<Location> {
+        Some(Location {
+            id: None,
+            physical_location: self.physical_location(range)?,
+            message: None,
+        })
+    }
+
+    fn related_location(&self, id: usize, range: Range, message: &str) -> Option<Location> {
+        Some(Location {
+            id: Some(id),
+            physical_location: self.physical_location(range)?,
+            message: Some(Message::new(message)),
+        })
+    }
+
+    fn fixes(&self, frame: &DiagnosticFrame) -> Vec<Fix> {
+        frame
+            .suggestions
+            .iter()
+            .filter_map(|suggestion| {
+                let location = self.physical_location(suggestion.position)?;
+                Some(Fix {
+                    artifact_changes: vec![ArtifactChange {
+                        artifact_location: location.artifact_location,
+                        replacements: vec![Replacement {
+                            deleted_region: location.region,
+                            inserted_content: Message::new(suggestion.replacement.clone()),
+                        }],
+                    }],
+                })
+            })
+            .collect()
+    }
+
+    fn result(&self, frame: &DiagnosticFrame, rule_index: usize) -> SarifResult {
+        let level = match frame.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        };
+
+        let main = frame
+            .positions
+            .iter()
+            .position(|marker| marker.main)
+            .unwrap_or(0);
+
+        let locations = frame
+            .positions
+            .get(main)
+            .and_then(|marker| self.location(marker.position))
+            .into_iter()
+            .collect();
+
+        let related_locations = frame
+            .positions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != main)
+            .filter_map(|(i, marker)| self.related_location(i, marker.position, &marker.text))
+            .collect();
+
+        SarifResult {
+            rule_id: code_name(frame.code),
+            rule_index,
+            level,
+            message: Message::new(message_text(frame)),
+            locations,
+            related_locations,
+            fixes: self.fixes(frame),
+        }
+    }
+}
+
+/// The title of the diagnostic followed by its subtitles and hints.
+fn message_text(frame: &DiagnosticFrame) -> String {
+    let mut lines = vec![frame.title.clone()];
+
+    for subtitle in &frame.subtitles {
+        match subtitle {
+            Subtitle::Normal(_, phr) | Subtitle::Bold(_, phr) => lines.push(phr.clone()),
+            Subtitle::Phrase(_, words) => lines.push(
+                words
+                    .iter()
+                    .map(|word| match word {
+                        Word::Dimmed(str) | Word::White(str) | Word::Normal(str) | Word::Painted(_, str) => {
+                            str.trim()
+                        }
+                    })
+                    .filter(|str| !str.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            Subtitle::LineBreak => (),
+        }
+    }
+
+    for hint in &frame.hints {
+        lines.push(format!("Hint: {}", hint));
+    }
+
+    lines.join("\n")
+}
+
+fn rule(code: u32) -> Rule {
+    let explanation = explain(code);
+
+    Rule {
+        id: code_name(code),
+        short_description: explanation.map(|explanation| Message::new(explanation.title)),
+        full_description: explanation.map(|explanation| Message::new(explanation.text)),
+    }
+}
+
+/// Renders the diagnostics as a SARIF log with a single run. The
+/// locations are resolved with the `cache` and the ones that are not
+/// in any file are left out.
+pub fn render_sarif<C: FileCache>(diagnostics: &[Box<dyn Diagnostic>], cache: &C) -> String {
+    let writer = Writer { cache };
+
+    let frames = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_diagnostic_frame())
+        .collect::<Vec<_>>();
+
+    let mut codes = frames.iter().map(|frame| frame.code).collect::<Vec<_>>();
+    codes.sort_unstable();
+    codes.dedup();
+
+    let results = frames
+        .iter()
+        .map(|frame| writer.result(frame, codes.binary_search(&frame.code).unwrap()))
+        .collect();
+
+    let log = Log {
+        schema: SCHEMA,
+        version: "2.1.0",
+        runs: vec![Run {
+            tool: Tool {
+                driver: ToolDriver {
+                    name: "kind2",
+                    information_uri: INFORMATION_URI,
+                    rules: codes.into_iter().map(rule).collect(),
+                },
+            },
+            column_kind: "unicodeCodePoints",
+            results,
+        }],
+    };
+
+    serde_json::to_string(&log).unwrap()
+}
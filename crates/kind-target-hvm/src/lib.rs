@@ -1,18 +1,26 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
 use hvm::u60;
 
 use kind_tree::untyped;
 
-use hvm::syntax::{File, Rule, Term};
+use hvm::syntax::{self, Rule, Term};
+
+/// A compiled HVM file. The [Display] of the HVM file does not
+/// print the strictness of the arguments, so this one marks the
+/// strict arguments of the first rule of each function with `!`.
+pub struct File(pub syntax::File);
 
 pub fn compile_book(book: untyped::Book, trace: bool) -> File {
-    let mut file = File {
+    let mut file = syntax::File {
         rules: Default::default(),
         smaps: Default::default(),
     };
     for (_, entry) in book.entrs {
         compile_entry(&mut file, entry, trace);
     }
-    file
+    File(file)
 }
 
 pub fn compile_str(val: &str) -> Box<Term> {
@@ -79,7 +87,16 @@ fn compile_rule(name: String, rule: untyped::Rule) -> Rule {
     }
 }
 
-fn compile_entry(file: &mut File, entry: Box<untyped::Entry>, trace: bool) {
+fn compile_entry(file: &mut syntax::File, entry: Box<untyped::Entry>, trace: bool) {
+    let smap = entry.args.iter().map(|arg| arg.2).collect::<Vec<_>>();
+
+    if !entry.rules.is_empty() && smap.iter().any(|strict| *strict) {
+        file.smaps.push((entry.name.to_string(), smap.clone()));
+        if entry.attrs.trace.is_some() || trace {
+            file.smaps.push((format!("{}__trace", entry.name), smap));
+        }
+    }
+
     if entry.attrs.trace.is_some() || trace {
         let _with_args = entry.attrs.trace.unwrap_or(false);
 
@@ -122,3 +139,166 @@ fn compile_entry(file: &mut File, entry: Box<untyped::Entry>, trace: bool) {
         }
     }
 }
+
+impl Display for File {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut smaps = self
+            .0
+            .smaps
+            .iter()
+            .map(|(name, smap)| (name.as_str(), smap))
+            .collect::<HashMap<_, _>>();
+
+        for (i, rule) in self.0.rules.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            match &*rule.lhs {
+                Term::Ctr { name, args } if smaps.contains_key(name.as_str()) => {
+                    let smap = smaps.remove(name.as_str()).unwrap();
+                    write!(f, "({}", name)?;
+                    for (arg, strict) in args.iter().zip(smap) {
+                        write!(f, " {}{}", if *strict { "!" } else { "" }, Shown(arg, Strings::Code))?;
+                    }
+                    write!(f, ") = {}", Shown(&rule.rhs, Strings::Code))?;
+                }
+                _ => write!(
+                    f,
+                    "{} = {}",
+                    Shown(&rule.lhs, Strings::Code),
+                    Shown(&rule.rhs, Strings::Code)
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the characters of a term in the `(String.cons ... String.nil)`
+/// shape.
+pub fn read_string(mut term: &Term) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        match term {
+            Term::Ctr { name, args } if name == "String.cons" && args.len() == 2 => {
+                match *args[0] {
+                    Term::U6O { numb } => text.push(char::from_u32(numb as u32)?),
+                    _ => return None,
+                }
+                term = &args[1];
+            }
+            Term::Ctr { name, args } if name == "String.nil" && args.is_empty() => return Some(text),
+            _ => return None,
+        }
+    }
+}
+
+/// Shows a term that was read back from the HVM like its [Display]
+/// does, but with the quotes and the backslashes of the strings
+/// escaped, so a string that has them can be told apart from the end
+/// of the string.
+pub fn show_term(term: &Term) -> String {
+    Shown(term, Strings::Escaped).to_string()
+}
+
+/// How the strings are written when a term is shown. The HVM does not
+/// read escape sequences, so a string is read until the next quote.
+#[derive(Clone, Copy)]
+enum Strings {
+    /// As the HVM reads them, inside of backticks if they have quotes
+    /// or as a list of characters if they have backticks too.
+    Code,
+    /// With the quotes and the backslashes escaped.
+    Escaped,
+}
+
+/// Shows a term with the [Display] of the HVM, except for the strings
+/// that it cannot show in the way that is asked.
+struct Shown<'a>(&'a Term, Strings);
+
+impl Display for Shown<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.1.replace(self.0))
+    }
+}
+
+impl Strings {
+    /// Shows a string, or returns [None] if the HVM already shows it
+    /// in this way.
+    fn show(self, text: &str) -> Option<String> {
+        match self {
+            Strings::Code if !text.contains('"') => None,
+            Strings::Code if !text.contains('`') => Some(format!("`{}`", text)),
+            Strings::Code => {
+                let mut res = String::new();
+                for chr in text.chars() {
+                    res.push_str(&format!("(String.cons {} ", chr as u64));
+                }
+                res.push_str("(String.nil)");
+                res.push_str(&")".repeat(text.chars().count()));
+                Some(res)
+            }
+            Strings::Escaped if !text.contains(['"', '\\']) => None,
+            Strings::Escaped => {
+                let mut res = String::from('"');
+                for chr in text.chars() {
+                    if chr == '"' || chr == '\\' {
+                        res.push('\\');
+                    }
+                    res.push(chr);
+                }
+                res.push('"');
+                Some(res)
+            }
+        }
+    }
+
+    /// Copies a term replacing the strings that the HVM does not show
+    /// in this way by variables named after how they are shown, because
+    /// the HVM shows the name of a variable as it is.
+    fn replace(self, term: &Term) -> Term {
+        if let Some(name) = read_string(term).and_then(|text| self.show(&text)) {
+            return Term::Var { name };
+        }
+
+        let go = |term: &Term| Box::new(self.replace(term));
+
+        match term {
+            Term::Dup { nam0, nam1, expr, body } => Term::Dup {
+                nam0: nam0.clone(),
+                nam1: nam1.clone(),
+                expr: go(expr),
+                body: go(body),
+            },
+            Term::Sup { val0, val1 } => Term::Sup {
+                val0: go(val0),
+                val1: go(val1),
+            },
+            Term::Let { name, expr, body } => Term::Let {
+                name: name.clone(),
+                expr: go(expr),
+                body: go(body),
+            },
+            Term::Lam { name, body } => Term::Lam {
+                name: name.clone(),
+                body: go(body),
+            },
+            Term::App { func, argm } => Term::App {
+                func: go(func),
+                argm: go(argm),
+            },
+            Term::Ctr { name, args } => Term::Ctr {
+                name: name.clone(),
+                args: args.iter().map(|arg| go(arg)).collect(),
+            },
+            Term::Op2 { oper, val0, val1 } => Term::Op2 {
+                oper: *oper,
+                val0: go(val0),
+                val1: go(val1),
+            },
+            Term::Var { .. } | Term::U6O { .. } | Term::F6O { .. } => term.clone(),
+        }
+    }
+}
@@ -9,6 +9,8 @@ use tiny_keccak::Hasher;
 
 pub use kindelia_lang::ast as kdl;
 
+use crate::signature::Signatures;
+use crate::stats::Renaming;
 use crate::{diagnostic::KdlDiagnostic, GenericCompilationToHVMError};
 
 pub const KDL_NAME_LEN: usize = 12;
@@ -49,8 +51,10 @@ pub struct File {
 pub struct CompileCtx<'a> {
     file: File,
     kdl_names: FxHashMap<String, kdl::Name>,
+    renamings: Vec<Renaming>,
     kdl_states: Vec<String>,
     book: &'a untyped::Book,
+    signatures: &'a Signatures,
 
     kdl_used_names: im_rc::HashSet<String>,
 
@@ -59,7 +63,11 @@ pub struct CompileCtx<'a> {
 }
 
 impl<'a> CompileCtx<'a> {
-    pub fn new(book: &'a untyped::Book, sender: Sender<Box<dyn Diagnostic>>) -> CompileCtx<'a> {
+    pub fn new(
+        book: &'a untyped::Book,
+        signatures: &'a Signatures,
+        sender: Sender<Box<dyn Diagnostic>>,
+    ) -> CompileCtx<'a> {
         CompileCtx {
             file: File {
                 ctrs: Default::default(),
@@ -67,9 +75,11 @@ impl<'a> CompileCtx<'a> {
                 runs: Default::default(),
             },
             kdl_names: Default::default(),
+            renamings: Default::default(),
             kdl_states: Default::default(),
             kdl_used_names: Default::default(),
             book,
+            signatures,
             sender,
             failed: false,
         }
@@ -79,6 +89,24 @@ impl<'a> CompileCtx<'a> {
         self.sender.send(err).unwrap();
         self.failed = true;
     }
+
+    /// Gets the signature of the statement generated by an entry
+    /// that is marked with `#kdl_sign`. A missing signature is an
+    /// error, because Kindelia rejects a statement with a wrong one.
+    pub fn sign(&mut self, entry: &untyped::Entry) -> Option<kdl::Signature> {
+        if !entry.attrs.kdl_sign {
+            return None;
+        }
+
+        match self.signatures.get(entry.name.to_str()) {
+            Some(sign) => Some(sign.clone()),
+            None => {
+                let err = KdlDiagnostic::MissingSignature(entry.name.to_string(), entry.name.range);
+                self.send_err(Box::new(err));
+                None
+            }
+        }
+    }
 }
 
 // Functions to generate a new name
@@ -126,16 +154,24 @@ pub fn compile_book(
     book: &untyped::Book,
     sender: Sender<Box<dyn Diagnostic>>,
     namespace: &str,
-) -> Result<File, GenericCompilationToHVMError> {
-    let mut ctx = CompileCtx::new(book, sender);
+    signatures: &Signatures,
+) -> Result<(File, Vec<Renaming>), GenericCompilationToHVMError> {
+    let mut ctx = CompileCtx::new(book, signatures, sender);
 
     for (name, entry) in &book.entrs {
-        let new_name = entry
-            .attrs
-            .kdl_name
-            .clone()
-            .map(|x| x.to_string())
-            .unwrap_or_else(|| name_shortener(&entry.name, namespace).to_string());
+        let new_name = match &entry.attrs.kdl_name {
+            Some(kdl_name) => kdl_name.to_string(),
+            None => {
+                let new_name = name_shortener(&entry.name, namespace).to_string();
+                if new_name != *name {
+                    ctx.renamings.push(Renaming {
+                        name: name.clone(),
+                        kdl_name: new_name.clone(),
+                    });
+                }
+                new_name
+            }
+        };
 
         if let Ok(new_name) = from_str(&new_name) {
             ctx.kdl_names.insert(name.clone(), new_name);
@@ -144,6 +180,11 @@ pub fn compile_book(
         }
     }
 
+    // The entries refer to each other by these names.
+    if ctx.failed {
+        return Err(GenericCompilationToHVMError);
+    }
+
     for (_name, entry) in &book.entrs {
         compile_entry(&mut ctx, entry);
     }
@@ -152,7 +193,7 @@ pub fn compile_book(
         return Err(GenericCompilationToHVMError);
     }
 
-    Ok(ctx.file)
+    Ok((ctx.file, ctx.renamings))
 }
 
 pub fn compile_rule(ctx: &mut CompileCtx, rule: &untyped::Rule) -> kdl::Rule {
@@ -469,7 +510,8 @@ pub fn compile_entry(ctx: &mut CompileCtx, entry: &untyped::Entry) {
             ctx.send_err(Box::new(KdlDiagnostic::ShouldHaveOnlyOneRule(entry.range)));
         } else {
             let expr = compile_expr(ctx, &entry.rules[0].body);
-            let statement = kdl::Statement::Run { expr, sign: None };
+            let sign = ctx.sign(entry);
+            let statement = kdl::Statement::Run { expr, sign };
             ctx.file.runs.push(statement);
         }
     } else {
@@ -497,7 +539,7 @@ fn compile_common_function(ctx: &mut CompileCtx, entry: &untyped::Entry) {
         let sttm = kdl::Statement::Ctr {
             name,
             args,
-            sign: None,
+            sign: ctx.sign(entry),
         };
         ctx.file.ctrs.insert(entry.name.to_string(), sttm);
     } else {
@@ -507,6 +549,13 @@ fn compile_common_function(ctx: &mut CompileCtx, entry: &untyped::Entry) {
             .iter()
             .map(|rule| compile_rule(ctx, rule))
             .collect::<Vec<_>>();
+
+        for (idx, (name, range, strict)) in entry.args.iter().enumerate() {
+            if *strict && !is_matched(&rules, idx) {
+                ctx.send_err(Box::new(KdlDiagnostic::CannotForceArgument(name.clone(), *range)));
+            }
+        }
+
         let func = kdl::Func { rules };
 
         let init = if let Some(state_name) = &entry.attrs.kdl_state {
@@ -535,12 +584,22 @@ fn compile_common_function(ctx: &mut CompileCtx, entry: &untyped::Entry) {
             args,
             func,
             init,
-            sign: None,
+            sign: ctx.sign(entry),
         };
         ctx.file.funs.insert(entry.name.to_string(), sttm);
     }
 }
 
+/// Kindelia only evaluates the arguments that are matched against a
+/// constructor or a number before applying a rule, and it has no other
+/// way to make an argument strict.
+fn is_matched(rules: &[kdl::Rule], idx: usize) -> bool {
+    rules.iter().any(|rule| match &rule.lhs {
+        kdl::Term::Fun { args, .. } => matches!(args.get(idx), Some(kdl::Term::Ctr { .. } | kdl::Term::Num { .. })),
+        _ => false,
+    })
+}
+
 fn compile_u120_new(ctx: &mut CompileCtx, entry: &untyped::Entry) {
     // U120.new hi lo = (hi << 60) | lo
     let hi_name = from_str("hi").unwrap();
@@ -1,3 +1,4 @@
+use kind_report::codes;
 use kind_report::data::{Color, Diagnostic, DiagnosticFrame, Marker, Severity};
 use kind_span::Range;
 
@@ -7,6 +8,8 @@ pub enum KdlDiagnostic {
     ShouldHaveOnlyOneRule(Range),
     NoInitEntry(Range),
     FloatUsed(Range),
+    MissingSignature(String, Range),
+    CannotForceArgument(String, Range),
 }
 
 impl Diagnostic for KdlDiagnostic {
@@ -17,17 +20,20 @@ impl Diagnostic for KdlDiagnostic {
             KdlDiagnostic::ShouldHaveOnlyOneRule(range) => Some(range.ctx),
             KdlDiagnostic::NoInitEntry(range) => Some(range.ctx),
             KdlDiagnostic::FloatUsed(range) => Some(range.ctx),
+            KdlDiagnostic::MissingSignature(_, range) => Some(range.ctx),
+            KdlDiagnostic::CannotForceArgument(_, range) => Some(range.ctx),
         }
     }
 
     fn to_diagnostic_frame(&self) -> kind_report::data::DiagnosticFrame {
         match self {
             KdlDiagnostic::InvalidVarName(s, range) => DiagnosticFrame {
-                code: 600,
+                code: codes::INVALID_VAR_NAME,
                 severity: Severity::Error,
                 title: format!("Invalid variable name '{s}' for Kindelia."),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -37,11 +43,12 @@ impl Diagnostic for KdlDiagnostic {
                 }],
             },
             KdlDiagnostic::ShouldNotHaveArguments(range) => DiagnosticFrame {
-                code: 601,
+                code: codes::SHOULD_NOT_HAVE_ARGUMENTS,
                 severity: Severity::Error,
                 title: "This type of entry should not have arguments".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -51,11 +58,12 @@ impl Diagnostic for KdlDiagnostic {
                 }],
             },
             KdlDiagnostic::ShouldHaveOnlyOneRule(range) => DiagnosticFrame {
-                code: 603,
+                code: codes::SHOULD_HAVE_ONLY_ONE_RULE,
                 severity: Severity::Error,
                 title: "This entry should only have one rule.".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -65,11 +73,12 @@ impl Diagnostic for KdlDiagnostic {
                 }],
             },
             KdlDiagnostic::NoInitEntry(range) => DiagnosticFrame {
-                code: 604,
+                code: codes::NO_INIT_ENTRY,
                 severity: Severity::Error,
                 title: "This entry must have a init entry".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -79,11 +88,42 @@ impl Diagnostic for KdlDiagnostic {
                 }],
             },
             KdlDiagnostic::FloatUsed(range) => DiagnosticFrame {
-                code: 605,
+                code: codes::FLOAT_USED,
                 severity: Severity::Error,
                 title: "Found F60 in kindelia program".to_string(),
                 subtitles: vec![],
                 hints: vec![],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            KdlDiagnostic::MissingSignature(name, range) => DiagnosticFrame {
+                code: codes::MISSING_SIGNATURE,
+                severity: Severity::Error,
+                title: format!("Cannot find the signature of '{}'.", name),
+                subtitles: vec![],
+                hints: vec!["The compiler does not sign statements, so give the signature made by the Kindelia client with `--signatures`.".to_string()],
+                suggestions: vec![],
+                positions: vec![Marker {
+                    position: *range,
+                    color: Color::Fst,
+                    text: "Here!".to_string(),
+                    no_code: false,
+                    main: true,
+                }],
+            },
+            KdlDiagnostic::CannotForceArgument(name, range) => DiagnosticFrame {
+                code: codes::CANNOT_FORCE_ARGUMENT,
+                severity: Severity::Error,
+                title: format!("Kindelia cannot evaluate the argument '{}' strictly.", name),
+                subtitles: vec![],
+                hints: vec!["Kindelia only evaluates the arguments that are matched against a constructor or a number.".to_string()],
+                suggestions: vec![],
                 positions: vec![Marker {
                     position: *range,
                     color: Color::Fst,
@@ -102,7 +142,9 @@ impl Diagnostic for KdlDiagnostic {
             | ShouldNotHaveArguments(_)
             | ShouldHaveOnlyOneRule(_)
             | NoInitEntry(_)
-            | FloatUsed(_) => Severity::Error
+            | FloatUsed(_)
+            | CannotForceArgument(_, _)
+            | MissingSignature(_, _) => Severity::Error,
         }
     }
 }
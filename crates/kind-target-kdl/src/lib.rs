@@ -5,11 +5,15 @@ use kind_report::data::Diagnostic;
 use kind_tree::untyped;
 
 pub use compile::File;
+pub use signature::{parse_signatures, Signatures};
+pub use stats::{Stats, StatsFormat};
 
 mod compile;
 mod diagnostic;
 mod flatten;
 mod linearize;
+mod signature;
+mod stats;
 mod subst;
 
 #[derive(Debug)]
@@ -27,13 +31,27 @@ pub fn compile_book(
     book: untyped::Book,
     sender: Sender<Box<dyn Diagnostic>>,
     namespace: &str,
+    signatures: &Signatures,
 ) -> Result<compile::File, GenericCompilationToHVMError> {
+    compile_book_with_stats(book, sender, namespace, signatures).map(|(file, _)| file)
+}
+
+/// Compiles the book and measures the size and cost of each
+/// compiled function.
+pub fn compile_book_with_stats(
+    book: untyped::Book,
+    sender: Sender<Box<dyn Diagnostic>>,
+    namespace: &str,
+    signatures: &Signatures,
+) -> Result<(compile::File, Stats), GenericCompilationToHVMError> {
     // TODO: Remove kdl_states (maybe check if they're ever called?)
-    // TODO: Convert to some sort of Kindelia.Contract
     let flattened = flatten(book);
 
-    let file = compile::compile_book(&flattened, sender, namespace)?;
+    let (file, renamings) = compile::compile_book(&flattened, sender, namespace, signatures)?;
+    let dups = stats::count_file_dups(&file);
 
     let file = linearize::linearize_file(file);
-    Ok(file)
+    let stats = Stats::new(&file, &dups, renamings);
+
+    Ok((file, stats))
 }
@@ -77,11 +77,11 @@ impl LinearizeCtx {
 pub fn linearize_file(file: File) -> File {
     let mut runs = Vec::new();
     for stmt in file.runs {
-        if let Statement::Run { expr, sign: _ } = stmt {
+        if let Statement::Run { expr, sign } = stmt {
             let expr = linearize_term_independent(&expr);
             let stmt = Statement::Run {
                 expr: *expr,
-                sign: None,
+                sign,
             };
             runs.push(stmt);
         } else {
@@ -95,7 +95,7 @@ pub fn linearize_file(file: File) -> File {
             args,
             func,
             init,
-            sign: _,
+            sign,
         } = stmt
         {
             let init = init.map(|x| *linearize_term_independent(&x));
@@ -110,7 +110,7 @@ pub fn linearize_file(file: File) -> File {
                 args,
                 func,
                 init,
-                sign: None,
+                sign,
             };
             funs.insert(kind_name, stmt);
         } else {
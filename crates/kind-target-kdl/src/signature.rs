@@ -0,0 +1,44 @@
+//! Signatures of Kindelia statements. The compiler does not sign
+//! statements: the hash that Kindelia checks depends on the binary
+//! serialization of the statement, which is done by the Kindelia
+//! client. The statements of the entries marked with `#kdl_sign`
+//! only receive the signatures that were already made for them and
+//! stored in a local file, where each line contains the name of an
+//! entry and the hex of its signature:
+//!
+//! ```text
+//! // Comments and empty lines are ignored
+//! Counter 00a1b2...
+//! ```
+//!
+//! An entry without a signature in the file is an error, and the
+//! statements are not generated.
+
+use fxhash::FxHashMap;
+
+use crate::compile::kdl;
+
+pub type Signatures = FxHashMap<String, kdl::Signature>;
+
+pub fn parse_signatures(code: &str) -> Result<Signatures, String> {
+    let mut signatures = Signatures::default();
+
+    for (i, line) in code.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        let (name, hex) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("Line {} should contain a name and a signature.", i + 1))?;
+
+        let signature = kdl::Signature::from_hex(hex.trim())
+            .ok_or_else(|| format!("Invalid signature for '{}' in line {}.", name, i + 1))?;
+
+        signatures.insert(name.to_string(), signature);
+    }
+
+    Ok(signatures)
+}
@@ -0,0 +1,177 @@
+//! Size and cost report of the compiled statements. Deploying
+//! to Kindelia is paid per byte, so this module measures the
+//! output of the compiler per function.
+
+use std::fmt::Display;
+
+use fxhash::FxHashMap;
+use kindelia_lang::ast::{Statement, Term};
+use serde::Serialize;
+
+use crate::File;
+
+/// Format of the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Table,
+    Json,
+}
+
+/// A name that was changed by the name shortener because it
+/// is longer than the limit of Kindelia names.
+#[derive(Debug, Clone, Serialize)]
+pub struct Renaming {
+    pub name: String,
+    pub kdl_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionStats {
+    pub name: String,
+    pub kdl_name: String,
+    pub rules: usize,
+    /// Number of nodes in all of the rules and in the initial state.
+    pub size: usize,
+    /// Number of `dup` nodes introduced by the linearization.
+    pub dups: usize,
+    /// Size of the serialized statement.
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Stats {
+    pub functions: Vec<FunctionStats>,
+    pub renamings: Vec<Renaming>,
+    /// Size of the entire serialized file.
+    pub bytes: usize,
+}
+
+fn term_size(term: &Term) -> usize {
+    match term {
+        Term::Var { .. } | Term::Num { .. } => 1,
+        Term::Lam { body, .. } => 1 + term_size(body),
+        Term::App { func, argm } => 1 + term_size(func) + term_size(argm),
+        Term::Dup { expr, body, .. } => 1 + term_size(expr) + term_size(body),
+        Term::Ctr { args, .. } | Term::Fun { args, .. } => 1 + args.iter().map(term_size).sum::<usize>(),
+        Term::Op2 { val0, val1, .. } => 1 + term_size(val0) + term_size(val1),
+    }
+}
+
+fn count_dups(term: &Term) -> usize {
+    match term {
+        Term::Var { .. } | Term::Num { .. } => 0,
+        Term::Lam { body, .. } => count_dups(body),
+        Term::App { func, argm } => count_dups(func) + count_dups(argm),
+        Term::Dup { expr, body, .. } => 1 + count_dups(expr) + count_dups(body),
+        Term::Ctr { args, .. } | Term::Fun { args, .. } => args.iter().map(count_dups).sum(),
+        Term::Op2 { val0, val1, .. } => count_dups(val0) + count_dups(val1),
+    }
+}
+
+/// All of the terms inside of a statement.
+fn statement_terms(statement: &Statement) -> Vec<&Term> {
+    match statement {
+        Statement::Fun { func, init, .. } => func
+            .rules
+            .iter()
+            .flat_map(|rule| [&rule.lhs, &rule.rhs])
+            .chain(init.iter())
+            .collect(),
+        Statement::Run { expr, .. } => vec![expr],
+        _ => vec![],
+    }
+}
+
+fn statement_dups(statement: &Statement) -> usize {
+    statement_terms(statement).into_iter().map(count_dups).sum()
+}
+
+/// Number of `dup` nodes in each function of a file. It's used before the
+/// linearization to know how many of them were introduced by it.
+pub fn count_file_dups(file: &File) -> FxHashMap<String, usize> {
+    file.funs
+        .iter()
+        .map(|(name, statement)| (name.clone(), statement_dups(statement)))
+        .collect()
+}
+
+impl Stats {
+    /// Measures a file that was already linearized. The functions and
+    /// the renamings are sorted by name, so the report does not depend
+    /// on the order of the definitions in the book.
+    pub fn new(file: &File, dups_before: &FxHashMap<String, usize>, mut renamings: Vec<Renaming>) -> Stats {
+        let mut functions = Vec::new();
+
+        for (name, statement) in &file.funs {
+            if let Statement::Fun { name: kdl_name, func, .. } = statement {
+                let dups_before = dups_before.get(name).copied().unwrap_or(0);
+
+                functions.push(FunctionStats {
+                    name: name.clone(),
+                    kdl_name: kdl_name.to_string(),
+                    rules: func.rules.len(),
+                    size: statement_terms(statement).into_iter().map(term_size).sum(),
+                    dups: statement_dups(statement).saturating_sub(dups_before),
+                    bytes: statement.to_string().len(),
+                })
+            }
+        }
+
+        functions.sort_by(|x, y| x.name.cmp(&y.name));
+        renamings.sort_by(|x, y| x.name.cmp(&y.name));
+
+        Stats {
+            functions,
+            renamings,
+            bytes: file.to_string().len(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn render(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Table => self.to_string(),
+            StatsFormat::Json => self.to_json(),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .functions
+            .iter()
+            .map(|fun| fun.name.len())
+            .chain(std::iter::once("Function".len()))
+            .max()
+            .unwrap_or_default();
+
+        writeln!(
+            f,
+            "{:<width$}  {:<12}  {:>5}  {:>6}  {:>5}  {:>7}",
+            "Function", "Name", "Rules", "Size", "Dups", "Bytes"
+        )?;
+
+        for fun in &self.functions {
+            writeln!(
+                f,
+                "{:<width$}  {:<12}  {:>5}  {:>6}  {:>5}  {:>7}",
+                fun.name, fun.kdl_name, fun.rules, fun.size, fun.dups, fun.bytes
+            )?;
+        }
+
+        if !self.renamings.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Shortened names:")?;
+            for renaming in &self.renamings {
+                writeln!(f, "  {} -> {}", renaming.name, renaming.kdl_name)?;
+            }
+        }
+
+        writeln!(f)?;
+        write!(f, "Total: {} bytes", self.bytes)
+    }
+}
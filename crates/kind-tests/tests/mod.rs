@@ -1,13 +1,17 @@
 #![feature(result_flattening)]
 
+use kind_checker::{EvalError, EvalLimits};
+use kind_driver::backend::{BackendOptions, HvmBackend, KdlBackend};
 use kind_driver::session::Session;
-use kind_report::data::Diagnostic;
-use kind_report::report::Report;
+use kind_report::data::collect_diagnostics;
+use kind_report::report::{line_and_column, parse_position, FileCache, Report};
+use kind_report::sarif::render_sarif;
 use kind_report::RenderConfig;
 
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use ntest::timeout;
 use pretty_assertions::assert_eq;
@@ -27,6 +31,30 @@ fn golden_test(path: &Path, run: &dyn Fn(&Path) -> String) {
     }
 }
 
+/// A directory in the temporary directory of the system that is only
+/// used by one test of one run, and that is removed when it's dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("kind2-{}-{}-{}", name, std::process::id(), id));
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
 fn test_kind2(path: &Path, run: fn(&PathBuf, &mut Session) -> Option<String>) -> Result<(), Error> {
     for entry in WalkDir::new(path).follow_links(true) {
         let entry = entry?;
@@ -39,7 +67,7 @@ fn test_kind2(path: &Path, run: fn(&PathBuf, &mut Session) -> Option<String>) ->
 
                 let res = run(&PathBuf::from(path), &mut session);
 
-                let diagnostics = tx.try_iter().collect::<Vec<Box<dyn Diagnostic>>>();
+                let diagnostics = collect_diagnostics(tx.try_iter(), &session);
                 let render = RenderConfig::ascii(2);
 
                 kind_report::check_if_colors_are_supported(true);
@@ -62,25 +90,144 @@ fn test_kind2(path: &Path, run: fn(&PathBuf, &mut Session) -> Option<String>) ->
     Ok(())
 }
 
+#[test]
+#[timeout(15000)]
+fn test_fix() -> Result<(), Error> {
+    for entry in WalkDir::new("./suite/fix").follow_links(true) {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_file() && path.extension().map(|x| x == "kind2").unwrap_or(false) {
+            golden_test(path, &|path| {
+                let (rx, tx) = std::sync::mpsc::channel();
+                let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+                let mut session = Session::new(root, rx);
+
+                let _ = driver::desugar_book(&mut session, &PathBuf::from(path));
+
+                let suggestions = tx
+                    .try_iter()
+                    .flat_map(|diag| diag.to_diagnostic_frame().suggestions)
+                    .filter(|suggestion| suggestion.position.ctx.0 == 0)
+                    .collect::<Vec<_>>();
+
+                driver::fix::apply_suggestions(&session.loaded_sources[0], &suggestions).0
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `fix` only rewrites the file that it was given, even if a dependency
+/// that was loaded from the root has a suggestion too.
+#[test]
+#[timeout(15000)]
+fn test_fix_keeps_dependencies() {
+    let dir = TempDir::new("fix-dependencies");
+    let root = dir.path().to_path_buf();
+    fs::create_dir_all(root.join("Dep")).unwrap();
+
+    let dependency = "Dep.Add (a: U60) (b: U60) : U60\nDep.Add a b = (+ a b)\n\nDep.Add.one : U60\nDep.Add.one = Dep.Add 1\n";
+    let dependency_path = root.join("Dep").join("Add.kind2");
+    fs::write(&dependency_path, dependency).unwrap();
+
+    let path = root.join("Main.kind2");
+    fs::write(&path, "Main : U60\nMain = (+ (Dep.Add 2) Dep.Add.one)\n").unwrap();
+
+    let (rx, tx) = std::sync::mpsc::channel();
+    let mut session = Session::new(root.clone(), rx);
+    let _ = driver::desugar_book(&mut session, &path);
+    let diagnostics = collect_diagnostics(tx.try_iter(), &session);
+
+    assert_eq!(driver::fix::fix_file(&session, &path, &diagnostics).unwrap(), 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "Main : U60\nMain = (+ (Dep.Add 2 _) Dep.Add.one)\n");
+    assert_eq!(fs::read_to_string(&dependency_path).unwrap(), dependency);
+}
+
+/// The diagnostics are sorted by the path of their files and not by
+/// the order in which the files were loaded.
+#[test]
+#[timeout(15000)]
+fn test_diagnostics_sorted_by_path() {
+    let dir = TempDir::new("sorted-diagnostics");
+    let root = dir.path().to_path_buf();
+    fs::create_dir_all(root.join("Dep")).unwrap();
+
+    let dependency_path = root.join("Dep").join("Broken.kind2");
+    fs::write(&dependency_path, "Dep.Broken : U60\nDep.Broken = Dep.Broken.missing\n").unwrap();
+
+    let path = root.join("Main.kind2");
+    fs::write(&path, "Main : U60\nMain = (+ Dep.Broken Main.missing)\n").unwrap();
+
+    let (rx, tx) = std::sync::mpsc::channel();
+    let mut session = Session::new(root.clone(), rx);
+    let _ = driver::desugar_book(&mut session, &path);
+    let diagnostics = collect_diagnostics(tx.try_iter(), &session);
+
+    let paths: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| session.fetch(diagnostic.get_syntax_ctx().unwrap()).unwrap().0)
+        .collect();
+
+    let root = root.canonicalize().unwrap();
+    assert_eq!(paths, vec![root.join("Dep").join("Broken.kind2"), root.join("Main.kind2")]);
+}
+
+#[test]
+#[timeout(15000)]
+fn test_sarif() -> Result<(), Error> {
+    for entry in WalkDir::new("./suite/sarif").follow_links(true) {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_file() && path.extension().map(|x| x == "kind2").unwrap_or(false) {
+            golden_test(path, &|path| {
+                let (rx, tx) = std::sync::mpsc::channel();
+                let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+                let mut session = Session::new(root, rx);
+                session.emit_warnings = true;
+
+                let _ = driver::desugar_book(&mut session, &PathBuf::from(path));
+
+                let diagnostics = collect_diagnostics(tx.try_iter(), &session);
+                render_sarif(&diagnostics, &session)
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_main(path: &PathBuf, session: &mut Session) -> Option<String> {
+    let entrypoints = vec!["Main".to_string()];
+    let check = driver::type_check_book(&HvmBackend, session, path, entrypoints, Some(1), true);
+    check.map(|_| "Ok!".to_string()).ok()
+}
+
+/// The derivations are checked by [test_checker_derive] because they
+/// generate a lot of definitions and take longer to check.
+const DERIVE_SUITE: &str = "./suite/checker/derive";
+
 #[test]
 #[timeout(30000)]
 fn test_checker() -> Result<(), Error> {
-    test_kind2(Path::new("./suite/checker"), |path, session| {
-        let entrypoints = vec!["Main".to_string()];
-        let check = driver::type_check_book(session, path, entrypoints, Some(1), true);
-        check.map(|_| "Ok!".to_string()).ok()
-    })?;
+    for entry in WalkDir::new("./suite/checker").min_depth(1).max_depth(1) {
+        let entry = entry?;
+        if entry.path() != Path::new(DERIVE_SUITE) {
+            test_kind2(entry.path(), check_main)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[timeout(60000)]
+fn test_checker_derive() -> Result<(), Error> {
+    test_kind2(Path::new(DERIVE_SUITE), check_main)?;
     Ok(())
 }
 
 #[test]
 #[timeout(30000)]
 fn test_checker_issues() -> Result<(), Error> {
-    test_kind2(Path::new("./suite/issues/checker"), |path, session| {
-        let entrypoints = vec!["Main".to_string()];
-        let check = driver::type_check_book(session, path, entrypoints, Some(1), true);
-        check.map(|_| "Ok!".to_string()).ok()
-    })?;
+    test_kind2(Path::new("./suite/issues/checker"), check_main)?;
     Ok(())
 }
 
@@ -89,22 +236,54 @@ fn test_checker_issues() -> Result<(), Error> {
 fn test_run() -> Result<(), Error> {
     test_kind2(Path::new("./suite/run"), |path, session| {
         let entrypoints = vec!["Main".to_string()];
-        let check = driver::erase_book(session, path, entrypoints)
-            .map(|file| driver::compile_book_to_hvm(file, false))
-            .map(|file| driver::execute_file(&file.to_string(), Some(1)))
-            .flatten();
+        let options = BackendOptions::default();
+        let check = driver::run_book(session, path, entrypoints, &options, Some(1), EvalLimits::default());
 
         check.ok().map(|x| x.0)
     })?;
     Ok(())
 }
 
+/// The programs of `suite/run` that the checker rejects on purpose,
+/// to test how they run anyway.
+const UNCHECKED_RUNS: &[&str] = &["MatchMotive", "NoMatch", "User", "VecMatch"];
+
+#[test]
+#[timeout(60000)]
+fn test_run_type_checks() -> Result<(), Error> {
+    for entry in WalkDir::new("./suite/run").follow_links(true) {
+        let entry = entry?;
+        let path = entry.path();
+        let name = path.file_stem().and_then(|x| x.to_str()).unwrap_or_default();
+
+        if path.extension().map(|x| x == "kind2").unwrap_or(false) && !UNCHECKED_RUNS.contains(&name) {
+            let (rx, _tx) = std::sync::mpsc::channel();
+            let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+            let mut session = Session::new(root, rx);
+
+            let entrypoints = vec!["Main".to_string()];
+            let check = driver::type_check_book(&HvmBackend, &mut session, &PathBuf::from(path), entrypoints, Some(1), true);
+            assert!(check.is_ok(), "The file '{}' does not type check", path.display());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[timeout(15000)]
+fn test_show_expanded() -> Result<(), Error> {
+    test_kind2(Path::new("./suite/show"), |path, session| {
+        driver::show_expanded_book(session, path).ok()
+    })?;
+    Ok(())
+}
+
 #[test]
 #[timeout(15000)]
 fn test_eval() -> Result<(), Error> {
     test_kind2(Path::new("./suite/eval"), |path, session| {
         let check = driver::desugar_book(session, path)
-            .map(|file| driver::eval_in_checker(&file));
+            .and_then(|file| driver::eval_in_checker(session, &file, EvalLimits::default()));
 
         check.ok().map(|x| x.0)
     })?;
@@ -116,7 +295,7 @@ fn test_eval() -> Result<(), Error> {
 fn test_eval_issues() -> Result<(), Error> {
     test_kind2(Path::new("./suite/issues/eval"), |path, session| {
         let check = driver::desugar_book(session, path)
-            .map(|file| driver::eval_in_checker(&file));
+            .and_then(|file| driver::eval_in_checker(session, &file, EvalLimits::default()));
 
         check.ok().map(|x| x.0)
     })?;
@@ -128,33 +307,244 @@ fn test_eval_issues() -> Result<(), Error> {
 fn test_run_issues() -> Result<(), Error> {
     test_kind2(Path::new("./suite/issues/run"), |path, session| {
         let entrypoints = vec!["Main".to_string()];
-        let check = driver::erase_book(session, path, entrypoints)
-            .map(|file| driver::compile_book_to_hvm(file, false))
-            .map(|file| driver::execute_file(&file.to_string(), Some(1)))
-            .flatten();
+        let options = BackendOptions::default();
+        let check = driver::run_book(session, path, entrypoints, &options, Some(1), EvalLimits::default());
 
         check.ok().map(|x| x.0)
     })?;
     Ok(())
 }
 
+/// The evaluation performs a few rewrites that are not applications of
+/// functions after it crosses a limit, so these tests only check the
+/// diagnostic codes.
+fn test_limits(path: &Path, limits: EvalLimits, code: u32) -> Result<(), Error> {
+    for entry in WalkDir::new(path).follow_links(true) {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_file() && path.extension().map(|x| x == "kind2").unwrap_or(false) {
+            let (rx, tx) = std::sync::mpsc::channel();
+            let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+            let mut session = Session::new(root, rx);
+
+            let entrypoints = vec!["Main".to_string()];
+            let options = BackendOptions::default();
+            let res = driver::run_book(&mut session, &PathBuf::from(path), entrypoints, &options, Some(1), limits);
+
+            let codes = tx
+                .try_iter()
+                .map(|diag| diag.to_diagnostic_frame().code)
+                .collect::<Vec<u32>>();
+
+            assert!(res.is_err(), "Testing file '{}'", path.display());
+            assert_eq!(codes, vec![code], "Testing file '{}'", path.display());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[timeout(15000)]
+fn test_run_limits() -> Result<(), Error> {
+    let limits = EvalLimits { max_rewrites: Some(100000), heap_size: None };
+    test_limits(Path::new("./suite/limits/rewrites"), limits, 104)?;
+
+    let limits = EvalLimits { max_rewrites: None, heap_size: Some(1 << 16) };
+    test_limits(Path::new("./suite/limits/heap"), limits, 105)?;
+    Ok(())
+}
+
+/// The derivations written in Kind run with the limits of the session,
+/// and the limit that stops one is reported at its `#derive`.
+#[test]
+#[timeout(30000)]
+fn test_derivation_limits() {
+    let cases = [
+        ("Rewrites", EvalLimits { max_rewrites: Some(100000), heap_size: None }, 104),
+        ("Heap", EvalLimits { max_rewrites: None, heap_size: Some(1 << 16) }, 105),
+    ];
+
+    for (name, limits, code) in cases {
+        let path = PathBuf::from(format!("./suite/limits/derive/{}.kind2", name));
+        let (rx, tx) = std::sync::mpsc::channel();
+        let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+        let mut session = Session::new(root, rx);
+        session.eval_limits = limits;
+
+        assert_eq!(check_main(&path, &mut session), None, "Testing file '{}'", path.display());
+
+        let frames = tx.try_iter().map(|diag| diag.to_diagnostic_frame()).collect::<Vec<_>>();
+        let codes = frames.iter().map(|frame| frame.code).collect::<Vec<_>>();
+        assert_eq!(codes, vec![code], "Testing file '{}'", path.display());
+
+        let source = fs::read_to_string(&path).unwrap();
+        let range = frames[0].positions[0].position;
+        assert_eq!(&source[range.start.index as usize..range.end.index as usize], "Derive.Loop");
+    }
+}
+
+/// An evaluation that crosses a limit must stop, instead of running in
+/// the background, so the ones that come after it are not slowed down.
+#[test]
+#[timeout(15000)]
+fn test_limits_stop_evaluation() {
+    let file = "(Loop n) = (Loop (+ n 1))\n(Grow xs) = (Grow (Cons 0 xs))";
+    for _ in 0..50 {
+        let limits = EvalLimits { max_rewrites: Some(100000), heap_size: None };
+        let res = kind_checker::eval_with_limits(file, "(Loop 0)", false, Some(1), limits);
+        assert!(matches!(res, Err(EvalError::RewriteLimit(100000, _))));
+
+        let limits = EvalLimits { max_rewrites: None, heap_size: Some(1 << 16) };
+        let res = kind_checker::eval_with_limits(file, "(Grow Nil)", false, Some(1), limits);
+        assert!(matches!(res, Err(EvalError::HeapLimit(65536, _))));
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn test_warnings() -> Result<(), Error> {
+    test_kind2(Path::new("./suite/warnings"), |path, session| {
+        session.emit_warnings = true;
+        let check = driver::to_book(session, path);
+        check.map(|_| "Ok!".to_string()).ok()
+    })?;
+    Ok(())
+}
+
+#[test]
+#[timeout(15000)]
+fn test_lints() -> Result<(), Error> {
+    test_kind2(Path::new("./suite/lint"), |path, session| {
+        session.emit_warnings = true;
+        let check = driver::desugar_book(session, path);
+        check.map(|_| "Ok!".to_string()).ok()
+    })?;
+    Ok(())
+}
+
+/// The definitions that use names that cannot be resolved are skipped
+/// and the other ones are still checked.
+#[test]
+#[timeout(30000)]
+fn test_types_skip_unresolved() {
+    let (rx, tx) = std::sync::mpsc::channel();
+    let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+    let mut session = Session::new(root, rx);
+
+    let path = PathBuf::from("./suite/recovery/Unresolved.kind2");
+    let types = driver::infer_types(&mut session, &path, Some(1)).unwrap();
+
+    let code = &session.loaded_sources[0];
+    let lines = types
+        .iter()
+        .map(|info| line_and_column(code, info.range.start.index as usize).0)
+        .collect::<Vec<_>>();
+
+    assert!(lines.contains(&2), "The types of 'Double' are missing");
+    assert!(lines.contains(&9), "The types of 'Main' are missing");
+    assert!(lines.iter().all(|line| *line != 6), "'Quad' should not be checked");
+
+    let codes = tx.try_iter().map(|diag| diag.to_diagnostic_frame().code).collect::<Vec<_>>();
+    assert_eq!(codes, vec![kind_report::codes::UNBOUND_VARIABLE]);
+}
+
+#[test]
+#[timeout(30000)]
+fn test_types() -> Result<(), Error> {
+    test_kind2(Path::new("./suite/types"), |path, session| {
+        let types = driver::infer_types(session, path, Some(1)).ok()?;
+        let code = &session.loaded_sources[0];
+
+        let mut res = String::new();
+        for info in types {
+            let (start, end) = (info.range.start.index as usize, info.range.end.index as usize);
+            let (line, column) = line_and_column(code, start);
+            let expr = code[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+            res.push_str(&format!("{}:{} {} : {}\n", line, column, expr, info.typ));
+        }
+        Some(res)
+    })?;
+    Ok(())
+}
+
+#[test]
+#[timeout(30000)]
+fn test_type_at() {
+    let (rx, _tx) = std::sync::mpsc::channel();
+    let root = PathBuf::from("./suite/lib").canonicalize().unwrap();
+    let mut session = Session::new(root, rx);
+
+    let path = PathBuf::from("./suite/types/Inference.kind2");
+    let types = driver::infer_types(&mut session, &path, Some(1)).unwrap();
+
+    let type_at = |position: &str| {
+        let position = parse_position(position).unwrap();
+        driver::type_at(&session, &types, position).map(|info| info.typ.clone())
+    };
+
+    // The innermost expression wins and constructors have their value type.
+    assert_eq!(type_at("16:7").as_deref(), Some("((x_1 : U60) -> U60)"));
+    assert_eq!(type_at("16:10").as_deref(), Some("U60"));
+    assert_eq!(type_at("2:3").as_deref(), Some("Nat"));
+    assert_eq!(type_at("3:6").as_deref(), Some("((pred : Nat) -> Nat)"));
+
+    assert_eq!(type_at("4:1"), None);
+    assert_eq!(type_at("40:1"), None);
+    assert_eq!(type_at("16:100"), None);
+
+    assert!(parse_position("0:1").is_err());
+    assert!(parse_position("16").is_err());
+}
+
 #[test]
 #[timeout(15000)]
 fn test_kdl() -> Result<(), Error> {
     test_kind2(Path::new("./suite/kdl"), |path, session| {
         let entrypoints = vec!["Main".to_string()];
-        let check = driver::compile_book_to_kdl(path, session, "", entrypoints);
+        // A test can come with the signatures of its statements.
+        let signatures = path.with_extension("signatures");
+        let mut options = BackendOptions::default();
+        if signatures.exists() {
+            options = options.with_flag("signatures", signatures.to_string_lossy());
+        }
+        let check = driver::compile_book_to_backend(&KdlBackend, session, path, entrypoints, &options);
+        check.ok().map(|x| x.to_string())
+    })?;
+    Ok(())
+}
+
+#[test]
+#[timeout(15000)]
+fn test_hvm() -> Result<(), Error> {
+    test_kind2(Path::new("./suite/hvm"), |path, session| {
+        let entrypoints = vec!["Main".to_string()];
+        let options = BackendOptions::default();
+        let check = driver::compile_book_to_backend(&HvmBackend, session, path, entrypoints, &options);
         check.ok().map(|x| x.to_string())
     })?;
     Ok(())
 }
 
+#[test]
+#[timeout(15000)]
+fn test_kdl_stats() -> Result<(), Error> {
+    test_kind2(Path::new("./suite/kdl-stats"), |path, session| {
+        let entrypoints = vec!["Main".to_string()];
+        // The fixtures whose name ends with `Json` test the JSON report.
+        let json = path.file_stem().is_some_and(|stem| stem.to_string_lossy().ends_with("Json"));
+        let options = BackendOptions::default().with_flag("stats", if json { "json" } else { "table" });
+        let check = driver::compile_book_to_backend_with_report(&KdlBackend, session, path, entrypoints, &options);
+        check.ok().and_then(|(_, report)| report)
+    })?;
+    Ok(())
+}
+
 #[test]
 #[timeout(15000)]
 fn test_erasure() -> Result<(), Error> {
     test_kind2(Path::new("./suite/erasure"), |path, session| {
         let entrypoints = vec!["Main".to_string()];
-        let check = driver::erase_book(session, path, entrypoints).map(|file| file.to_string());
+        let check = driver::erase_book(&HvmBackend, session, path, entrypoints).map(|file| file.to_string());
         check.ok()
     })?;
     Ok(())
@@ -165,8 +555,120 @@ fn test_erasure() -> Result<(), Error> {
 fn test_coverage() -> Result<(), Error> {
     test_kind2(Path::new("./suite/issues/coverage"), |path, session| {
         let entrypoints = vec!["Main".to_string()];
-        let check = driver::type_check_book(session, path, entrypoints, Some(1), true);
+        let check = driver::type_check_book(&HvmBackend, session, path, entrypoints, Some(1), true);
         check.map(|_| "Ok!".to_string()).ok()
     })?;
     Ok(())
 }
+
+/// Writes an example of `kind2 explain` to `path`. The other files of
+/// the directory of the example follow it, each one after a line
+/// `// File: <path>`.
+fn write_example(dir: &Path, path: &Path, source: &str) {
+    let mut sections = source.split("\n// File: ");
+    fs::write(path, sections.next().unwrap()).unwrap();
+
+    for section in sections {
+        let (name, content) = section.split_once('\n').unwrap_or((section, ""));
+        let file = dir.join(name);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+}
+
+/// Compiles the source of an example of `kind2 explain` and returns
+/// the diagnostics that it emits. The examples of the errors of
+/// `--signatures` read the file `Main.signatures` and the ones of the
+/// evaluation limits run `Main` with small limits.
+fn explanation_diagnostics(code: u32, name: &str, source: &str) -> Vec<(u32, kind_report::data::Severity)> {
+    use kind_report::codes;
+
+    let temp = TempDir::new(&format!("explain-{}-{:04}", name, code));
+    let dir = temp.path().to_path_buf();
+    let path = dir.join(format!("E{:04}.kind2", code));
+    write_example(&dir, &path, source);
+
+    let (rx, tx) = std::sync::mpsc::channel();
+    let mut session = Session::new(dir.clone(), rx);
+    session.emit_warnings = true;
+    let entrypoints = vec!["Main".to_string()];
+
+    match code {
+        codes::INVALID_SIGNATURES | codes::CANNOT_FIND_FILE | codes::MISSING_SIGNATURE => {
+            let signatures = dir.join("Main.signatures");
+            let options = BackendOptions::default().with_flag("signatures", signatures.to_string_lossy());
+            let _ = driver::compile_book_to_backend(&KdlBackend, &mut session, &path, entrypoints, &options);
+        }
+        600..=699 => {
+            let options = BackendOptions::default();
+            let _ = driver::compile_book_to_backend(&KdlBackend, &mut session, &path, entrypoints, &options);
+        }
+        codes::REWRITE_LIMIT_EXCEEDED | codes::HEAP_LIMIT_EXCEEDED => {
+            let limits = if code == codes::REWRITE_LIMIT_EXCEEDED {
+                EvalLimits { max_rewrites: Some(100_000), heap_size: None }
+            } else {
+                EvalLimits { max_rewrites: None, heap_size: Some(10_000) }
+            };
+            let options = BackendOptions::default();
+            let _ = driver::run_book(&mut session, &path, entrypoints, &options, Some(1), limits);
+        }
+        codes::THERE_ISNT_A_MAIN => {
+            let _ = driver::erase_book(&HvmBackend, &mut session, &path, entrypoints)
+                .and_then(|book| driver::check_main_entry(&mut session, &book));
+        }
+        _ => {
+            let _ = driver::type_check_book(&HvmBackend, &mut session, &path, entrypoints, Some(1), true);
+        }
+    }
+
+    tx.try_iter()
+        .map(|diag| diag.to_diagnostic_frame())
+        .map(|frame| (frame.code, frame.severity))
+        .collect()
+}
+
+/// Every code of the registry has exactly one explanation and every
+/// explanation is of a code of the registry.
+#[test]
+fn test_codes_are_explained() {
+    use kind_report::codes::ALL;
+    use kind_report::explain::EXPLANATIONS;
+
+    let mut codes = ALL.to_vec();
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(codes.len(), ALL.len(), "There are repeated codes in the registry");
+
+    let mut explained = EXPLANATIONS.iter().map(|explanation| explanation.code).collect::<Vec<_>>();
+    explained.sort_unstable();
+
+    assert_eq!(explained, codes);
+}
+
+#[test]
+#[timeout(120000)]
+fn test_explanations() {
+    use kind_report::data::Severity;
+
+    let mut failures = Vec::new();
+
+    for explanation in kind_report::explain::EXPLANATIONS {
+        let code = explanation.code;
+
+        if let Some(bad) = explanation.bad {
+            let diagnostics = explanation_diagnostics(code, "bad", bad);
+            if !diagnostics.iter().any(|(got, _)| *got == code) {
+                failures.push(format!("The example of E{:04} emits {:?}", code, diagnostics));
+            }
+        }
+
+        if let Some(fixed) = explanation.fixed {
+            let diagnostics = explanation_diagnostics(code, "fixed", fixed);
+            if diagnostics.iter().any(|(got, severity)| *got == code || *severity == Severity::Error) {
+                failures.push(format!("The fixed example of E{:04} emits {:?}", code, diagnostics));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
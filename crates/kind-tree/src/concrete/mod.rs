@@ -161,6 +161,14 @@ impl TopLevel {
     pub fn is_definition(&self) -> bool {
         matches!(self, TopLevel::Entry(_))
     }
+
+    pub fn get_attrs(&self) -> &[Attribute] {
+        match self {
+            TopLevel::SumType(sum) => &sum.attrs,
+            TopLevel::RecordType(rec) => &rec.attrs,
+            TopLevel::Entry(entry) => &entry.attrs,
+        }
+    }
 }
 
 /// A module is a collection of top level entries
@@ -169,7 +177,8 @@ impl TopLevel {
 #[derive(Clone, Debug)]
 pub struct Module {
     pub entries: Vec<TopLevel>,
-    pub uses: FxHashMap<String, String>,
+    /// The aliases of the module and where they were declared.
+    pub uses: FxHashMap<String, (String, Range)>,
 }
 
 /// Metadata about entries, it's really useful when we
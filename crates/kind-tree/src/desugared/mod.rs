@@ -14,6 +14,8 @@ use crate::{
     Attributes, telescope::Telescope,
 };
 
+pub mod resugar;
+
 /// Just a vector of expressions. It is called spine because
 /// it is usually in a form like (a b c d e) that can be interpret
 /// as ((((a b) c) d) e) that looks like a spine.
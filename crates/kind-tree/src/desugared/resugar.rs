@@ -0,0 +1,287 @@
+//! Prints desugared expressions the way that they are written in
+//! the surface syntax. It recognizes the patterns that are generated
+//! by the desugarer (and by the type checker when it normalizes them):
+//! * `String.cons` chains become string literals
+//! * `List.cons` chains become list literals
+//! * `Sigma` and `Sigma.new` become sigma types and pairs
+//! * Calls to the `match` eliminators become `match` expressions
+
+use std::fmt::{Display, Error, Formatter};
+
+use fxhash::FxHashMap;
+
+use super::{try_desugar_to_nat, Book, Expr, ExprKind};
+
+/// A type that has a `match` eliminator.
+#[derive(Clone, Debug)]
+struct Eliminator {
+    typ: String,
+    /// Name of each constructor with the names of its fields in the
+    /// same order of the cases of the eliminator.
+    constructors: Vec<(String, Vec<String>)>,
+}
+
+/// The eliminators of a book indexed by their names, they're needed
+/// to print a call to an eliminator as a `match`.
+#[derive(Clone, Debug, Default)]
+pub struct Eliminators(FxHashMap<String, Eliminator>);
+
+impl Eliminators {
+    pub fn from_book(book: &Book) -> Eliminators {
+        let mut eliminators = FxHashMap::default();
+
+        for family in book.families.values() {
+            let match_id = family.name.add_segment("match").to_string();
+
+            let type_entry = match book.entrs.get(family.name.to_str()) {
+                Some(entry) if book.entrs.contains_key(&match_id) => entry,
+                _ => continue,
+            };
+
+            let params = family.parameters.len();
+            let indices = &type_entry.args[params..];
+
+            let mut constructors = Vec::new();
+
+            for constructor in &family.constructors {
+                let Some(entry) = book.entrs.get(constructor.to_str()) else {
+                    continue;
+                };
+
+                // The constructors without an explicit return type receive
+                // the indices of the type before their fields.
+                let receives_indices = entry.args.len() >= params + indices.len()
+                    && entry.args[params..params + indices.len()]
+                        .iter()
+                        .zip(indices)
+                        .all(|(arg, index)| arg.name.to_str() == index.name.to_str());
+
+                let skip = params + if receives_indices { indices.len() } else { 0 };
+
+                let fields = entry.args[skip..].iter().map(|arg| arg.name.to_string()).collect();
+                let name = constructor.to_str().rsplit('.').next().unwrap_or_default();
+
+                constructors.push((name.to_string(), fields));
+            }
+
+            eliminators.insert(
+                match_id,
+                Eliminator {
+                    typ: family.name.to_string(),
+                    constructors,
+                },
+            );
+        }
+
+        Eliminators(eliminators)
+    }
+}
+
+/// An expression that is displayed in the surface syntax. It's
+/// created by [Expr::resugar].
+pub struct Resugared<'a> {
+    expr: &'a Expr,
+    eliminators: &'a Eliminators,
+}
+
+impl Expr {
+    pub fn resugar<'a>(&'a self, eliminators: &'a Eliminators) -> Resugared<'a> {
+        Resugared { expr: self, eliminators }
+    }
+}
+
+impl<'a> Resugared<'a> {
+    fn with(&self, expr: &'a Expr) -> Resugared<'a> {
+        expr.resugar(self.eliminators)
+    }
+
+    fn spine(&self, args: &'a [Box<Expr>]) -> String {
+        args.iter().map(|x| format!(" {}", self.with(x))).collect()
+    }
+
+    fn fmt_pi_types(&self, expr: &'a Expr, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match &expr.data {
+            ExprKind::All { param, typ, body, erased } => {
+                let tilde = if *erased { "~" } else { "" };
+                if param.to_string().starts_with('_') {
+                    write!(f, "{}{} -> ", tilde, self.with(typ))?;
+                } else {
+                    write!(f, "{}({} : {}) -> ", tilde, param, self.with(typ))?;
+                }
+                self.fmt_pi_types(body, f)
+            }
+            _ => write!(f, "{}", self.with(expr)),
+        }
+    }
+
+    fn fmt_match(&self, name: &str, args: &'a [Box<Expr>], f: &mut Formatter<'_>) -> Result<bool, Error> {
+        let Some(eliminator) = self.eliminators.0.get(name) else {
+            return Ok(false);
+        };
+
+        let cases = eliminator.constructors.len();
+
+        if args.len() < cases + 2 {
+            return Ok(false);
+        }
+
+        let (scrutinee, motive) = (&args[args.len() - cases - 2], &args[args.len() - cases - 1]);
+
+        let ExprKind::Lambda { param: bound, body: motive, .. } = &motive.data else {
+            return Ok(false);
+        };
+
+        let mut branches = Vec::with_capacity(cases);
+
+        for ((constructor, fields), case) in eliminator.constructors.iter().zip(&args[args.len() - cases..]) {
+            let mut bindings = String::new();
+            let mut body = case;
+
+            for field in fields {
+                let ExprKind::Lambda { param, body: next, .. } = &body.data else {
+                    return Ok(false);
+                };
+
+                // Fields that were not bound are accessed as `name.field`.
+                if param.to_str() == field {
+                    bindings.push_str(&format!(" {}", field))
+                } else if param.to_str() != format!("{}.{}", bound, field) {
+                    bindings.push_str(&format!(" ({} = {})", field, param))
+                }
+
+                body = next;
+            }
+
+            branches.push(format!("{}{} => {}; ", constructor, bindings, self.with(body)));
+        }
+
+        write!(f, "(match {} {}", eliminator.typ, bound)?;
+
+        match &scrutinee.data {
+            ExprKind::Var { name } if name.to_str() == bound.to_str() => (),
+            _ => write!(f, " = {}", self.with(scrutinee))?,
+        }
+
+        if !matches!(motive.data, ExprKind::Hole { .. }) {
+            write!(f, " : {}", self.with(motive))?;
+        }
+
+        write!(f, " {{ {}}})", branches.concat())?;
+
+        Ok(true)
+    }
+}
+
+/// Reads a chain of `String.cons` with characters that ends in `String.nil`.
+fn try_resugar_to_string(expr: &Expr) -> Option<String> {
+    let mut string = String::new();
+    let mut current = expr;
+
+    loop {
+        match &current.data {
+            ExprKind::Ctr { name, args } if name.to_str() == "String.nil" && args.is_empty() => return Some(string),
+            ExprKind::Ctr { name, args } if name.to_str() == "String.cons" && args.len() == 2 => {
+                let ExprKind::NumU60 { numb } = args[0].data else {
+                    return None;
+                };
+                string.push(char::from_u32(numb.try_into().ok()?)?);
+                current = &args[1];
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Writes a string in the surface syntax. Only the quotes and the
+/// backslashes are escaped, the lexer reads every other character as
+/// it is.
+fn write_string(f: &mut Formatter, string: &str) -> Result<(), Error> {
+    write!(f, "\"")?;
+    for chr in string.chars() {
+        match chr {
+            '"' | '\\' => write!(f, "\\{}", chr)?,
+            _ => write!(f, "{}", chr)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Reads a chain of `List.cons` that ends in `List.nil`.
+fn try_resugar_to_list(expr: &Expr) -> Option<Vec<&Expr>> {
+    let mut elements = Vec::new();
+    let mut current = expr;
+
+    loop {
+        match &current.data {
+            ExprKind::Ctr { name, args } if name.to_str() == "List.nil" && args.len() == 1 => return Some(elements),
+            ExprKind::Ctr { name, args } if name.to_str() == "List.cons" && args.len() == 3 => {
+                elements.push(&args[1]);
+                current = &args[2];
+            }
+            _ => return None,
+        }
+    }
+}
+
+impl<'a> Display for Resugared<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        use ExprKind::*;
+        match &self.expr.data {
+            All { .. } => {
+                write!(f, "(")?;
+                self.fmt_pi_types(self.expr, f)?;
+                write!(f, ")")
+            }
+            Lambda { param, body, erased } => {
+                let tilde = if *erased { "~" } else { "" };
+                write!(f, "({}{} => {})", tilde, param, self.with(body))
+            }
+            Sub { name, redx, expr, .. } => write!(f, "(## {}/{} {})", name, redx, self.with(expr)),
+            App { fun, args } => write!(
+                f,
+                "({}{})",
+                self.with(fun),
+                args.iter()
+                    .map(|x| match x.erased {
+                        true => format!(" ~({})", self.with(&x.data)),
+                        false => format!(" {}", self.with(&x.data)),
+                    })
+                    .collect::<String>()
+            ),
+            Fun { name, args } | Ctr { name, args } => {
+                if let Some(res) = try_desugar_to_nat(name, args, 0) {
+                    return write!(f, "{res}n");
+                }
+
+                if let Some(res) = try_resugar_to_string(self.expr) {
+                    return write_string(f, &res);
+                }
+
+                if let Some(res) = try_resugar_to_list(self.expr) {
+                    let elements = res.iter().map(|x| self.with(x).to_string()).collect::<Vec<_>>();
+                    return write!(f, "[{}]", elements.join(" "));
+                }
+
+                match (name.to_str(), args.as_slice()) {
+                    ("Sigma.new", [_, _, fst, snd]) => {
+                        write!(f, "($ {} {})", self.with(fst), self.with(snd))
+                    }
+                    ("Sigma", [typ, body]) => match &body.data {
+                        Lambda { param, body, .. } => {
+                            write!(f, "([{} : {}] -> {})", param, self.with(typ), self.with(body))
+                        }
+                        _ => write!(f, "({}{})", name, self.spine(args)),
+                    },
+                    _ if self.fmt_match(name.to_str(), args, f)? => Ok(()),
+                    _ if args.is_empty() => write!(f, "{}", name),
+                    _ => write!(f, "({}{})", name, self.spine(args)),
+                }
+            }
+            Let { name, val, next } => write!(f, "(let {} = {}; {})", name, self.with(val), self.with(next)),
+            Ann { expr, typ } => write!(f, "({} :: {})", self.with(expr), self.with(typ)),
+            Binary { op, left, right } => write!(f, "({} {} {})", op, self.with(left), self.with(right)),
+            Typ | NumTypeU60 | NumTypeF60 | Str { .. } | NumU60 { .. } | NumF60 { .. } | Var { .. }
+            | Hole { .. } | Hlp(_) | Err => write!(f, "{}", self.expr),
+        }
+    }
+}
@@ -35,6 +35,9 @@ pub struct Attributes {
     pub kdl_erase: bool,
     pub kdl_name: Option<Ident>,
     pub kdl_state: Option<Ident>,
+    pub kdl_contract: Option<Ident>,
+    pub kdl_sign: bool,
+    pub strict: Vec<Ident>,
     pub trace: Option<bool>, // Some is enabled and some(true) is enabled with arguments
     pub keep: bool,
     pub partial: bool,